@@ -0,0 +1,131 @@
+use std::{
+    convert::Infallible,
+    future::{Future, Ready, ready},
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use axum_core::{
+    body::Body,
+    extract::{FromRequestParts, OptionalFromRequestParts},
+};
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use htmxtools::{
+    HtmxLayer, HxAutoVaryLayer, HxCacheControlLayer, HxTraceLayer,
+    request::{HtmxRequestExt, HxCurrentUrl, HxRequest, HxRequestKind, HxSource, HxTarget},
+};
+use http::{HeaderValue, Request, Response, Uri, request::Parts};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// A request as a handler sees it. Routers store their own extensions in every request (`axum` stores the original
+/// URI), so the extensions are already allocated.
+fn request() -> Request<()> {
+    Request::get("/items")
+        .extension(Uri::from_static("/items"))
+        .header("hx-request", "true")
+        .header("hx-current-url", "https://example.com/items?page=2")
+        .header("hx-target", "div#items")
+        .header("hx-source", "button#load-more")
+        .body(())
+        .unwrap()
+}
+
+/// Polls a future that never waits. Extractors and the benchmarked services complete immediately.
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = pin!(fut);
+    match fut.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future is not ready"),
+    }
+}
+
+/// Extracts the same headers as a handler using several htmx extractors would.
+async fn extract(parts: &mut Parts) {
+    let _ = <HxRequest as OptionalFromRequestParts<()>>::from_request_parts(parts, &()).await;
+    let _ = <HxCurrentUrl as FromRequestParts<()>>::from_request_parts(parts, &()).await;
+    let _ = <HxTarget as FromRequestParts<()>>::from_request_parts(parts, &()).await;
+    let _ = <HxSource as OptionalFromRequestParts<()>>::from_request_parts(parts, &()).await;
+    let _ = <HxRequestKind as FromRequestParts<()>>::from_request_parts(parts, &()).await;
+}
+
+/// Reads the same headers through [`HtmxRequestExt`], which decodes them on every read when the request has no
+/// cached context.
+fn extract_uncached(parts: &Parts) {
+    let _ = parts.hx_header::<HxRequest>();
+    let _ = parts.hx_header::<HxCurrentUrl>();
+    let _ = parts.hx_header::<HxTarget>();
+    let _ = parts.hx_header::<HxSource>();
+    let _ = parts.hx_request_kind();
+}
+
+/// A handler that reads the headers twice, like a custom extractor and the handler itself would.
+#[derive(Clone)]
+struct Handler {
+    cached: bool,
+}
+
+impl Service<Request<()>> for Handler {
+    type Response = Response<Body>;
+
+    type Error = Infallible;
+
+    type Future = Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<()>) -> Self::Future {
+        let (mut parts, _) = req.into_parts();
+        for _ in 0..2 {
+            if self.cached {
+                block_on(extract(&mut parts));
+            } else {
+                extract_uncached(&parts);
+            }
+        }
+
+        ready(Ok(Response::new(Body::empty())))
+    }
+}
+
+fn cache_control() -> HxCacheControlLayer {
+    HxCacheControlLayer::new()
+        .partial(HeaderValue::from_static("private, no-store"))
+        .page(HeaderValue::from_static("max-age=60"))
+}
+
+fn route(c: &mut Criterion) {
+    let mut group = c.benchmark_group("route");
+
+    // A route behind the tracing, auto vary and cache control middleware, which all classify the request. Without the
+    // context stored by `HtmxLayer`, every middleware and extractor decodes the headers again.
+    let mut uncached = HxTraceLayer::new()
+        .layer(HxAutoVaryLayer::new().layer(cache_control().layer(Handler { cached: false })));
+    group.bench_function("uncached", |b| {
+        b.iter_batched(
+            request,
+            |req| block_on(uncached.call(req)),
+            BatchSize::SmallInput,
+        )
+    });
+
+    let mut cached = HtmxLayer::new()
+        .tracing()
+        .auto_vary()
+        .cache_control(cache_control())
+        .layer(Handler { cached: true });
+    group.bench_function("cached", |b| {
+        b.iter_batched(
+            request,
+            |req| block_on(cached.call(req)),
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, route);
+criterion_main!(benches);
@@ -0,0 +1,293 @@
+//! Integration with `actix-web`.
+//!
+//! `actix-web` does not use the `http` 1.x header types, so this module implements its traits directly:
+//!
+//! - Every type in [`crate::request`] implements `FromRequest`. `Option<T>` resolves to `None` if the header is missing
+//!   (or invalid).
+//! - Every type in [`crate::response`] implements `Responder`, and [`HxResponseBuilderExt`] adds HTMX response headers
+//!   to an `HttpResponseBuilder`.
+//! - [`HxAutoVary`] is a middleware that adds the `Vary` header to responses based on the extracted HTMX headers, like
+//!   [`HxAutoVaryLayer`](crate::HxAutoVaryLayer) does for `tower`.
+//!
+//! ```rust,no_run
+//! use actix_web::{App, HttpResponse, Responder, web};
+//! use htmxtools::{
+//!     actix::{HxAutoVary, HxResponseBuilderExt},
+//!     request::HxRequest,
+//!     response::HxTrigger,
+//! };
+//!
+//! async fn index(hx_request: Option<HxRequest>) -> impl Responder {
+//!     if hx_request.is_some() {
+//!         HttpResponse::Ok()
+//!             .hx_header(HxTrigger::new().with_event("loaded".to_string()))
+//!             .body("<div>Fragment</div>")
+//!     } else {
+//!         HttpResponse::Ok().body("<html><body><div>Fragment</div></body></html>")
+//!     }
+//! }
+//!
+//! let app = App::new()
+//!     .wrap(HxAutoVary::new())
+//!     .route("/", web::get().to(index));
+//! ```
+use std::{
+    future::{Future, Ready, ready},
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+
+use actix_web::{
+    Error, FromRequest, HttpMessage, HttpRequest, HttpResponse, HttpResponseBuilder, Responder,
+    body::BoxBody,
+    dev::{Payload, Service, ServiceRequest, ServiceResponse, Transform, forward_ready},
+    error::{ErrorBadRequest, InternalError},
+    http::header::{self, HeaderMap, HeaderName, HeaderValue},
+};
+use headers_core::Header;
+use pin_project_lite::pin_project;
+
+use crate::{
+    auto_vary::SharedSet,
+    request::{
+        HxBoosted, HxCurrentUrl, HxHeader, HxHistoryRestoreRequest, HxRequest, HxRequestHeader,
+        HxRequestKind, HxRequestType, HxSource, HxTarget, hx_header::decode_untracked,
+    },
+    response::{
+        HxLocation, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl, HxReselect, HxReswap,
+        HxRetarget, HxTrigger, HxTriggerAfterSettle, HxTriggerAfterSwap,
+    },
+    util::compat,
+};
+
+/// Returns the HTMX request headers of a request as an `http` 1.x `HeaderMap`.
+fn hx_headers(req: &HttpRequest) -> http::HeaderMap {
+    compat::hx_header_map(
+        req.headers()
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_bytes())),
+    )
+}
+
+/// Records request headers for auto vary, if the request is served by [`HxAutoVary`].
+fn record(req: &HttpRequest, headers: &[HxRequestHeader]) {
+    if let Some(set) = req.extensions().get::<SharedSet>()
+        && let Ok(mut lock) = set.lock()
+    {
+        for header in headers {
+            lock.add(*header);
+        }
+    }
+}
+
+macro_rules! impl_from_request {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl FromRequest for $ty {
+                type Error = Error;
+
+                type Future = Ready<Result<Self, Self::Error>>;
+
+                fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+                    record(req, &[<$ty as HxHeader>::KIND]);
+
+                    let name = <$ty as Header>::name();
+                    ready(match decode_untracked(&hx_headers(req)) {
+                        Ok(Some(header)) => Ok(header),
+                        Ok(None) => Err(ErrorBadRequest(format!("Header of type `{name}` was missing"))),
+                        Err(_) => Err(ErrorBadRequest(format!("Header of type `{name}` is invalid"))),
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_from_request!(
+    HxBoosted,
+    HxCurrentUrl,
+    HxHistoryRestoreRequest,
+    HxRequest,
+    HxRequestType,
+    HxSource,
+    HxTarget,
+);
+
+impl FromRequest for HxRequestKind {
+    type Error = Error;
+
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let kind = HxRequestKind::from_headers(&hx_headers(req));
+        record(req, kind.consulted_headers());
+
+        ready(Ok(kind))
+    }
+}
+
+/// Appends the values of an HTMX response header to a header map.
+fn append<H: Header>(headers: &mut HeaderMap, header: &H) {
+    for value in compat::encode(header) {
+        if let Ok(value) = HeaderValue::from_bytes(value.as_bytes()) {
+            headers.append(HeaderName::from_static(H::name().as_str()), value);
+        }
+    }
+}
+
+/// Extension trait to add HTMX response headers to an `HttpResponseBuilder`.
+pub trait HxResponseBuilderExt {
+    /// Appends the values of an HTMX response header.
+    fn hx_header<H: Header>(&mut self, header: H) -> &mut Self;
+}
+
+impl HxResponseBuilderExt for HttpResponseBuilder {
+    fn hx_header<H: Header>(&mut self, header: H) -> &mut Self {
+        for value in compat::encode(&header) {
+            if let Ok(value) = HeaderValue::from_bytes(value.as_bytes()) {
+                self.append_header((H::name().as_str(), value));
+            }
+        }
+
+        self
+    }
+}
+
+macro_rules! impl_responder {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Responder for $ty {
+                type Body = BoxBody;
+
+                fn respond_to(self, _: &HttpRequest) -> HttpResponse<Self::Body> {
+                    let mut response = HttpResponse::Ok().finish();
+                    append(response.headers_mut(), &self);
+                    response
+                }
+            }
+        )*
+    };
+}
+
+impl_responder!(
+    HxLocation,
+    HxPushUrl,
+    HxRedirect,
+    HxRefresh,
+    HxReplaceUrl,
+    HxReselect,
+    HxReswap,
+    HxRetarget,
+    HxTrigger,
+    HxTriggerAfterSettle,
+    HxTriggerAfterSwap,
+);
+
+/// A middleware that automatically adds the `Vary` header to responses based on the extracted HTMX headers. This is
+/// the `actix-web` equivalent of [`HxAutoVaryLayer`](crate::HxAutoVaryLayer).
+///
+/// Errors returned by the inner service are still returned as errors. They are converted to their response, the `Vary`
+/// header is added, and the response is returned with [`InternalError::from_response`]: the status is kept, but the
+/// error can no longer be downcast to its original type.
+#[derive(Debug, Clone, Default)]
+pub struct HxAutoVary;
+
+impl HxAutoVary {
+    /// Creates a new `HxAutoVary`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for HxAutoVary
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Response = ServiceResponse<B>;
+
+    type Error = Error;
+
+    type Transform = HxAutoVaryMiddleware<S>;
+
+    type InitError = ();
+
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(HxAutoVaryMiddleware { service }))
+    }
+}
+
+/// The service created by [`HxAutoVary`].
+#[derive(Debug)]
+pub struct HxAutoVaryMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for HxAutoVaryMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Response = ServiceResponse<B>;
+
+    type Error = Error;
+
+    type Future = HxAutoVaryResponseFuture<S::Future>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let set = SharedSet::default();
+        req.extensions_mut().insert(set.clone());
+
+        HxAutoVaryResponseFuture {
+            fut: self.service.call(req),
+            set,
+        }
+    }
+}
+
+pin_project! {
+    /// Future returned by [`HxAutoVaryMiddleware`].
+    pub struct HxAutoVaryResponseFuture<F> {
+        #[pin]
+        fut: F,
+        set: SharedSet,
+    }
+}
+
+impl<F, B> Future for HxAutoVaryResponseFuture<F>
+where
+    F: Future<Output = Result<ServiceResponse<B>, Error>>,
+{
+    type Output = Result<ServiceResponse<B>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        Poll::Ready(match ready!(this.fut.poll(cx)) {
+            Ok(mut response) => {
+                add_vary(response.headers_mut(), this.set);
+                Ok(response)
+            }
+            Err(error) => {
+                let mut response = error.error_response();
+                add_vary(response.headers_mut(), this.set);
+                Err(InternalError::from_response(error, response).into())
+            }
+        })
+    }
+}
+
+fn add_vary(headers: &mut HeaderMap, set: &SharedSet) {
+    let Ok(lock) = set.lock() else {
+        return;
+    };
+
+    let existing = headers.get_all(header::VARY).map(HeaderValue::as_bytes);
+    for value in compat::vary_values(existing, &lock) {
+        if let Ok(value) = HeaderValue::from_bytes(value.as_bytes()) {
+            headers.append(header::VARY, value);
+        }
+    }
+}
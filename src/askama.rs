@@ -0,0 +1,163 @@
+//! Integration with `askama`, for rendering a single block of a template as an htmx fragment.
+//!
+//! - [`HxBlocks`] renders the blocks of a template by name. Implement it with the sub-templates askama generates for
+//!   the `blocks` listed in `#[template(...)]`.
+//! - [`HxTemplate`] renders the whole template for requests that expect a full page, and only the block named after
+//!   the id of the `hx-target` header, or an explicit block, for partial htmx requests.
+//!
+//! ```rust,ignore
+//! use askama::Template;
+//! use axum_core::response::IntoResponse;
+//! use htmxtools::{
+//!     askama::{HxBlocks, HxTemplate},
+//!     request::{HxRequestKind, HxTarget},
+//! };
+//!
+//! // `items.html` extends a layout and defines `{% block items %}<ul id="items">...</ul>{% endblock %}`.
+//! #[derive(Template)]
+//! #[template(path = "items.html", blocks = ["items"])]
+//! struct ItemsPage {
+//!     items: Vec<String>,
+//! }
+//!
+//! impl HxBlocks for ItemsPage {
+//!     fn render_block(&self, name: &str) -> Option<askama::Result<String>> {
+//!         match name {
+//!             "items" => Some(self.as_items().render()),
+//!             _ => None,
+//!         }
+//!     }
+//! }
+//!
+//! async fn items(kind: HxRequestKind, target: Option<HxTarget>) -> impl IntoResponse {
+//!     HxTemplate::new(ItemsPage { items: vec!["Milk".to_string()] }, kind, target)
+//! }
+//! ```
+use askama::{Error, Template};
+#[cfg(feature = "axum")]
+use axum_core::response::{IntoResponse, Response as AxumResponse};
+use headers_core::Header;
+#[cfg(feature = "axum")]
+use http::StatusCode;
+use http::{HeaderMap, HeaderValue, Response, header::CONTENT_TYPE};
+
+use crate::{
+    request::{HxRequestKind, HxTarget},
+    response::hx_page::add_vary,
+    util::header_list,
+};
+
+/// An askama template whose blocks can be rendered on their own.
+///
+/// Askama generates a sub-template for every block listed in `#[template(blocks = [...])]`, with an `as_<block>`
+/// method. `render_block` maps block names to these sub-templates. Block names are Rust identifiers, so
+/// [`HxTemplate`] also tries the id of the target with `-` replaced by `_`: an `hx-target` of `div#item-list` renders
+/// the block `item-list` or `item_list`.
+pub trait HxBlocks: Template {
+    /// Renders the block `name`, or returns `None` if the template has no such block.
+    fn render_block(&self, name: &str) -> Option<askama::Result<String>>;
+}
+
+/// Renders an askama template as a full page, or a single block of it as an htmx fragment.
+///
+/// The template is rendered whole for requests that expect a full page (see [`HxRequestKind::is_full_page`]). For
+/// partial htmx requests:
+///
+/// 1. If a block was set with [`HxTemplate::with_block`], that block is rendered. It is an error if the template has no
+///    such block.
+/// 2. Otherwise, the block named after the id of the `hx-target` header is rendered, if the template has one.
+/// 3. Otherwise, the whole template is rendered.
+///
+/// Like [`HxRender`](crate::response::HxRender), the response varies by the headers consulted to classify the request,
+/// and also by `hx-target` when the block is chosen from it.
+#[derive(Debug, Clone)]
+pub struct HxTemplate<T> {
+    template: T,
+    kind: HxRequestKind,
+    target: Option<HxTarget>,
+    block: Option<String>,
+}
+
+impl<T: HxBlocks> HxTemplate<T> {
+    /// Creates a new `HxTemplate` for a request of the given kind and `hx-target` header.
+    pub fn new(template: T, kind: HxRequestKind, target: Option<HxTarget>) -> Self {
+        Self {
+            template,
+            kind,
+            target,
+            block: None,
+        }
+    }
+
+    /// Renders `block` for partial htmx requests, instead of the block named after the target.
+    pub fn with_block(mut self, block: impl Into<String>) -> Self {
+        self.block = Some(block.into());
+        self
+    }
+
+    /// Renders the template or the block, without adding the `Vary` header.
+    pub fn render(&self) -> askama::Result<String> {
+        if self.kind.is_full_page() {
+            return self.template.render();
+        }
+
+        if let Some(block) = &self.block {
+            return self.template.render_block(block).unwrap_or_else(|| {
+                Err(Error::custom(format!(
+                    "the template has no block `{block}`"
+                )))
+            });
+        }
+
+        match self.target.as_ref().and_then(HxTarget::id) {
+            Some(id) => self
+                .template
+                .render_block(id)
+                .or_else(|| self.template.render_block(&id.replace('-', "_")))
+                .unwrap_or_else(|| self.template.render()),
+            None => self.template.render(),
+        }
+    }
+
+    /// Renders the template into an `http::Response` with the `Content-Type` and `Vary` headers.
+    pub fn into_http(self) -> askama::Result<Response<String>> {
+        let mut response = Response::new(self.render()?);
+        self.add_headers(response.headers_mut());
+        Ok(response)
+    }
+
+    fn add_headers(&self, headers: &mut HeaderMap) {
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("text/html; charset=utf-8"),
+        );
+
+        add_vary(headers, self.kind);
+        if !self.kind.is_full_page() && self.block.is_none() {
+            header_list::append_vary(headers, HxTarget::name());
+        }
+    }
+}
+
+/// Responds with `500 Internal Server Error` if the template fails to render, and logs the error with the `tracing`
+/// feature.
+#[cfg(feature = "axum")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+impl<T: HxBlocks> IntoResponse for HxTemplate<T> {
+    fn into_response(self) -> AxumResponse {
+        match self.render() {
+            Ok(body) => {
+                let mut response = body.into_response();
+                self.add_headers(response.headers_mut());
+                response
+            }
+            #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+            Err(error) => {
+                #[cfg(feature = "tracing")]
+                tracing::error!(%error, block = ?self.block, "failed to render the template");
+
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
@@ -0,0 +1,408 @@
+//! Typed values of htmx attributes, to render in templates.
+//!
+//! - [`HxSwap`] is the value of `hx-swap`: a swap strategy with modifiers.
+//! - [`HxTriggerSpec`] is the value of `hx-trigger`: events with filters and modifiers.
+//! - [`HxAttributes`] is a set of `hx-*` attributes, rendered with their values escaped.
+//!
+//! They implement `Display`, which renders the value as htmx parses it. Selectors are plain strings, and the response
+//! header types that share their syntax with an attribute, such as [`HxReswap`] and [`HxPushUrl`], can be used as
+//! attribute values too.
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use htmxtools::{
+//!     attribute::{HxAttributes, HxSwap, HxTriggerSpec},
+//!     response::{HxPushUrl, HxReswap},
+//! };
+//!
+//! let swap = HxSwap::new(HxReswap::OuterHtml).transition().settle(Duration::from_millis(100));
+//! assert_eq!(swap.to_string(), "outerHTML transition:true settle:100ms");
+//!
+//! let trigger = HxTriggerSpec::new("keyup").changed().delay(Duration::from_millis(500)).or("search");
+//! assert_eq!(trigger.to_string(), "keyup changed delay:500ms, search");
+//!
+//! let attributes = HxAttributes::new()
+//!     .get("/search?q=a&b")
+//!     .target("#results")
+//!     .swap(HxReswap::InnerHtml)
+//!     .push_url(HxPushUrl::False);
+//! assert_eq!(
+//!     attributes.to_string(),
+//!     r##" hx-get="/search?q=a&amp;b" hx-target="#results" hx-swap="innerHTML" hx-push-url="false""##,
+//! );
+//! ```
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+use crate::response::{HxPushUrl, HxReplaceUrl, HxReswap};
+
+/// The value of the `hx-swap` attribute: a swap strategy followed by modifiers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HxSwap {
+    strategy: HxReswap,
+    modifiers: Vec<String>,
+}
+
+impl HxSwap {
+    /// Creates a new `HxSwap` with the given strategy and no modifiers.
+    pub fn new(strategy: HxReswap) -> Self {
+        Self {
+            strategy,
+            modifiers: Vec::new(),
+        }
+    }
+
+    /// Returns the swap strategy.
+    pub fn strategy(&self) -> HxReswap {
+        self.strategy
+    }
+
+    /// Uses the View Transition API for the swap (`transition:true`).
+    pub fn transition(self) -> Self {
+        self.modifier("transition:true")
+    }
+
+    /// Waits `delay` between receiving the response and swapping the content (`swap:`).
+    pub fn swap(self, delay: Duration) -> Self {
+        self.modifier(format!("swap:{}", time(delay)))
+    }
+
+    /// Waits `delay` between the swap and the settle (`settle:`).
+    pub fn settle(self, delay: Duration) -> Self {
+        self.modifier(format!("settle:{}", time(delay)))
+    }
+
+    /// Ignores any `<title>` in the response (`ignoreTitle:true`).
+    pub fn ignore_title(self) -> Self {
+        self.modifier("ignoreTitle:true")
+    }
+
+    /// Scrolls the target to the top after the swap (`scroll:top`).
+    pub fn scroll_top(self) -> Self {
+        self.modifier("scroll:top")
+    }
+
+    /// Scrolls the target to the bottom after the swap (`scroll:bottom`).
+    pub fn scroll_bottom(self) -> Self {
+        self.modifier("scroll:bottom")
+    }
+
+    /// Scrolls the top of the target into view after the swap (`show:top`).
+    pub fn show_top(self) -> Self {
+        self.modifier("show:top")
+    }
+
+    /// Scrolls the bottom of the target into view after the swap (`show:bottom`).
+    pub fn show_bottom(self) -> Self {
+        self.modifier("show:bottom")
+    }
+
+    /// Sets whether the focused element is scrolled into view after the swap (`focus-scroll:`).
+    pub fn focus_scroll(self, focus_scroll: bool) -> Self {
+        self.modifier(format!("focus-scroll:{focus_scroll}"))
+    }
+
+    /// Appends a modifier as is, such as `scroll:#another-div:top`.
+    pub fn modifier(mut self, modifier: impl Into<String>) -> Self {
+        self.modifiers.push(modifier.into());
+        self
+    }
+}
+
+impl From<HxReswap> for HxSwap {
+    fn from(strategy: HxReswap) -> Self {
+        Self::new(strategy)
+    }
+}
+
+impl Display for HxSwap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.strategy.as_str())?;
+        for modifier in &self.modifiers {
+            write!(f, " {modifier}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The value of the `hx-trigger` attribute: one or more events, each with an optional filter and modifiers.
+///
+/// Filters and modifiers apply to the last event added with [`HxTriggerSpec::new`], [`HxTriggerSpec::every`] or
+/// [`HxTriggerSpec::or`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HxTriggerSpec {
+    events: Vec<TriggerEvent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct TriggerEvent {
+    event: String,
+    filter: Option<String>,
+    modifiers: Vec<String>,
+}
+
+impl HxTriggerSpec {
+    /// Creates a new `HxTriggerSpec` for a single event.
+    pub fn new(event: impl Into<String>) -> Self {
+        Self {
+            events: vec![TriggerEvent::new(event.into())],
+        }
+    }
+
+    /// Creates a new `HxTriggerSpec` that polls every `interval` (`every 2s`).
+    pub fn every(interval: Duration) -> Self {
+        Self::new(format!("every {}", time(interval)))
+    }
+
+    /// Adds another event that also triggers the request.
+    pub fn or(mut self, event: impl Into<String>) -> Self {
+        self.events.push(TriggerEvent::new(event.into()));
+        self
+    }
+
+    /// Only triggers if the JavaScript expression evaluates to `true` (`click[ctrlKey]`).
+    pub fn filter(mut self, expression: impl Into<String>) -> Self {
+        self.last().filter = Some(expression.into());
+        self
+    }
+
+    /// Only triggers once (`once`).
+    pub fn once(self) -> Self {
+        self.modifier("once")
+    }
+
+    /// Only triggers if the value of the element has changed (`changed`).
+    pub fn changed(self) -> Self {
+        self.modifier("changed")
+    }
+
+    /// Waits `delay` before triggering, restarting the delay on every event (`delay:`).
+    pub fn delay(self, delay: Duration) -> Self {
+        self.modifier(format!("delay:{}", time(delay)))
+    }
+
+    /// Triggers at most once per `interval` (`throttle:`).
+    pub fn throttle(self, interval: Duration) -> Self {
+        self.modifier(format!("throttle:{}", time(interval)))
+    }
+
+    /// Listens for the event on the elements matching `selector` instead (`from:`).
+    pub fn from_element(self, selector: impl Display) -> Self {
+        self.modifier(format!("from:{selector}"))
+    }
+
+    /// Only triggers if the target of the event matches `selector` (`target:`).
+    pub fn target(self, selector: impl Display) -> Self {
+        self.modifier(format!("target:{selector}"))
+    }
+
+    /// Stops the event from propagating to parent elements (`consume`).
+    pub fn consume(self) -> Self {
+        self.modifier("consume")
+    }
+
+    /// Appends a modifier as is, such as `queue:last`.
+    pub fn modifier(mut self, modifier: impl Into<String>) -> Self {
+        self.last().modifiers.push(modifier.into());
+        self
+    }
+
+    fn last(&mut self) -> &mut TriggerEvent {
+        self.events
+            .last_mut()
+            .expect("a trigger spec has at least one event")
+    }
+}
+
+impl TriggerEvent {
+    fn new(event: String) -> Self {
+        Self {
+            event,
+            filter: None,
+            modifiers: Vec::new(),
+        }
+    }
+}
+
+impl Display for HxTriggerSpec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, event) in self.events.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+
+            f.write_str(&event.event)?;
+            if let Some(filter) = &event.filter {
+                write!(f, "[{filter}]")?;
+            }
+            for modifier in &event.modifiers {
+                write!(f, " {modifier}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A set of `hx-*` attributes.
+///
+/// `Display` renders every attribute preceded by a space, as in ` hx-get="/items" hx-target="#list"`, with the values
+/// escaped for double-quoted HTML attributes. Setting an attribute twice replaces its value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct HxAttributes {
+    attributes: Vec<(Cow<'static, str>, String)>,
+}
+
+impl HxAttributes {
+    /// Creates an empty set of attributes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets `hx-get`.
+    pub fn get(self, url: impl Display) -> Self {
+        self.set("hx-get", url.to_string())
+    }
+
+    /// Sets `hx-post`.
+    pub fn post(self, url: impl Display) -> Self {
+        self.set("hx-post", url.to_string())
+    }
+
+    /// Sets `hx-put`.
+    pub fn put(self, url: impl Display) -> Self {
+        self.set("hx-put", url.to_string())
+    }
+
+    /// Sets `hx-patch`.
+    pub fn patch(self, url: impl Display) -> Self {
+        self.set("hx-patch", url.to_string())
+    }
+
+    /// Sets `hx-delete`.
+    pub fn delete(self, url: impl Display) -> Self {
+        self.set("hx-delete", url.to_string())
+    }
+
+    /// Sets `hx-target` to a selector.
+    pub fn target(self, selector: impl Display) -> Self {
+        self.set("hx-target", selector.to_string())
+    }
+
+    /// Sets `hx-select` to a selector.
+    pub fn select(self, selector: impl Display) -> Self {
+        self.set("hx-select", selector.to_string())
+    }
+
+    /// Sets `hx-swap`.
+    pub fn swap(self, swap: impl Into<HxSwap>) -> Self {
+        self.set("hx-swap", swap.into().to_string())
+    }
+
+    /// Sets `hx-trigger`.
+    pub fn trigger(self, trigger: HxTriggerSpec) -> Self {
+        self.set("hx-trigger", trigger.to_string())
+    }
+
+    /// Sets `hx-push-url`.
+    pub fn push_url(self, push_url: HxPushUrl) -> Self {
+        self.set("hx-push-url", push_url_value(&push_url).into_owned())
+    }
+
+    /// Sets `hx-replace-url`.
+    pub fn replace_url(self, replace_url: HxReplaceUrl) -> Self {
+        self.set(
+            "hx-replace-url",
+            replace_url_value(&replace_url).into_owned(),
+        )
+    }
+
+    /// Sets an attribute by name, such as `hx-confirm` or `hx-vals`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `name` is empty or contains characters other than ASCII letters, digits, `-`, `_`, `:` and `.`.
+    pub fn attribute(self, name: impl Into<String>, value: impl Display) -> Self {
+        let name = name.into();
+        assert!(
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')),
+            "`{name}` is not a valid attribute name"
+        );
+        self.set(name, value.to_string())
+    }
+
+    /// Returns the attributes and their unescaped values, in the order they were first set.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.attributes
+            .iter()
+            .map(|(name, value)| (name.as_ref(), value.as_str()))
+    }
+
+    fn set(mut self, name: impl Into<Cow<'static, str>>, value: String) -> Self {
+        let name = name.into();
+        match self.attributes.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, v)) => *v = value,
+            None => self.attributes.push((name, value)),
+        }
+        self
+    }
+}
+
+impl Display for HxAttributes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (name, value) in self.iter() {
+            write!(f, " {name}=\"")?;
+            escape(value, f)?;
+            f.write_str("\"")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the value of `hx-push-url` for an `HxPushUrl`.
+pub(crate) fn push_url_value(push_url: &HxPushUrl) -> Cow<'static, str> {
+    match push_url {
+        HxPushUrl::True => Cow::Borrowed("true"),
+        HxPushUrl::False => Cow::Borrowed("false"),
+        HxPushUrl::Uri(uri) => Cow::Owned(uri.to_string()),
+    }
+}
+
+/// Returns the value of `hx-replace-url` for an `HxReplaceUrl`.
+pub(crate) fn replace_url_value(replace_url: &HxReplaceUrl) -> Cow<'static, str> {
+    match replace_url {
+        HxReplaceUrl::True => Cow::Borrowed("true"),
+        HxReplaceUrl::False => Cow::Borrowed("false"),
+        HxReplaceUrl::Uri(uri) => Cow::Owned(uri.to_string()),
+    }
+}
+
+/// Escapes `value` for a double-quoted HTML attribute.
+fn escape(value: &str, f: &mut impl fmt::Write) -> fmt::Result {
+    for c in value.chars() {
+        match c {
+            '&' => f.write_str("&amp;")?,
+            '"' => f.write_str("&quot;")?,
+            '<' => f.write_str("&lt;")?,
+            '>' => f.write_str("&gt;")?,
+            c => f.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Formats a duration in the htmx time syntax, in seconds if it is a whole number of seconds and in milliseconds
+/// otherwise.
+fn time(duration: Duration) -> String {
+    if duration.subsec_millis() == 0 && duration.as_secs() > 0 {
+        format!("{}s", duration.as_secs())
+    } else {
+        format!("{}ms", duration.as_millis())
+    }
+}
@@ -1,3 +1,7 @@
+#[cfg(feature = "tracing")]
+mod debug;
+mod hx_headers;
+
 use std::{
     future::Future,
     pin::Pin,
@@ -5,103 +9,110 @@ use std::{
     task::{Context, Poll, ready},
 };
 
-#[cfg(feature = "axum")]
-use http::request::Parts;
-use http::{HeaderValue, Request, Response, header::VARY};
+use http::{Extensions, HeaderMap, HeaderName, Request, Response, request::Parts};
 use http_body::Body;
 use pin_project_lite::pin_project;
 use tower_layer::Layer;
 use tower_service::Service;
 
-const HX_BOOSTED: HeaderValue = HeaderValue::from_static("hx-boosted");
-const HX_CURRENT_URL: HeaderValue = HeaderValue::from_static("hx-current-url");
-const HX_HISTORY_RESTORE_REQUEST: HeaderValue =
-    HeaderValue::from_static("hx-history-restore-request");
-const HX_REQUEST: HeaderValue = HeaderValue::from_static("hx-request");
-const HX_REQUEST_TYPE: HeaderValue = HeaderValue::from_static("hx-request-type");
-const HX_SOURCE: HeaderValue = HeaderValue::from_static("hx-source");
-const HX_TARGET: HeaderValue = HeaderValue::from_static("hx-target");
+#[cfg(feature = "tracing")]
+pub use self::debug::{HxAutoVaryDebug, HxAutoVaryDebugLayer, HxAutoVaryDebugResponseFuture};
+pub use self::hx_headers::HxHeaders;
+use crate::request::HxRequestHeader;
+use crate::util::header_list;
+
+pub(crate) type SharedSet = Arc<Mutex<HxRequestHeaderSet>>;
 
-#[derive(Debug, Clone, Copy)]
-pub struct HxRequestHeaderSet(u8);
+/// The set of request headers that a response varies by.
+///
+/// The set is recorded per request by [`HxAutoVary`] and written to the `Vary` header of the response. Use
+/// [`HxAutoVaryExt`] to add headers to the set or to read it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HxRequestHeaderSet {
+    builtin: u8,
+    others: Vec<HeaderName>,
+}
 
 impl HxRequestHeaderSet {
+    /// Creates a new empty set.
     pub fn new() -> Self {
-        Self(0)
+        Self::default()
     }
 
-    #[cfg(feature = "axum")]
+    /// Adds a built-in HTMX request header to the set.
     pub fn add(&mut self, header: HxRequestHeader) {
-        self.0 |= header.mask();
+        self.builtin |= header.mask();
     }
 
-    pub fn add_to_response<B>(&self, response: &mut Response<B>) {
-        for hx_request_header in HxRequestHeader::iter() {
-            if self.0 & hx_request_header.mask() != 0 {
-                hx_request_header.add_to_response(response);
-            }
+    /// Adds an arbitrary request header to the set.
+    pub fn add_name(&mut self, name: HeaderName) {
+        if let Some(header) = HxRequestHeader::from_name(&name) {
+            self.add(header);
+        } else if !self.others.contains(&name) {
+            self.others.push(name);
         }
     }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum HxRequestHeader {
-    Boosted,
-    CurrentUrl,
-    HistoryRestoreRequest,
-    Request,
-    RequestType,
-    Source,
-    Target,
-}
+    /// Returns `true` if the set contains the given built-in HTMX request header.
+    pub fn contains(&self, header: HxRequestHeader) -> bool {
+        self.builtin & header.mask() != 0
+    }
 
-impl HxRequestHeader {
-    pub fn iter() -> impl IntoIterator<Item = Self> {
-        [
-            HxRequestHeader::Boosted,
-            HxRequestHeader::CurrentUrl,
-            HxRequestHeader::HistoryRestoreRequest,
-            HxRequestHeader::Request,
-            HxRequestHeader::RequestType,
-            HxRequestHeader::Source,
-            HxRequestHeader::Target,
-        ]
-    }
-
-    pub fn mask(&self) -> u8 {
-        match self {
-            HxRequestHeader::Boosted => 1 << 0,
-            HxRequestHeader::CurrentUrl => 1 << 1,
-            HxRequestHeader::HistoryRestoreRequest => 1 << 2,
-            HxRequestHeader::Request => 1 << 3,
-            HxRequestHeader::RequestType => 1 << 4,
-            HxRequestHeader::Source => 1 << 5,
-            HxRequestHeader::Target => 1 << 6,
+    /// Returns `true` if the set contains a request header with the given name.
+    pub fn contains_name(&self, name: &HeaderName) -> bool {
+        match HxRequestHeader::from_name(name) {
+            Some(header) => self.contains(header),
+            None => self.others.contains(name),
         }
     }
 
-    pub fn value(self) -> HeaderValue {
-        match self {
-            HxRequestHeader::Boosted => HX_BOOSTED,
-            HxRequestHeader::CurrentUrl => HX_CURRENT_URL,
-            HxRequestHeader::HistoryRestoreRequest => HX_HISTORY_RESTORE_REQUEST,
-            HxRequestHeader::Request => HX_REQUEST,
-            HxRequestHeader::RequestType => HX_REQUEST_TYPE,
-            HxRequestHeader::Source => HX_SOURCE,
-            HxRequestHeader::Target => HX_TARGET,
-        }
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.builtin == 0 && self.others.is_empty()
     }
 
-    pub fn add_to_response<B>(self, response: &mut Response<B>) {
-        response.headers_mut().append(VARY, self.value());
+    /// Returns an iterator over the names of the request headers in the set.
+    pub fn iter(&self) -> impl Iterator<Item = &HeaderName> {
+        let builtin = self.builtin;
+
+        HxRequestHeader::iter()
+            .filter(move |header| builtin & header.mask() != 0)
+            .map(|header| -> &HeaderName { header.name() })
+            .chain(self.others.iter())
+    }
+
+    pub(crate) fn add_to_response<B>(&self, response: &mut Response<B>) {
+        self.add_to_headers(response.headers_mut());
+    }
+
+    pub(crate) fn add_to_headers(&self, headers: &mut HeaderMap) {
+        for name in self.iter() {
+            header_list::append_vary(headers, name);
+        }
     }
 }
 
 /// A layer that automatically adds the `Vary` header to responses based on the extracted HTMX headers. Read more about
 /// caching in HTMX [here](https://htmx.org/docs/#caching).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct HxAutoVaryLayer;
 
+impl HxAutoVaryLayer {
+    /// Creates a new `HxAutoVaryLayer`.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Creates a layer that adds the `Vary` header like this layer, and logs a warning for responses that vary by HTMX
+    /// headers they never declared. Handlers run twice for the requests it checks, see [`HxAutoVaryDebugLayer`] for
+    /// more details.
+    #[cfg(feature = "tracing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+    pub fn debug() -> HxAutoVaryDebugLayer {
+        HxAutoVaryDebugLayer
+    }
+}
+
 impl<S> Layer<S> for HxAutoVaryLayer {
     type Service = HxAutoVary<S>;
 
@@ -134,11 +145,13 @@ where
     }
 
     fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
-        let set = Arc::new(Mutex::new(HxRequestHeaderSet::new()));
+        let set = SharedSet::default();
         req.extensions_mut().insert(set.clone());
 
-        let fut = self.inner.call(req);
-        HxAutoVaryResponseFuture { fut, set }
+        HxAutoVaryResponseFuture {
+            fut: self.inner.call(req),
+            set,
+        }
     }
 }
 
@@ -147,7 +160,7 @@ pin_project! {
     pub struct HxAutoVaryResponseFuture<F> {
         #[pin]
         fut: F,
-        set: Arc<Mutex<HxRequestHeaderSet>>,
+        set: SharedSet,
     }
 }
 
@@ -162,28 +175,89 @@ where
         let this = self.project();
         let mut response = ready!(this.fut.poll(cx));
 
-        if let Ok(ref mut response) = response {
-            if let Ok(lock) = this.set.lock() {
-                lock.add_to_response(response);
-            }
+        if let Ok(ref mut response) = response
+            && let Ok(lock) = this.set.lock()
+        {
+            lock.add_to_response(response);
         }
 
         Poll::Ready(response)
     }
 }
 
-#[cfg(feature = "axum")]
-pub trait HxAutoVaryAdd {
-    fn hx_auto_vary_add(self, header: HxRequestHeader);
+/// Extension trait to record the request headers a response varies by.
+///
+/// This is how extractors and middleware take part in [`HxAutoVary`]: every header added here is written to the `Vary`
+/// header of the response. All methods are no-ops (or return `None`) when the request is not served by
+/// [`HxAutoVary`].
+///
+/// ```rust
+/// use htmxtools::{HxAutoVaryExt, request::HxRequestHeader};
+/// use http::{Request, header::ACCEPT_LANGUAGE};
+///
+/// fn record(request: &Request<()>) {
+///     request.hx_auto_vary_add(HxRequestHeader::Request);
+///     request.hx_auto_vary_add_name(ACCEPT_LANGUAGE);
+/// }
+/// ```
+pub trait HxAutoVaryExt {
+    /// Adds a built-in HTMX request header to the auto-vary set.
+    fn hx_auto_vary_add(&self, header: HxRequestHeader);
+
+    /// Adds an arbitrary request header to the auto-vary set.
+    fn hx_auto_vary_add_name(&self, name: HeaderName);
+
+    /// Returns a snapshot of the current auto-vary set.
+    fn hx_auto_vary_headers(&self) -> Option<HxRequestHeaderSet>;
 }
 
-#[cfg(feature = "axum")]
-impl HxAutoVaryAdd for &mut Parts {
-    fn hx_auto_vary_add(self, header: HxRequestHeader) {
-        if let Some(set) = self.extensions.get_mut::<Arc<Mutex<HxRequestHeaderSet>>>() {
-            if let Ok(mut lock) = set.lock() {
-                lock.add(header);
-            }
+impl HxAutoVaryExt for Extensions {
+    fn hx_auto_vary_add(&self, header: HxRequestHeader) {
+        if let Some(set) = self.get::<SharedSet>()
+            && let Ok(mut lock) = set.lock()
+        {
+            lock.add(header);
+        }
+    }
+
+    fn hx_auto_vary_add_name(&self, name: HeaderName) {
+        if let Some(set) = self.get::<SharedSet>()
+            && let Ok(mut lock) = set.lock()
+        {
+            lock.add_name(name);
         }
     }
+
+    fn hx_auto_vary_headers(&self) -> Option<HxRequestHeaderSet> {
+        self.get::<SharedSet>()
+            .and_then(|set| set.lock().ok().map(|lock| lock.clone()))
+    }
+}
+
+impl HxAutoVaryExt for Parts {
+    fn hx_auto_vary_add(&self, header: HxRequestHeader) {
+        self.extensions.hx_auto_vary_add(header);
+    }
+
+    fn hx_auto_vary_add_name(&self, name: HeaderName) {
+        self.extensions.hx_auto_vary_add_name(name);
+    }
+
+    fn hx_auto_vary_headers(&self) -> Option<HxRequestHeaderSet> {
+        self.extensions.hx_auto_vary_headers()
+    }
+}
+
+impl<B> HxAutoVaryExt for Request<B> {
+    fn hx_auto_vary_add(&self, header: HxRequestHeader) {
+        self.extensions().hx_auto_vary_add(header);
+    }
+
+    fn hx_auto_vary_add_name(&self, name: HeaderName) {
+        self.extensions().hx_auto_vary_add_name(name);
+    }
+
+    fn hx_auto_vary_headers(&self) -> Option<HxRequestHeaderSet> {
+        self.extensions().hx_auto_vary_headers()
+    }
 }
@@ -0,0 +1,294 @@
+use std::{
+    fmt,
+    future::{Future, poll_fn},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use http::{
+    HeaderMap, HeaderName, Method, Request, Response,
+    header::{CONTENT_LENGTH, CONTENT_TYPE, VARY},
+};
+use http_body::Body;
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use super::{HxAutoVary, HxAutoVaryLayer, SharedSet};
+use crate::request::HxContext;
+use crate::util::{
+    body::{is_bufferable, to_bytes},
+    header_list,
+};
+
+/// The largest response body compared in debug mode.
+const MAX_COMPARED_SIZE: u64 = 1024 * 1024;
+
+/// A layer that adds the `Vary` header like [`HxAutoVaryLayer`](crate::HxAutoVaryLayer), and logs a warning for
+/// responses that vary by HTMX headers they never declared. Handlers run twice for the requests it checks. Created by
+/// [`HxAutoVaryLayer::debug`](crate::HxAutoVaryLayer::debug).
+///
+/// When the response to a `GET` or `HEAD` request does not list some of the `hx-*` headers of the request in `Vary`,
+/// the request is sent again without these headers. If the status, the `Content-Type` or the body of the second
+/// response differs, the response depends on headers missing from `Vary`, usually because the handler read them
+/// directly from the `HeaderMap` instead of using the extractors, [`HxHeaders`](crate::HxHeaders) or
+/// [`HxAutoVaryExt`](crate::HxAutoVaryExt), and a warning naming the headers is logged.
+///
+/// Requests are handled twice, so this layer is meant for development only, and responses that are not deterministic
+/// (for example because they contain a CSRF token) are reported as well. Bodies of unknown size, bodies larger than
+/// 1 MiB and event streams are not compared. If the body of the first response cannot be read, an error is logged and
+/// the response is sent with its status and headers, but with an empty body.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HxAutoVaryDebugLayer;
+
+impl<S> Layer<S> for HxAutoVaryDebugLayer {
+    type Service = HxAutoVaryDebug<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HxAutoVaryDebug {
+            inner: HxAutoVaryLayer.layer(inner),
+        }
+    }
+}
+
+/// A service that adds the `Vary` header like [`HxAutoVary`], and logs a warning for responses that vary by HTMX
+/// headers they never declared. See [`HxAutoVaryDebugLayer`] for more details.
+#[derive(Debug, Clone)]
+pub struct HxAutoVaryDebug<S> {
+    inner: HxAutoVary<S>,
+}
+
+impl<ReqBody, ResBody, S> Service<Request<ReqBody>> for HxAutoVaryDebug<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: 'static,
+    ReqBody: Default + Send + 'static,
+    ResBody: Body + From<Bytes> + Send + 'static,
+    ResBody::Data: Send,
+{
+    type Response = S::Response;
+
+    type Error = S::Error;
+
+    type Future = HxAutoVaryDebugResponseFuture<
+        <HxAutoVary<S> as Service<Request<ReqBody>>>::Future,
+        ResBody,
+        S::Error,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        if !matches!(*req.method(), Method::GET | Method::HEAD)
+            || !req.headers().keys().any(is_htmx_header)
+        {
+            return HxAutoVaryDebugResponseFuture {
+                kind: Kind::Direct {
+                    fut: self.inner.call(req),
+                },
+            };
+        }
+
+        let replay = Replay::new(&req);
+        let service = self.inner.clone();
+
+        let fut = self.inner.call(req);
+        HxAutoVaryDebugResponseFuture {
+            kind: Kind::Compare {
+                fut: Box::pin(async move {
+                    let response = fut.await?;
+                    Ok(replay.compare(response, service).await)
+                }),
+            },
+        }
+    }
+}
+
+pin_project! {
+    /// Future returned by [`HxAutoVaryDebug`].
+    pub struct HxAutoVaryDebugResponseFuture<F, B, E> {
+        #[pin]
+        kind: Kind<F, B, E>,
+    }
+}
+
+pin_project! {
+    /// Requests that are not compared are not boxed.
+    #[project = KindProj]
+    enum Kind<F, B, E> {
+        Direct {
+            #[pin]
+            fut: F,
+        },
+        Compare {
+            fut: Pin<Box<dyn Future<Output = Result<Response<B>, E>> + Send>>,
+        },
+    }
+}
+
+impl<F, B, E> fmt::Debug for HxAutoVaryDebugResponseFuture<F, B, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HxAutoVaryDebugResponseFuture")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, B, E> Future for HxAutoVaryDebugResponseFuture<F, B, E>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+{
+    type Output = Result<Response<B>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().kind.project() {
+            KindProj::Direct { fut } => fut.poll(cx),
+            KindProj::Compare { fut } => fut.as_mut().poll(cx),
+        }
+    }
+}
+
+/// The parts of a request needed to send it again.
+struct Replay {
+    parts: http::request::Parts,
+}
+
+impl Replay {
+    fn new<B>(req: &Request<B>) -> Self {
+        let mut builder = Request::builder()
+            .method(req.method().clone())
+            .uri(req.uri().clone())
+            .version(req.version());
+
+        if let Some(headers) = builder.headers_mut() {
+            headers.clone_from(req.headers());
+        }
+        if let Some(extensions) = builder.extensions_mut() {
+            extensions.clone_from(req.extensions());
+            // Recorded headers and decoded values belong to the original request.
+            extensions.remove::<SharedSet>();
+            extensions.remove::<HxContext>();
+        }
+
+        let (parts, ()) = builder
+            .body(())
+            .expect("parts of a valid request")
+            .into_parts();
+        Self { parts }
+    }
+
+    async fn compare<S, ReqBody, ResBody>(
+        mut self,
+        response: Response<ResBody>,
+        mut service: S,
+    ) -> Response<ResBody>
+    where
+        S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+        ReqBody: Default,
+        ResBody: Body + From<Bytes>,
+    {
+        let undeclared = undeclared(&self.parts.headers, response.headers());
+        if undeclared.is_empty() {
+            return response;
+        }
+
+        let (mut parts, body) = response.into_parts();
+        if !is_bufferable(&parts.headers, &body, MAX_COMPARED_SIZE) {
+            return Response::from_parts(parts, body);
+        }
+
+        let bytes = match to_bytes(body).await {
+            Ok(bytes) => bytes,
+            Err(_) => {
+                tracing::error!(
+                    request = %self.request(),
+                    "failed to read the response body to compare it"
+                );
+                parts.headers.remove(CONTENT_LENGTH);
+                return Response::from_parts(parts, ResBody::from(Bytes::new()));
+            }
+        };
+
+        let request = self.request();
+        for name in &undeclared {
+            self.parts.headers.remove(name);
+        }
+
+        let same = match send(&mut service, self.parts).await {
+            Some(other) => {
+                other.status() == parts.status
+                    && other.headers().get(CONTENT_TYPE) == parts.headers.get(CONTENT_TYPE)
+                    && same_body(other, &bytes).await
+            }
+            None => true,
+        };
+
+        if !same {
+            let headers = undeclared
+                .iter()
+                .map(HeaderName::as_str)
+                .collect::<Vec<_>>()
+                .join(", ");
+            tracing::warn!(
+                request,
+                headers,
+                "response varies by htmx headers missing from `Vary`; read htmx headers using the extractors, \
+                 `HxHeaders` or `HxAutoVaryExt` so that they are added to `Vary`"
+            );
+        }
+
+        Response::from_parts(parts, ResBody::from(bytes))
+    }
+
+    fn request(&self) -> String {
+        format!("{} {}", self.parts.method, self.parts.uri)
+    }
+}
+
+/// Returns the names of the `hx-*` request headers not listed in the `Vary` header of the response.
+fn undeclared(request: &HeaderMap, response: &HeaderMap) -> Vec<HeaderName> {
+    let vary = header_list::elements(response, VARY)
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>();
+
+    if vary.iter().any(|name| name == "*") {
+        return Vec::new();
+    }
+
+    request
+        .keys()
+        .filter(|name| is_htmx_header(name) && !vary.iter().any(|vary| vary == name.as_str()))
+        .cloned()
+        .collect()
+}
+
+fn is_htmx_header(name: &HeaderName) -> bool {
+    name.as_str().starts_with("hx-")
+}
+
+/// Sends the request to the service, returning `None` if the service fails.
+async fn send<S, ReqBody, ResBody>(
+    service: &mut S,
+    parts: http::request::Parts,
+) -> Option<Response<ResBody>>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    ReqBody: Default,
+{
+    poll_fn(|cx| service.poll_ready(cx)).await.ok()?;
+    service
+        .call(Request::from_parts(parts, ReqBody::default()))
+        .await
+        .ok()
+}
+
+/// Returns `true` if the body of the response is the same as `bytes`.
+async fn same_body<B: Body>(response: Response<B>, bytes: &Bytes) -> bool {
+    let (parts, body) = response.into_parts();
+
+    is_bufferable(&parts.headers, &body, MAX_COMPARED_SIZE)
+        && to_bytes(body).await.is_ok_and(|other| other == *bytes)
+}
@@ -0,0 +1,115 @@
+#[cfg(feature = "axum")]
+use std::convert::Infallible;
+
+#[cfg(feature = "axum")]
+use axum_core::extract::FromRequestParts;
+use http::{
+    Extensions, HeaderMap, HeaderName, HeaderValue, Request, header::GetAll, request::Parts,
+};
+
+use super::SharedSet;
+
+/// The `hx-*` request headers, recording every header looked up in them for [`HxAutoVary`](crate::HxAutoVary).
+///
+/// Use this in place of `HeaderMap` in handlers and middleware that read HTMX headers directly. Looking up any name
+/// starting with `hx-` (whether the header is present or not) adds it to the `Vary` header of the response. Only the
+/// `hx-*` headers are copied from the request, so other headers are never found here: read them from the `HeaderMap`
+/// as usual.
+///
+/// ```rust,no_run
+/// use axum_core::response::IntoResponse;
+/// use htmxtools::HxHeaders;
+///
+/// async fn handler(headers: HxHeaders) -> impl IntoResponse {
+///     if headers.contains_key("hx-request") {
+///         "<div>Fragment</div>"
+///     } else {
+///         "<html><body><div>Fragment</div></body></html>"
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HxHeaders {
+    headers: HeaderMap,
+    set: Option<SharedSet>,
+}
+
+impl HxHeaders {
+    /// Creates a new `HxHeaders` from request parts.
+    pub fn from_parts(parts: &Parts) -> Self {
+        Self::new(&parts.headers, &parts.extensions)
+    }
+
+    /// Creates a new `HxHeaders` from a request.
+    pub fn from_request<B>(request: &Request<B>) -> Self {
+        Self::new(request.headers(), request.extensions())
+    }
+
+    fn new(headers: &HeaderMap, extensions: &Extensions) -> Self {
+        let mut hx_headers = HeaderMap::new();
+        for (name, value) in headers {
+            if name.as_str().starts_with("hx-") {
+                hx_headers.append(name, value.clone());
+            }
+        }
+
+        Self {
+            headers: hx_headers,
+            set: extensions.get::<SharedSet>().cloned(),
+        }
+    }
+
+    /// Returns a reference to the first value of the header with the given name.
+    pub fn get<K: AsRef<str>>(&self, key: K) -> Option<&HeaderValue> {
+        let name = self.track(key.as_ref())?;
+        self.headers.get(name)
+    }
+
+    /// Returns a view of all values of the header with the given name.
+    pub fn get_all<K: AsRef<str>>(&self, key: K) -> Option<GetAll<'_, HeaderValue>> {
+        let name = self.track(key.as_ref())?;
+        Some(self.headers.get_all(name))
+    }
+
+    /// Returns `true` if the headers contain a header with the given name.
+    pub fn contains_key<K: AsRef<str>>(&self, key: K) -> bool {
+        self.track(key.as_ref())
+            .is_some_and(|name| self.headers.contains_key(name))
+    }
+
+    /// Returns the `hx-*` headers. Lookups in the returned map are not recorded.
+    pub fn as_untracked(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Consumes `self` and returns the `hx-*` headers.
+    pub fn into_untracked(self) -> HeaderMap {
+        self.headers
+    }
+
+    fn track(&self, key: &str) -> Option<HeaderName> {
+        let name = HeaderName::from_bytes(key.as_bytes()).ok()?;
+
+        if name.as_str().starts_with("hx-")
+            && let Some(set) = &self.set
+            && let Ok(mut lock) = set.lock()
+        {
+            lock.add_name(name.clone());
+        }
+
+        Some(name)
+    }
+}
+
+#[cfg(feature = "axum")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+impl<S> FromRequestParts<S> for HxHeaders
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_parts(parts))
+    }
+}
@@ -0,0 +1,512 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    future::{Future, Ready, ready},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use http::{
+    HeaderMap, HeaderName, HeaderValue, Method, Request, Response, StatusCode, Uri, Version,
+    header::{AUTHORIZATION, CACHE_CONTROL, COOKIE, SET_COOKIE, VARY},
+};
+use http_body::Body;
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{
+    HxAutoVaryExt,
+    auto_vary::SharedSet,
+    util::{
+        body::{BoxError, is_bufferable, read_error, to_bytes},
+        header_list,
+    },
+};
+
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+const DEFAULT_MAX_ENTRIES: usize = 1024;
+const DEFAULT_MAX_ENTRY_SIZE: usize = 1024 * 1024;
+
+/// A layer that caches responses to `GET` requests in memory, keyed by the HTMX variant of the request.
+///
+/// Responses are keyed on the method, the URI and the values of the request headers they vary by: the headers recorded
+/// by [`HxAutoVary`](crate::HxAutoVary) and the headers listed in the `Vary` header set by the handler. This follows
+/// the same rules a CDN follows, so the partial and the full rendering of the same URL are cached separately.
+///
+/// Only `200 OK` responses are cached. Responses with `Cache-Control: no-store` or `Cache-Control: private`, with a
+/// `Set-Cookie` header or with `Vary: *` are never cached. Cached responses are served with the status, version,
+/// headers and body they were stored with (response extensions are not kept), and the headers they vary by are recorded
+/// again so that `HxAutoVaryLayer` (which must wrap this layer) adds them to the `Vary` header.
+///
+/// Like any shared cache, this layer must not serve a response personalized for one user to another one
+/// ([RFC 9111, section 3.5](https://www.rfc-editor.org/rfc/rfc9111#section-3.5)):
+///
+/// - Requests with an `Authorization` header are only served from and stored in the cache if the response has
+///   `Cache-Control: public` or `Cache-Control: s-maxage`.
+/// - Requests with a `Cookie` header are only served from and stored in the cache if the response varies by `Cookie`,
+///   either because it is listed in the `Vary` header set by the handler or because it was recorded with
+///   [`HxAutoVaryExt`]. Each combination of cookies then gets its own entry.
+///
+/// The response body is buffered in memory to be stored, so bodies of unknown size (such as streams), bodies larger
+/// than [`HxCacheLayer::max_entry_size`] and event streams are never cached. If the response body cannot be read, the
+/// error is logged (when the `tracing` feature is enabled) and an empty `500 Internal Server Error` response is sent
+/// instead.
+///
+/// Use [`HxCacheLayer::store`] to get a handle to the underlying [`HxCacheStore`] and invalidate entries.
+#[derive(Debug, Clone, Default)]
+pub struct HxCacheLayer {
+    config: Config,
+    store: HxCacheStore,
+}
+
+impl HxCacheLayer {
+    /// Creates a new `HxCacheLayer` with a TTL of 60 seconds, at most 1024 entries and at most 1 MiB per entry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time to live of cached entries.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.config.ttl = ttl;
+        self
+    }
+
+    /// Sets the maximum number of cached entries. The oldest entries are evicted first.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.config.max_entries = max_entries;
+        self
+    }
+
+    /// Sets the maximum size of the body of a cached entry. Larger responses are not cached.
+    pub fn max_entry_size(mut self, max_entry_size: usize) -> Self {
+        self.config.max_entry_size = max_entry_size;
+        self
+    }
+
+    /// Returns a handle to the store used by this layer.
+    pub fn store(&self) -> HxCacheStore {
+        self.store.clone()
+    }
+}
+
+impl<S> Layer<S> for HxCacheLayer {
+    type Service = HxCache<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HxCache {
+            inner,
+            config: self.config,
+            store: self.store.clone(),
+        }
+    }
+}
+
+/// A handle to the responses cached by [`HxCacheLayer`].
+#[derive(Debug, Clone, Default)]
+pub struct HxCacheStore {
+    inner: Arc<Mutex<Store>>,
+}
+
+impl HxCacheStore {
+    /// Removes all cached responses for URIs whose path is in the given prefix.
+    ///
+    /// The prefix is matched on whole path segments: `/items` invalidates `/items` and `/items/1`, but not
+    /// `/itemsfoo`.
+    pub fn invalidate_prefix(&self, prefix: &str) {
+        if let Ok(mut store) = self.inner.lock() {
+            let keys: Vec<_> = store
+                .resources
+                .keys()
+                .filter(|(_, uri)| has_prefix(uri.path(), prefix))
+                .cloned()
+                .collect();
+
+            for key in keys {
+                store.retain(&key, |_| false);
+            }
+        }
+    }
+
+    /// Removes all cached responses.
+    pub fn clear(&self) {
+        if let Ok(mut store) = self.inner.lock() {
+            store.resources.clear();
+            store.order.clear();
+        }
+    }
+
+    /// Returns the number of cached responses.
+    pub fn len(&self) -> usize {
+        self.inner.lock().map_or(0, |store| store.order.len())
+    }
+
+    /// Returns `true` if there are no cached responses.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, key: &(Method, Uri), headers: &HeaderMap) -> Option<Entry> {
+        let mut store = self.inner.lock().ok()?;
+        let now = Instant::now();
+
+        store.retain(key, |entry| entry.expires_at > now);
+        store
+            .resources
+            .get(key)?
+            .iter()
+            .find(|entry| entry.matches(headers))
+            .filter(|entry| entry.is_usable_for(headers))
+            .cloned()
+    }
+
+    fn insert(&self, config: Config, key: (Method, Uri), headers: &HeaderMap, entry: Entry) {
+        let Ok(mut store) = self.inner.lock() else {
+            return;
+        };
+
+        if config.max_entries == 0 {
+            return;
+        }
+
+        store.retain(&key, |other| !other.matches(headers));
+        store.push(key, entry);
+        store.evict(config.max_entries);
+    }
+}
+
+/// Returns `true` if `path` is `prefix` or one of its sub-paths.
+fn has_prefix(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/') || prefix.ends_with('/'))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Config {
+    ttl: Duration,
+    max_entries: usize,
+    max_entry_size: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            ttl: DEFAULT_TTL,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            max_entry_size: DEFAULT_MAX_ENTRY_SIZE,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Store {
+    resources: HashMap<(Method, Uri), Vec<Entry>>,
+    /// The keys of the cached entries by [`Entry::id`], in insertion order.
+    order: BTreeMap<u64, (Method, Uri)>,
+    next_id: u64,
+}
+
+impl Store {
+    fn push(&mut self, key: (Method, Uri), mut entry: Entry) {
+        entry.id = self.next_id;
+        self.next_id += 1;
+
+        self.order.insert(entry.id, key.clone());
+        self.resources.entry(key).or_default().push(entry);
+    }
+
+    /// Keeps the entries for `key` for which `keep` returns `true`.
+    fn retain(&mut self, key: &(Method, Uri), mut keep: impl FnMut(&Entry) -> bool) {
+        let Some(entries) = self.resources.get_mut(key) else {
+            return;
+        };
+
+        let order = &mut self.order;
+        entries.retain(|entry| {
+            let kept = keep(entry);
+            if !kept {
+                order.remove(&entry.id);
+            }
+            kept
+        });
+
+        if entries.is_empty() {
+            self.resources.remove(key);
+        }
+    }
+
+    /// Removes the oldest entries until at most `max_entries` are left. Entries share the TTL of the layer, so expired
+    /// entries are the oldest ones and are removed first.
+    fn evict(&mut self, max_entries: usize) {
+        while self.order.len() > max_entries {
+            let Some((id, key)) = self.order.pop_first() else {
+                break;
+            };
+
+            self.retain(&key, |entry| entry.id != id);
+        }
+    }
+}
+
+/// The values of the request headers an entry varies by, in the same order as [`Entry::vary`].
+type Variant = Vec<Vec<HeaderValue>>;
+
+fn variant(vary: &[HeaderName], headers: &HeaderMap) -> Variant {
+    vary.iter()
+        .map(|name| headers.get_all(name).iter().cloned().collect())
+        .collect()
+}
+
+/// A cached response, selected for requests whose headers it varies by have the same values as in [`Entry::variant`].
+#[derive(Debug, Clone)]
+struct Entry {
+    /// Set when the entry is stored, see [`Store::order`].
+    id: u64,
+    vary: Vec<HeaderName>,
+    variant: Variant,
+    status: StatusCode,
+    version: Version,
+    headers: HeaderMap,
+    body: Bytes,
+    expires_at: Instant,
+}
+
+impl Entry {
+    fn matches(&self, headers: &HeaderMap) -> bool {
+        variant(&self.vary, headers) == self.variant
+    }
+
+    /// Returns `true` if the entry may be served to a request with these headers, see [`HxCacheLayer`].
+    fn is_usable_for(&self, headers: &HeaderMap) -> bool {
+        (!headers.contains_key(COOKIE) || self.vary.contains(&COOKIE))
+            && (!headers.contains_key(AUTHORIZATION) || is_public(&self.headers))
+    }
+}
+
+/// A service that caches responses to `GET` requests in memory. See [`HxCacheLayer`] for more details.
+#[derive(Debug, Clone)]
+pub struct HxCache<S> {
+    inner: S,
+    config: Config,
+    store: HxCacheStore,
+}
+
+impl<ReqBody, ResBody, S> Service<Request<ReqBody>> for HxCache<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+    S::Error: 'static,
+    ResBody: Body + From<Bytes> + Send + 'static,
+    ResBody::Data: Send,
+    ResBody::Error: Into<BoxError>,
+{
+    type Response = S::Response;
+
+    type Error = S::Error;
+
+    type Future = HxCacheResponseFuture<S::Future, ResBody, S::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        if req.method() != Method::GET {
+            return HxCacheResponseFuture {
+                kind: Kind::Direct {
+                    fut: self.inner.call(req),
+                },
+            };
+        }
+
+        let key = (req.method().clone(), req.uri().clone());
+
+        if let Some(entry) = self.store.get(&key, req.headers()) {
+            for name in entry.vary {
+                req.hx_auto_vary_add_name(name);
+            }
+
+            let mut response = Response::new(ResBody::from(entry.body));
+            *response.status_mut() = entry.status;
+            *response.version_mut() = entry.version;
+            *response.headers_mut() = entry.headers;
+
+            return HxCacheResponseFuture {
+                kind: Kind::Cached {
+                    fut: ready(Ok(response)),
+                },
+            };
+        }
+
+        let headers = req.headers().clone();
+        let set = req.extensions().get::<SharedSet>().cloned();
+        let store = self.store.clone();
+        let config = self.config;
+
+        let fut = self.inner.call(req);
+        HxCacheResponseFuture {
+            kind: Kind::Store {
+                fut: Box::pin(async move {
+                    let response = fut.await?;
+                    Ok(store_response(response, &store, config, key, &headers, set).await)
+                }),
+            },
+        }
+    }
+}
+
+pin_project! {
+    /// Future returned by [`HxCache`].
+    pub struct HxCacheResponseFuture<F, B, E> {
+        #[pin]
+        kind: Kind<F, B, E>,
+    }
+}
+
+pin_project! {
+    /// Only responses that may be stored are boxed.
+    #[project = KindProj]
+    enum Kind<F, B, E> {
+        Direct {
+            #[pin]
+            fut: F,
+        },
+        Cached {
+            fut: Ready<Result<Response<B>, E>>,
+        },
+        Store {
+            fut: Pin<Box<dyn Future<Output = Result<Response<B>, E>> + Send>>,
+        },
+    }
+}
+
+impl<F, B, E> fmt::Debug for HxCacheResponseFuture<F, B, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HxCacheResponseFuture")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, B, E> Future for HxCacheResponseFuture<F, B, E>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+{
+    type Output = Result<Response<B>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().kind.project() {
+            KindProj::Direct { fut } => fut.poll(cx),
+            KindProj::Cached { fut } => Pin::new(fut).poll(cx),
+            KindProj::Store { fut } => fut.as_mut().poll(cx),
+        }
+    }
+}
+
+async fn store_response<B>(
+    response: Response<B>,
+    store: &HxCacheStore,
+    config: Config,
+    key: (Method, Uri),
+    headers: &HeaderMap,
+    set: Option<SharedSet>,
+) -> Response<B>
+where
+    B: Body + From<Bytes>,
+    B::Error: Into<BoxError>,
+{
+    let Some(vary) = cacheable_vary(&response, headers, set) else {
+        return response;
+    };
+
+    let max_entry_size = u64::try_from(config.max_entry_size).unwrap_or(u64::MAX);
+    if !is_bufferable(response.headers(), response.body(), max_entry_size) {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+
+    let bytes = match to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(error) => return read_error(error, "failed to read the response body to cache it"),
+    };
+
+    if bytes.len() <= config.max_entry_size {
+        let now = Instant::now();
+
+        store.insert(
+            config,
+            key,
+            headers,
+            Entry {
+                id: 0,
+                variant: variant(&vary, headers),
+                vary,
+                status: parts.status,
+                version: parts.version,
+                headers: parts.headers.clone(),
+                body: bytes.clone(),
+                expires_at: now + config.ttl,
+            },
+        );
+    }
+
+    Response::from_parts(parts, B::from(bytes))
+}
+
+/// Returns the names of the request headers the response varies by, or `None` if the response must not be cached.
+fn cacheable_vary<B>(
+    response: &Response<B>,
+    request: &HeaderMap,
+    set: Option<SharedSet>,
+) -> Option<Vec<HeaderName>> {
+    if response.status() != StatusCode::OK || response.headers().contains_key(SET_COOKIE) {
+        return None;
+    }
+
+    if header_list::elements(response.headers(), CACHE_CONTROL).any(|directive| {
+        directive.eq_ignore_ascii_case("no-store") || directive.eq_ignore_ascii_case("private")
+    }) {
+        return None;
+    }
+
+    if request.contains_key(AUTHORIZATION) && !is_public(response.headers()) {
+        return None;
+    }
+
+    let mut vary = Vec::new();
+
+    for name in header_list::elements(response.headers(), VARY) {
+        // `HeaderName` accepts `*`, which means that the response varies by more than the request headers.
+        if name == "*" {
+            return None;
+        }
+
+        vary.push(HeaderName::from_bytes(name.as_bytes()).ok()?);
+    }
+
+    if let Some(set) = set
+        && let Ok(lock) = set.lock()
+    {
+        vary.extend(lock.iter().cloned());
+    }
+
+    vary.sort_unstable_by(|a, b| a.as_str().cmp(b.as_str()));
+    vary.dedup();
+
+    if request.contains_key(COOKIE) && !vary.contains(&COOKIE) {
+        return None;
+    }
+
+    Some(vary)
+}
+
+/// Returns `true` if a response to a request with an `Authorization` header may be stored and reused by a shared cache.
+fn is_public(headers: &HeaderMap) -> bool {
+    header_list::elements(headers, CACHE_CONTROL).any(|directive| {
+        let name = directive.split('=').next().unwrap_or_default().trim();
+        name.eq_ignore_ascii_case("public") || name.eq_ignore_ascii_case("s-maxage")
+    })
+}
@@ -0,0 +1,227 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+
+use http::{Extensions, HeaderValue, Request, Response, header::CACHE_CONTROL};
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+#[cfg(feature = "auto-vary")]
+use crate::auto_vary::SharedSet;
+use crate::request::HxRequestKind;
+
+/// A layer that adds the `Cache-Control` header to responses based on the [`HxRequestKind`] of the request. Read more
+/// about caching in HTMX [here](https://htmx.org/docs/#caching).
+///
+/// No policy is configured by default. Responses that already have a `Cache-Control` header are left unchanged unless
+/// [`HxCacheControlLayer::override_existing`] is enabled.
+///
+/// Unless the `Cache-Control` header set by the handler is kept, the headers used to classify the request are recorded
+/// for [`HxAutoVary`](crate::HxAutoVary) (when the `auto-vary` feature is enabled), so `HxAutoVaryLayer` must wrap this
+/// layer for the `Vary` header to be correct.
+#[derive(Debug, Clone, Default)]
+pub struct HxCacheControlLayer {
+    policy: HxCacheControlPolicy,
+}
+
+#[derive(Debug, Clone, Default)]
+struct HxCacheControlPolicy {
+    page: Option<HeaderValue>,
+    history_restore: Option<HeaderValue>,
+    boosted: Option<HeaderValue>,
+    full: Option<HeaderValue>,
+    partial: Option<HeaderValue>,
+    override_existing: bool,
+}
+
+impl HxCacheControlPolicy {
+    fn is_empty(&self) -> bool {
+        self.page.is_none()
+            && self.history_restore.is_none()
+            && self.boosted.is_none()
+            && self.full.is_none()
+            && self.partial.is_none()
+    }
+
+    fn get(&self, kind: HxRequestKind) -> Option<&HeaderValue> {
+        match kind {
+            HxRequestKind::Page => self.page.as_ref(),
+            HxRequestKind::HistoryRestore => self.history_restore.as_ref(),
+            HxRequestKind::Boosted => self.boosted.as_ref(),
+            HxRequestKind::Full => self.full.as_ref(),
+            HxRequestKind::Partial => self.partial.as_ref(),
+        }
+    }
+
+    fn get_mut(&mut self, kind: HxRequestKind) -> &mut Option<HeaderValue> {
+        match kind {
+            HxRequestKind::Page => &mut self.page,
+            HxRequestKind::HistoryRestore => &mut self.history_restore,
+            HxRequestKind::Boosted => &mut self.boosted,
+            HxRequestKind::Full => &mut self.full,
+            HxRequestKind::Partial => &mut self.partial,
+        }
+    }
+}
+
+impl HxCacheControlLayer {
+    /// Creates a new `HxCacheControlLayer` without any policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `Cache-Control` value for requests of the given kind.
+    pub fn policy(mut self, kind: HxRequestKind, value: HeaderValue) -> Self {
+        *self.policy.get_mut(kind) = Some(value);
+        self
+    }
+
+    /// Sets the `Cache-Control` value for regular (non-htmx) requests.
+    pub fn page(self, value: HeaderValue) -> Self {
+        self.policy(HxRequestKind::Page, value)
+    }
+
+    /// Sets the `Cache-Control` value for history restore requests.
+    pub fn history_restore(self, value: HeaderValue) -> Self {
+        self.policy(HxRequestKind::HistoryRestore, value)
+    }
+
+    /// Sets the `Cache-Control` value for boosted requests.
+    pub fn boosted(self, value: HeaderValue) -> Self {
+        self.policy(HxRequestKind::Boosted, value)
+    }
+
+    /// Sets the `Cache-Control` value for htmx requests for full page content.
+    pub fn full(self, value: HeaderValue) -> Self {
+        self.policy(HxRequestKind::Full, value)
+    }
+
+    /// Sets the `Cache-Control` value for htmx requests for partial page updates.
+    pub fn partial(self, value: HeaderValue) -> Self {
+        self.policy(HxRequestKind::Partial, value)
+    }
+
+    /// Sets whether `Cache-Control` headers set by the handler are overridden. Defaults to `false`.
+    pub fn override_existing(mut self, override_existing: bool) -> Self {
+        self.policy.override_existing = override_existing;
+        self
+    }
+}
+
+impl<S> Layer<S> for HxCacheControlLayer {
+    type Service = HxCacheControl<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HxCacheControl {
+            inner,
+            policy: self.policy.clone(),
+        }
+    }
+}
+
+/// A service that adds the `Cache-Control` header to responses based on the [`HxRequestKind`] of the request. See
+/// [`HxCacheControlLayer`] for more details.
+#[derive(Debug, Clone)]
+pub struct HxCacheControl<S> {
+    inner: S,
+    policy: HxCacheControlPolicy,
+}
+
+impl<ReqBody, ResBody, S> Service<Request<ReqBody>> for HxCacheControl<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+{
+    type Response = S::Response;
+
+    type Error = S::Error;
+
+    type Future = HxCacheControlResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let kind = (!self.policy.is_empty())
+            .then(|| HxRequestKind::cached(req.headers(), req.extensions()));
+        let recorder = Recorder::new(req.extensions());
+
+        let fut = self.inner.call(req);
+        HxCacheControlResponseFuture {
+            fut,
+            value: kind.and_then(|kind| self.policy.get(kind).cloned()),
+            kind,
+            override_existing: self.policy.override_existing,
+            recorder,
+        }
+    }
+}
+
+pin_project! {
+    /// Future returned by [`HxCacheControl`].
+    pub struct HxCacheControlResponseFuture<F> {
+        #[pin]
+        fut: F,
+        value: Option<HeaderValue>,
+        kind: Option<HxRequestKind>,
+        override_existing: bool,
+        recorder: Recorder,
+    }
+}
+
+impl<F, B, E> Future for HxCacheControlResponseFuture<F>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+{
+    type Output = Result<Response<B>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let mut response = ready!(this.fut.poll(cx));
+
+        // The response varies by the request kind unless the handler's `Cache-Control` header is kept.
+        if let Ok(ref mut response) = response
+            && let Some(kind) = this.kind.take()
+            && (*this.override_existing || !response.headers().contains_key(CACHE_CONTROL))
+        {
+            if let Some(value) = this.value.take() {
+                response.headers_mut().insert(CACHE_CONTROL, value);
+            }
+            this.recorder.record(kind);
+        }
+
+        Poll::Ready(response)
+    }
+}
+
+/// Records the headers consulted to classify the request for auto vary, once the response is known to depend on them.
+#[derive(Debug)]
+struct Recorder {
+    #[cfg(feature = "auto-vary")]
+    set: Option<SharedSet>,
+}
+
+impl Recorder {
+    #[cfg_attr(not(feature = "auto-vary"), allow(unused_variables))]
+    fn new(extensions: &Extensions) -> Self {
+        Self {
+            #[cfg(feature = "auto-vary")]
+            set: extensions.get::<SharedSet>().cloned(),
+        }
+    }
+
+    #[cfg_attr(not(feature = "auto-vary"), allow(unused_variables))]
+    fn record(&self, kind: HxRequestKind) {
+        #[cfg(feature = "auto-vary")]
+        if let Some(set) = &self.set
+            && let Ok(mut lock) = set.lock()
+        {
+            for header in kind.consulted_headers() {
+                lock.add(*header);
+            }
+        }
+    }
+}
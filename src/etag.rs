@@ -0,0 +1,290 @@
+use std::{
+    fmt,
+    future::Future,
+    hash::Hasher,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use http::{
+    HeaderMap, HeaderValue, Method, Request, Response, StatusCode,
+    header::{CONTENT_LENGTH, ETAG, IF_NONE_MATCH, VARY},
+    response::Parts,
+};
+use http_body::Body;
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{
+    auto_vary::SharedSet,
+    util::{
+        body::{BoxError, is_bufferable, read_error, to_bytes},
+        header_list,
+    },
+};
+
+/// The largest response body an `ETag` is computed for.
+const MAX_BODY_SIZE: u64 = 1024 * 1024;
+
+/// A layer that adds a weak `ETag` header to responses of `GET` and `HEAD` requests and answers matching conditional
+/// requests (`If-None-Match`) with `304 Not Modified`.
+///
+/// The `ETag` is computed from the response body together with the values of the request headers the response
+/// varies by: the headers recorded by [`HxAutoVary`](crate::HxAutoVary) and the headers listed in the `Vary` header set
+/// by the handler. This way, the partial and the full rendering of the same URL never share an `ETag`.
+///
+/// `HxAutoVaryLayer` must wrap this layer. Only `200 OK` responses are considered, and an `ETag` set by the handler is
+/// used as is. The response body is buffered in memory to compute the `ETag`, so no `ETag` is computed for bodies of
+/// unknown size (such as streams), bodies larger than 1 MiB and event streams. Responses to `HEAD` requests get the
+/// same `ETag` as the matching `GET` response as long as their body has not been removed yet, which is the case for
+/// layers added with `Router::layer` in `axum`.
+///
+/// If the response body cannot be read, the error is logged (when the `tracing` feature is enabled) and an empty
+/// `500 Internal Server Error` response is sent instead.
+#[derive(Debug, Clone, Default)]
+pub struct HxETagLayer;
+
+impl HxETagLayer {
+    /// Creates a new `HxETagLayer`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for HxETagLayer {
+    type Service = HxETag<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HxETag { inner }
+    }
+}
+
+/// A service that adds a weak `ETag` header to responses and answers matching conditional requests. See
+/// [`HxETagLayer`] for more details.
+#[derive(Debug, Clone)]
+pub struct HxETag<S> {
+    inner: S,
+}
+
+impl<ReqBody, ResBody, S> Service<Request<ReqBody>> for HxETag<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+    S::Error: 'static,
+    ResBody: Body + From<Bytes> + Send + 'static,
+    ResBody::Data: Send,
+    ResBody::Error: Into<BoxError>,
+{
+    type Response = S::Response;
+
+    type Error = S::Error;
+
+    type Future = HxETagResponseFuture<S::Future, ResBody, S::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        if !matches!(*req.method(), Method::GET | Method::HEAD) {
+            return HxETagResponseFuture {
+                kind: Kind::Direct {
+                    fut: self.inner.call(req),
+                },
+            };
+        }
+
+        let head = req.method() == Method::HEAD;
+        let headers = req.headers().clone();
+        let set = req.extensions().get::<SharedSet>().cloned();
+
+        let fut = self.inner.call(req);
+        HxETagResponseFuture {
+            kind: Kind::Respond {
+                fut: Box::pin(async move {
+                    let response = fut.await?;
+                    Ok(respond(response, head, &headers, set).await)
+                }),
+            },
+        }
+    }
+}
+
+pin_project! {
+    /// Future returned by [`HxETag`].
+    pub struct HxETagResponseFuture<F, B, E> {
+        #[pin]
+        kind: Kind<F, B, E>,
+    }
+}
+
+pin_project! {
+    /// Responses to requests other than `GET` and `HEAD` are not boxed.
+    #[project = KindProj]
+    enum Kind<F, B, E> {
+        Direct {
+            #[pin]
+            fut: F,
+        },
+        Respond {
+            fut: Pin<Box<dyn Future<Output = Result<Response<B>, E>> + Send>>,
+        },
+    }
+}
+
+impl<F, B, E> fmt::Debug for HxETagResponseFuture<F, B, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HxETagResponseFuture")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, B, E> Future for HxETagResponseFuture<F, B, E>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+{
+    type Output = Result<Response<B>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().kind.project() {
+            KindProj::Direct { fut } => fut.poll(cx),
+            KindProj::Respond { fut } => fut.as_mut().poll(cx),
+        }
+    }
+}
+
+async fn respond<B>(
+    response: Response<B>,
+    head: bool,
+    headers: &HeaderMap,
+    set: Option<SharedSet>,
+) -> Response<B>
+where
+    B: Body + From<Bytes>,
+    B::Error: Into<BoxError>,
+{
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+
+    let (etag, body) = match parts.headers.get(ETAG) {
+        Some(etag) => (etag.clone(), body),
+        None => {
+            if !is_bufferable(&parts.headers, &body, MAX_BODY_SIZE)
+                || (head && is_stripped(&parts, &body))
+            {
+                return Response::from_parts(parts, body);
+            }
+
+            let bytes = match to_bytes(body).await {
+                Ok(bytes) => bytes,
+                Err(error) => {
+                    return read_error(
+                        error,
+                        "failed to read the response body to compute the `ETag`",
+                    );
+                }
+            };
+
+            let etag = compute_etag(&bytes, headers, &parts.headers, set);
+            parts.headers.insert(ETAG, etag.clone());
+
+            (etag, B::from(bytes))
+        }
+    };
+
+    if if_none_match(headers, &etag) {
+        parts.status = StatusCode::NOT_MODIFIED;
+        parts.headers.remove(CONTENT_LENGTH);
+
+        return Response::from_parts(parts, B::from(Bytes::new()));
+    }
+
+    Response::from_parts(parts, body)
+}
+
+/// Returns `true` if the body of a `HEAD` response has already been removed, so that it cannot be used to compute the
+/// `ETag` of the matching `GET` response.
+fn is_stripped<B: Body>(parts: &Parts, body: &B) -> bool {
+    body.size_hint().exact() == Some(0)
+        && parts
+            .headers
+            .get(CONTENT_LENGTH)
+            .is_some_and(|length| length != "0")
+}
+
+fn compute_etag(
+    body: &[u8],
+    request_headers: &HeaderMap,
+    response_headers: &HeaderMap,
+    set: Option<SharedSet>,
+) -> HeaderValue {
+    let mut names = header_list::elements(response_headers, VARY)
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>();
+
+    if let Some(set) = set
+        && let Ok(lock) = set.lock()
+    {
+        names.extend(lock.iter().map(|name| name.as_str().to_owned()));
+    }
+
+    names.sort_unstable();
+    names.dedup();
+
+    let mut hasher = Fnv1a::default();
+    hasher.write(body);
+
+    for name in names {
+        hasher.write(&[0]);
+        hasher.write(name.as_bytes());
+
+        for value in request_headers.get_all(name.as_str()) {
+            hasher.write(&[1]);
+            hasher.write(value.as_bytes());
+        }
+    }
+
+    HeaderValue::try_from(format!("W/\"{:016x}\"", hasher.finish()))
+        .expect("hex digest is a valid header value")
+}
+
+fn if_none_match(headers: &HeaderMap, etag: &HeaderValue) -> bool {
+    let Ok(etag) = etag.to_str() else {
+        return false;
+    };
+    let etag = opaque_tag(etag);
+
+    header_list::elements(headers, IF_NONE_MATCH).any(|tag| tag == "*" || opaque_tag(tag) == etag)
+}
+
+/// Returns the opaque part of an entity tag, as required by the weak comparison function.
+fn opaque_tag(tag: &str) -> &str {
+    tag.strip_prefix("W/").unwrap_or(tag)
+}
+
+/// 64-bit FNV-1a hasher. Used instead of `DefaultHasher` so that entity tags are stable across builds.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use std::task::{Context, Poll};
+
+use http::Request;
+use tower_layer::{Identity, Layer};
+use tower_service::Service;
+
+#[cfg(feature = "cache-control")]
+use crate::HxCacheControlLayer;
+#[cfg(feature = "cache")]
+use crate::HxCacheLayer;
+#[cfg(feature = "etag")]
+use crate::HxETagLayer;
+#[cfg(feature = "tracing")]
+use crate::{HxAutoVaryDebugLayer, HxTraceLayer};
+use crate::{HxAutoVaryLayer, request::HxContext};
+
+/// A layer that composes the HTMX middleware of this crate, configured from a single builder.
+///
+/// Every middleware is disabled by default and enabled individually. The request is classified once, and the HTMX
+/// headers of the request are decoded at most once: the decoded values and the [`HxRequestKind`] are shared by all the
+/// middleware and the extractors.
+///
+/// The middleware are applied in the following order (outermost first): tracing, auto vary, `ETag`, cache and cache
+/// control. Enabling the `ETag` or the cache also enables auto vary, as they depend on it.
+///
+/// Each builder method returns a layer of a new type, and disabled middleware are [`Identity`] layers. Only the enabled
+/// middleware are part of the built service, so only their requirements on the inner service and the bodies apply: for
+/// example, [`HtmxLayer::auto_vary_debug`] requires the inner service to be `Clone` to send requests again, and the
+/// `ETag` and the cache require the response body to implement `From<Bytes>`.
+///
+/// Only the middleware of this crate are composed. Application-specific middleware, such as turning redirects into
+/// `HX-Location` responses or mapping errors to error fragments, are not part of this layer: add them with their own
+/// layers, outside of `HtmxLayer` so that they see the final response, or inside of it so that the `Vary`, `ETag` and
+/// cache middleware see the responses they produce.
+///
+/// [`HxRequestKind`]: crate::request::HxRequestKind
+#[derive(Debug, Clone, Default)]
+pub struct HtmxLayer<
+    Trace = Identity,
+    AutoVary = Identity,
+    ETag = Identity,
+    Cache = Identity,
+    CacheControl = Identity,
+> {
+    trace: Trace,
+    auto_vary: AutoVary,
+    etag: ETag,
+    cache: Cache,
+    cache_control: CacheControl,
+}
+
+impl HtmxLayer {
+    /// Creates a new `HtmxLayer` with every middleware disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<Trace, AutoVary, ETag, Cache, CacheControl>
+    HtmxLayer<Trace, AutoVary, ETag, Cache, CacheControl>
+{
+    /// Enables [`HxAutoVaryLayer`]. Auto vary stays in debug mode if it is enabled already.
+    pub fn auto_vary(self) -> HtmxLayer<Trace, AutoVary::Enabled, ETag, Cache, CacheControl>
+    where
+        AutoVary: HxAutoVarySlot,
+    {
+        HtmxLayer {
+            trace: self.trace,
+            auto_vary: self.auto_vary.enable(),
+            etag: self.etag,
+            cache: self.cache,
+            cache_control: self.cache_control,
+        }
+    }
+
+    /// Enables auto vary in debug mode, see [`HxAutoVaryDebugLayer`].
+    #[cfg(feature = "tracing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+    pub fn auto_vary_debug(
+        self,
+    ) -> HtmxLayer<Trace, HxAutoVaryDebugLayer, ETag, Cache, CacheControl> {
+        HtmxLayer {
+            trace: self.trace,
+            auto_vary: HxAutoVaryDebugLayer,
+            etag: self.etag,
+            cache: self.cache,
+            cache_control: self.cache_control,
+        }
+    }
+
+    /// Enables [`HxTraceLayer`].
+    #[cfg(feature = "tracing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+    pub fn tracing(self) -> HtmxLayer<HxTraceLayer, AutoVary, ETag, Cache, CacheControl> {
+        HtmxLayer {
+            trace: HxTraceLayer::new(),
+            auto_vary: self.auto_vary,
+            etag: self.etag,
+            cache: self.cache,
+            cache_control: self.cache_control,
+        }
+    }
+
+    /// Enables [`HxETagLayer`] and auto vary.
+    #[cfg(feature = "etag")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "etag")))]
+    pub fn etag(self) -> HtmxLayer<Trace, AutoVary::Enabled, HxETagLayer, Cache, CacheControl>
+    where
+        AutoVary: HxAutoVarySlot,
+    {
+        HtmxLayer {
+            trace: self.trace,
+            auto_vary: self.auto_vary.enable(),
+            etag: HxETagLayer::new(),
+            cache: self.cache,
+            cache_control: self.cache_control,
+        }
+    }
+
+    /// Enables the given [`HxCacheLayer`] and auto vary.
+    #[cfg(feature = "cache")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cache")))]
+    pub fn cache(
+        self,
+        cache: HxCacheLayer,
+    ) -> HtmxLayer<Trace, AutoVary::Enabled, ETag, HxCacheLayer, CacheControl>
+    where
+        AutoVary: HxAutoVarySlot,
+    {
+        HtmxLayer {
+            trace: self.trace,
+            auto_vary: self.auto_vary.enable(),
+            etag: self.etag,
+            cache,
+            cache_control: self.cache_control,
+        }
+    }
+
+    /// Enables the given [`HxCacheControlLayer`].
+    #[cfg(feature = "cache-control")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cache-control")))]
+    pub fn cache_control(
+        self,
+        cache_control: HxCacheControlLayer,
+    ) -> HtmxLayer<Trace, AutoVary, ETag, Cache, HxCacheControlLayer> {
+        HtmxLayer {
+            trace: self.trace,
+            auto_vary: self.auto_vary,
+            etag: self.etag,
+            cache: self.cache,
+            cache_control,
+        }
+    }
+}
+
+impl<S, Trace, AutoVary, ETag, Cache, CacheControl> Layer<S>
+    for HtmxLayer<Trace, AutoVary, ETag, Cache, CacheControl>
+where
+    CacheControl: Layer<S>,
+    Cache: Layer<CacheControl::Service>,
+    ETag: Layer<Cache::Service>,
+    AutoVary: Layer<ETag::Service>,
+    Trace: Layer<AutoVary::Service>,
+{
+    type Service = Htmx<Trace::Service>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        let inner = self.cache_control.layer(inner);
+        let inner = self.cache.layer(inner);
+        let inner = self.etag.layer(inner);
+        let inner = self.auto_vary.layer(inner);
+        let inner = self.trace.layer(inner);
+
+        Htmx { inner }
+    }
+}
+
+/// The auto vary middleware of an [`HtmxLayer`]: [`Identity`] when auto vary is disabled, [`HxAutoVaryLayer`] or
+/// [`HxAutoVaryDebugLayer`].
+///
+/// This trait is sealed. It lets [`HtmxLayer::etag`] and [`HtmxLayer::cache`] enable auto vary without turning off
+/// debug mode.
+pub trait HxAutoVarySlot: sealed::Sealed {
+    /// The auto vary middleware once auto vary is enabled.
+    type Enabled;
+
+    /// Enables auto vary.
+    fn enable(self) -> Self::Enabled;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+impl HxAutoVarySlot for Identity {
+    type Enabled = HxAutoVaryLayer;
+
+    fn enable(self) -> Self::Enabled {
+        HxAutoVaryLayer
+    }
+}
+
+impl sealed::Sealed for Identity {}
+
+impl HxAutoVarySlot for HxAutoVaryLayer {
+    type Enabled = Self;
+
+    fn enable(self) -> Self::Enabled {
+        self
+    }
+}
+
+impl sealed::Sealed for HxAutoVaryLayer {}
+
+#[cfg(feature = "tracing")]
+impl HxAutoVarySlot for HxAutoVaryDebugLayer {
+    type Enabled = Self;
+
+    fn enable(self) -> Self::Enabled {
+        self
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl sealed::Sealed for HxAutoVaryDebugLayer {}
+
+/// A service that composes the HTMX middleware of this crate. See [`HtmxLayer`] for more details.
+#[derive(Debug, Clone)]
+pub struct Htmx<S> {
+    inner: S,
+}
+
+impl<ReqBody, S> Service<Request<ReqBody>> for Htmx<S>
+where
+    S: Service<Request<ReqBody>>,
+{
+    type Response = S::Response;
+
+    type Error = S::Error;
+
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let (mut parts, body) = req.into_parts();
+
+        // The request is classified once, before any middleware runs. The middleware and the extractors read the kind
+        // from the context.
+        parts
+            .extensions
+            .get_or_insert_with(HxContext::default)
+            .kind(&parts.headers);
+
+        self.inner.call(Request::from_parts(parts, body))
+    }
+}
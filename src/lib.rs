@@ -12,6 +12,23 @@
 //! - **Axum Integration**: Built on top of `typed-headers` in `axum-extra` for seamless integration with the `axum`.
 //! - **Auto Vary**: Correctly handle response caching by automatically add the `Vary` header to responses based on the
 //!   extracted HTMX headers.
+//! - **Cache Control**: Apply `Cache-Control` policies to responses based on the kind of the HTMX request.
+//! - **ETag**: Compute weak `ETag`s per HTMX variant and answer conditional `GET` requests with `304 Not Modified`.
+//! - **Cache**: Cache responses in memory, keyed by the HTMX variant of the request.
+//! - **Tracing**: Trace requests and responses with their HTMX headers using `tracing`.
+//! - **Actix Web Integration**: Extractors, responders and auto vary middleware for `actix-web`.
+//! - **Poem Integration**: Extractors, responses and auto vary middleware for `poem`.
+//! - **Salvo Integration**: Extractors, scribes and an auto vary hoop for `salvo`.
+//! - **Warp Integration**: Filters, replies and a `Vary` wrapper for `warp`.
+//! - **Rocket Integration**: Request guards, responders and an auto vary fairing for `rocket`.
+//! - **Reqwest Integration**: Request builder and response extensions for calling htmx endpoints with `reqwest`.
+//! - **Testing**: Simulate htmx clients and assert on HTMX response headers in tests.
+//! - **Proptest**: Generate arbitrary HTMX headers that round trip, and fuzz header decoding with `proptest`.
+//! - **Page Rendering**: Render either the full page or only the fragment swapped by htmx, depending on the request.
+//! - **Askama Integration**: Render a single block of an `askama` template as the fragment for htmx requests.
+//! - **Maud Integration**: Render typed `hx-*` attribute values and attribute sets in `maud` templates.
+//! - **MiniJinja Integration**: Render the block of a `minijinja` template that matches the htmx target.
+//! - **Tera Integration**: Render htmx attributes with `tera` filters and pick a full or partial `tera` template.
 //!
 //! # Usage
 //!
@@ -31,14 +48,51 @@
 //! cargo add htmxtools --no-default-features
 //! ```
 //!
+//! Without `axum`, HTMX request headers can be read from `http::Request` and `http::request::Parts` using
+//! [`request::HtmxRequestExt`], and HTMX response headers can be set on `http::Response` and `http::response::Parts`
+//! using [`response::HtmxResponseExt`]. Headers read this way are recorded for auto vary just like the `axum`
+//! extractors.
+//!
+//! ```rust
+//! use htmxtools::{
+//!     request::HtmxRequestExt,
+//!     response::{HtmxResponseExt, HxTrigger},
+//! };
+//! use http::{Request, Response};
+//!
+//! fn handle<B>(request: &Request<B>) -> Response<String> {
+//!     let body = match request.hx_target() {
+//!         Some(target) => format!("The target tag is: {}", target.tag()),
+//!         None => "No target specified".to_string(),
+//!     };
+//!
+//!     let mut response = Response::new(body);
+//!     if request.is_htmx() {
+//!         response.hx_trigger(HxTrigger::new().with_event("loaded".to_string()));
+//!     }
+//!
+//!     response
+//! }
+//! ```
+//!
+//! Every HTMX header implements both directions of `headers::Header`, so the same types work on the server and on the
+//! client side. This is a change in behavior that came with the `reqwest` integration: `Header::encode` used to write
+//! nothing for the request headers and now writes their values, and `Header::decode` used to fail for the response
+//! headers and now parses them. Code that relied on the old behavior, for example to drop the HTMX headers when copying
+//! a request with `encode`, must handle the headers explicitly.
+//!
 //! ## Request Extractors
 //!
 //! To extract HTMX headers from incoming requests in `axum`, you can directly use headers in [`crate::request`] in your
 //! handler functions as they implement `FromRequestParts` and `OptionalFromRequestParts` traits.
 //!
+//! Each HTMX header is decoded at most once per request, and the decoded value is shared by all the extractors and
+//! middleware that read it.
+//!
 //! Here's an example of extracting the `hx-request` header:
 //!
-//! ```rust,no_run
+#![cfg_attr(feature = "axum", doc = "```rust,no_run")]
+#![cfg_attr(not(feature = "axum"), doc = "```rust,ignore")]
 //! use axum_core::response::IntoResponse;
 //! use htmxtools::request::HxRequest;
 //!
@@ -53,7 +107,8 @@
 //!
 //! Here's another example of extracting the `hx-target` header:
 //!
-//! ```rust,no_run
+#![cfg_attr(feature = "axum", doc = "```rust,no_run")]
+#![cfg_attr(not(feature = "axum"), doc = "```rust,ignore")]
 //! use axum_core::response::IntoResponse;
 //! use htmxtools::request::HxTarget;
 //!
@@ -76,7 +131,8 @@
 //!
 //! Here's an example of building a response with the `hx-push-url` header:
 //!
-//! ```rust,no_run
+#![cfg_attr(feature = "axum", doc = "```rust,no_run")]
+#![cfg_attr(not(feature = "axum"), doc = "```rust,ignore")]
 //! use axum_core::response::IntoResponse;
 //! use htmxtools::response::HxPushUrl;
 //! use http::Uri;
@@ -88,7 +144,8 @@
 //!
 //! Here's another example of building a response with `hx-retarget` and `hx-reswap` header:
 //!
-//! ```rust,no_run
+#![cfg_attr(feature = "axum", doc = "```rust,no_run")]
+#![cfg_attr(not(feature = "axum"), doc = "```rust,ignore")]
 //! use axum_core::response::IntoResponse;
 //! use htmxtools::response::{HxReswap, HxRetarget};
 //!
@@ -114,12 +171,576 @@
 //!     Router::new().layer(HxAutoVaryLayer)
 //! }
 //! ```
+//!
+//! Handlers and middleware that read HTMX headers directly from the `HeaderMap` bypass the extractors, so the headers
+//! they depend on are never added to `Vary`. Use [`HxHeaders`] in place of `HeaderMap` to record every `hx-*` header
+//! looked up in it. During development, enable the `tracing` feature and use `HxAutoVaryLayer::debug` in place of the
+//! `HxAutoVaryLayer`: it sends htmx `GET` requests again without the `hx-*` headers missing from `Vary`, and logs a
+//! warning when the response changes.
+//!
+#![cfg_attr(feature = "axum", doc = "```rust,no_run")]
+#![cfg_attr(not(feature = "axum"), doc = "```rust,ignore")]
+//! use axum_core::response::IntoResponse;
+//! use htmxtools::HxHeaders;
+//!
+//! async fn handler(headers: HxHeaders) -> impl IntoResponse {
+//!     if headers.contains_key("hx-request") {
+//!         "<div>Fragment</div>"
+//!     } else {
+//!         "<html><body><div>Fragment</div></body></html>"
+//!     }
+//! }
+//! ```
+//!
+//! Custom extractors and middleware can take part in auto vary using [`HxAutoVaryExt`], which is implemented for
+//! `http::Request`, `http::request::Parts` and `http::Extensions`. It can register built-in HTMX headers, arbitrary
+//! header names and read the set of headers registered so far.
+//!
+#![cfg_attr(feature = "axum", doc = "```rust,no_run")]
+#![cfg_attr(not(feature = "axum"), doc = "```rust,ignore")]
+//! use axum_core::extract::FromRequestParts;
+//! use htmxtools::{HxAutoVaryExt, request::HxRequestHeader};
+//! use http::{header::ACCEPT_LANGUAGE, request::Parts};
+//!
+//! struct Locale(Option<String>);
+//!
+//! impl<S: Send + Sync> FromRequestParts<S> for Locale {
+//!     type Rejection = std::convert::Infallible;
+//!
+//!     async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+//!         parts.hx_auto_vary_add(HxRequestHeader::Request);
+//!         parts.hx_auto_vary_add_name(ACCEPT_LANGUAGE);
+//!
+//!         Ok(Locale(
+//!             parts
+//!                 .headers
+//!                 .get(ACCEPT_LANGUAGE)
+//!                 .and_then(|value| value.to_str().ok())
+//!                 .map(ToOwned::to_owned),
+//!         ))
+//!     }
+//! }
+//! ```
+//!
+//! ## Cache Control
+//!
+//! To add the `Cache-Control` header to responses based on the kind of the request (a regular page request, a boosted
+//! or history restore request, or an htmx request for full or partial content), you can use the
+//! [`HxCacheControlLayer`].
+//!
+//! To use the `HxCacheControlLayer`, you need to enable the `cache-control` feature in your `Cargo.toml`.
+//!
+//! Here's an example of using the `HxCacheControlLayer` to prevent partial responses from being cached:
+//!
+//! ```rust,ignore
+//! use axum::Router;
+//! use htmxtools::{HxAutoVaryLayer, HxCacheControlLayer};
+//! use http::HeaderValue;
+//!
+//! fn app() -> Router {
+//!     Router::new()
+//!         .layer(
+//!             HxCacheControlLayer::new()
+//!                 .partial(HeaderValue::from_static("private, no-store"))
+//!                 .page(HeaderValue::from_static("max-age=60")),
+//!         )
+//!         .layer(HxAutoVaryLayer::new())
+//! }
+//! ```
+//!
+//! ## ETag
+//!
+//! To add a weak `ETag` header to responses and answer matching `If-None-Match` requests with `304 Not Modified`, you
+//! can use the [`HxETagLayer`]. The `ETag` is computed from the response body together with the values of the request
+//! headers recorded by the [`HxAutoVaryLayer`], so the partial and the full rendering of the same URL never share an
+//! `ETag`.
+//!
+//! To use the `HxETagLayer`, you need to enable the `etag` feature in your `Cargo.toml`.
+//!
+//! Here's an example of using the `HxETagLayer`:
+//!
+//! ```rust,ignore
+//! use axum::Router;
+//! use htmxtools::{HxAutoVaryLayer, HxETagLayer};
+//!
+//! fn app() -> Router {
+//!     Router::new()
+//!         .layer(HxETagLayer::new())
+//!         .layer(HxAutoVaryLayer::new())
+//! }
+//! ```
+//!
+//! ## Cache
+//!
+//! To cache responses to `GET` requests in memory, you can use the [`HxCacheLayer`]. Responses are keyed on the method,
+//! the URI and the values of the request headers recorded by the [`HxAutoVaryLayer`], so the partial and the full
+//! rendering of the same URL are cached separately. Cached entries can be invalidated by path prefix using the
+//! [`HxCacheStore`].
+//!
+//! To use the `HxCacheLayer`, you need to enable the `cache` feature in your `Cargo.toml`.
+//!
+//! Here's an example of using the `HxCacheLayer`:
+//!
+//! ```rust,ignore
+//! use std::time::Duration;
+//!
+//! use axum::Router;
+//! use htmxtools::{HxAutoVaryLayer, HxCacheLayer};
+//!
+//! fn app() -> Router {
+//!     let cache = HxCacheLayer::new()
+//!         .ttl(Duration::from_secs(30))
+//!         .max_entries(512);
+//!     let store = cache.store();
+//!
+//!     // Call `store.invalidate_prefix("/items")` after items are updated.
+//!
+//!     Router::new().layer(cache).layer(HxAutoVaryLayer::new())
+//! }
+//! ```
+//!
+//! ## Tracing
+//!
+//! To wrap every request in a `tracing` span carrying its HTMX headers and the kind of the request, and to log the HTMX
+//! headers of every response, you can use the [`HxTraceLayer`].
+//!
+//! To use the `HxTraceLayer`, you need to enable the `tracing` feature in your `Cargo.toml`.
+//!
+//! ## HtmxLayer
+//!
+//! To configure the HTMX middleware of this crate from a single builder, you can use the [`HtmxLayer`]. The middleware
+//! are applied in the right order, the request is classified once, and the HTMX headers of the request are decoded at
+//! most once and shared by all of them and by the extractors. Disabled middleware are not part of the built service,
+//! so they add no requirements on the inner service.
+//!
+//! Only the middleware of this crate are composed: application-specific layers, such as redirect handling or error
+//! mapping, are still added separately. With the `tracing` feature, `auto_vary_debug()` enables auto vary in debug
+//! mode.
+//!
+//! Here's an example of using the `HtmxLayer`:
+//!
+//! ```rust,ignore
+//! use axum::Router;
+//! use htmxtools::{HtmxLayer, HxCacheControlLayer};
+//! use http::HeaderValue;
+//!
+//! fn app() -> Router {
+//!     Router::new().layer(
+//!         HtmxLayer::new()
+//!             .auto_vary()
+//!             .etag()
+//!             .cache_control(HxCacheControlLayer::new().partial(HeaderValue::from_static("private, no-store"))),
+//!     )
+//! }
+//! ```
+//!
+//! ## Actix Web
+//!
+//! To use `htmxtools` with `actix-web`, enable the `actix` feature in your `Cargo.toml`. HTMX request headers can then
+//! be used as extractors (including `Option<T>`), HTMX response headers as responders, and the [`actix::HxAutoVary`]
+//! middleware adds the `Vary` header like the `HxAutoVaryLayer`. See the [`actix`] module for more details.
+//!
+//! ```rust,ignore
+//! use actix_web::{App, HttpResponse, Responder, web};
+//! use htmxtools::{actix::HxAutoVary, request::HxTarget};
+//!
+//! async fn index(hx_target: Option<HxTarget>) -> impl Responder {
+//!     match hx_target {
+//!         Some(target) => HttpResponse::Ok().body(format!("The target tag is: {}", target.tag())),
+//!         None => HttpResponse::Ok().body("No target specified"),
+//!     }
+//! }
+//!
+//! let app = App::new()
+//!     .wrap(HxAutoVary::new())
+//!     .route("/", web::get().to(index));
+//! ```
+//!
+//! ## Poem
+//!
+//! To use `htmxtools` with `poem`, enable the `poem` feature in your `Cargo.toml`. HTMX request headers can then be
+//! used as extractors (including `Option<T>`), HTMX response headers as responses or added to any response with
+//! [`poem::HxIntoResponseExt`], and the [`poem::HxAutoVary`] middleware adds the `Vary` header like the
+//! `HxAutoVaryLayer`. See the [`poem`] module for more details.
+//!
+//! ```rust,ignore
+//! use htmxtools::{poem::HxAutoVary, request::HxTarget};
+//! use poem::{EndpointExt, Route, get, handler};
+//!
+//! #[handler]
+//! fn index(hx_target: Option<HxTarget>) -> String {
+//!     match hx_target {
+//!         Some(target) => format!("The target tag is: {}", target.tag()),
+//!         None => "No target specified".to_string(),
+//!     }
+//! }
+//!
+//! let app = Route::new().at("/", get(index)).with(HxAutoVary::new());
+//! ```
+//!
+//! ## Salvo
+//!
+//! To use `htmxtools` with `salvo`, enable the `salvo` feature in your `Cargo.toml`. HTMX request headers can then be
+//! used as handler arguments or read from the request with [`request::HtmxRequestExt`], HTMX response headers can be
+//! rendered to a response, and the [`salvo::HxAutoVary`] hoop adds the `Vary` header like the `HxAutoVaryLayer`. See
+//! the [`salvo`] module for more details.
+//!
+//! ```rust,ignore
+//! use htmxtools::{request::HxTarget, salvo::HxAutoVary};
+//! use salvo_core::prelude::*;
+//!
+//! #[handler]
+//! async fn index(hx_target: HxTarget) -> String {
+//!     format!("The target tag is: {}", hx_target.tag())
+//! }
+//!
+//! let router = Router::new().hoop(HxAutoVary::new()).get(index);
+//! ```
+//!
+//! ## Warp
+//!
+//! To use `htmxtools` with `warp`, enable the `warp` feature in your `Cargo.toml`. The [`warp`] module provides filters
+//! for HTMX request headers, such as `htmx::request()`, `htmx::target()` and `htmx::optional::<HxSource>()`, implements
+//! `Reply` for HTMX response headers, adds them to any reply with [`warp::with_header`], and adds the `Vary` header
+//! with the [`warp::vary`] wrapper. See the [`warp`] module for more details.
+//!
+//! ```rust,ignore
+//! use htmxtools::{request::HxTarget, warp as htmx};
+//! use warp::Filter;
+//!
+//! let routes = warp::path("search")
+//!     .and(htmx::target())
+//!     .map(|target: HxTarget| format!("The target tag is: {}", target.tag()))
+//!     .with(warp::wrap_fn(htmx::vary));
+//! ```
+//!
+//! ## Rocket
+//!
+//! To use `htmxtools` with `rocket`, enable the `rocket` feature in your `Cargo.toml`. HTMX request headers can then be
+//! used as request guards (including `Option<T>`, or [`rocket::HxForward`] to forward the request if the header is
+//! missing), HTMX response headers as responders or added to any responder with [`rocket::HxResponderExt`], and the
+//! [`rocket::HxAutoVary`] fairing adds the `Vary` header like the `HxAutoVaryLayer`. See the [`rocket`] module for more
+//! details.
+//!
+//! ```rust,ignore
+//! use htmxtools::{request::HxTarget, rocket::HxAutoVary};
+//! use rocket::{get, routes};
+//!
+//! #[get("/")]
+//! fn index(hx_target: Option<HxTarget>) -> String {
+//!     match hx_target {
+//!         Some(target) => format!("The target tag is: {}", target.tag()),
+//!         None => "No target specified".to_string(),
+//!     }
+//! }
+//!
+//! let rocket = rocket::build().mount("/", routes![index]).attach(HxAutoVary::new());
+//! ```
+//!
+//! ## Reqwest
+//!
+//! To call htmx endpoints from Rust, for example in tests or from another service, enable the `reqwest` feature in your
+//! `Cargo.toml`. [`reqwest::HxRequestBuilderExt`] attaches HTMX request headers to a `RequestBuilder`, and
+//! [`reqwest::HxResponseExt`] parses the HTMX response headers of a `Response` into the types of this crate.
+//!
+//! ```rust,ignore
+//! use htmxtools::{
+//!     request::HxTarget,
+//!     reqwest::{HxRequestBuilderExt, HxResponseExt},
+//! };
+//!
+//! let response = reqwest::Client::new()
+//!     .get("http://localhost:3000/search")
+//!     .hx_request()
+//!     .hx_target(HxTarget::from_static("div#results"))
+//!     .send()
+//!     .await?;
+//!
+//! if let Some(trigger) = response.hx_trigger() {
+//!     for (event, data) in trigger.events() {
+//!         println!("{event}: {data:?}");
+//!     }
+//! }
+//! ```
+//!
+//! ## Testing
+//!
+//! To test handlers without a browser, enable the `testing` feature in your `Cargo.toml`. [`testing::HtmxTestRequest`]
+//! builds `http::Request`s with exactly the headers htmx sends for a plain click, boosted navigation, a history restore
+//! miss or a form submit, for htmx 4 or htmx 2. [`testing::HtmxResponseAssertions`] asserts on the HTMX response
+//! headers of a response, and prints the decoded headers if an assertion fails. [`testing::HtmxTestClient`] wraps a
+//! `tower::Service`, such as an `axum::Router`, and follows redirects, tracks history and records events like htmx
+//! does, to test multi-step flows. With the `testing-html` feature, [`testing::HtmxFragmentAssertions`] parses the body
+//! of a response and checks it against the swap it is used for, such as the id of the target for `outerHTML` swaps, the
+//! `hx-reselect` selector and `hx-swap-oob` markers. See the [`testing`] module for more details.
+//!
+//! ```rust,ignore
+//! use htmxtools::{
+//!     request::HxTarget,
+//!     response::HxReswap,
+//!     testing::{HtmxResponseAssertions, HtmxTestRequest},
+//! };
+//! use tower::ServiceExt;
+//!
+//! let request = HtmxTestRequest::click("/items")
+//!     .with_target(HxTarget::from_static("ul#items"))
+//!     .build();
+//!
+//! let response = app.oneshot(request).await.unwrap();
+//!
+//! response
+//!     .assert_triggered("showToast")
+//!     .assert_reswap(HxReswap::OuterHtml)
+//!     .assert_vary_contains("hx-target");
+//! ```
+//!
+//! ## Proptest
+//!
+//! To property test code that handles HTMX headers, enable the `proptest` feature in your `Cargo.toml`. Every request
+//! and response header implements `proptest::arbitrary::Arbitrary`, generating values that decode back to themselves
+//! after being encoded. [`proptest::header_value`] generates header values that are mostly invalid, to check that
+//! decoding never panics.
+//!
+//! ```rust,ignore
+//! use headers_core::Header;
+//! use htmxtools::{proptest::header_value, request::HxTarget};
+//! use proptest::prelude::*;
+//!
+//! proptest!(|(target in any::<HxTarget>())| {
+//!     let mut values = Vec::new();
+//!     target.encode(&mut values);
+//!     prop_assert_eq!(HxTarget::decode(&mut values.iter()).ok(), Some(target));
+//! });
+//!
+//! proptest!(|(value in header_value())| {
+//!     let _ = HxTarget::decode(&mut std::iter::once(&value));
+//! });
+//! ```
+//!
+//! ## Page Rendering
+//!
+//! To avoid branching on the HTMX headers in every page handler, implement [`response::HxPage`] for the data of a page,
+//! with a `full` rendering that includes the layout and a `partial` rendering of the fragment swapped by htmx. It works
+//! with any template engine. [`response::HxRender`] picks the rendering for the kind of the request: regular, history
+//! restore, boosted and `hx-request-type: full` requests get the full page, and all other htmx requests get the
+//! fragment. It also adds the headers it depends on to `Vary`.
+//!
+#![cfg_attr(feature = "axum", doc = "```rust,no_run")]
+#![cfg_attr(not(feature = "axum"), doc = "```rust,ignore")]
+//! use axum_core::response::IntoResponse;
+//! use htmxtools::{
+//!     request::HxRequestKind,
+//!     response::{HxPage, HxRender},
+//! };
+//!
+//! struct Dashboard {
+//!     user: String,
+//! }
+//!
+//! impl HxPage for Dashboard {
+//!     type Output = String;
+//!
+//!     fn full(self) -> String {
+//!         format!("<html><body><nav></nav>{}</body></html>", self.partial())
+//!     }
+//!
+//!     fn partial(self) -> String {
+//!         format!("<main>Welcome, {}</main>", self.user)
+//!     }
+//! }
+//!
+//! async fn handler(kind: HxRequestKind) -> impl IntoResponse {
+//!     HxRender::new(Dashboard { user: "admin".to_string() }, kind)
+//! }
+//! ```
+//!
+//! ## Askama
+//!
+//! To keep one `askama` template per page and render only a block of it for htmx requests, enable the `askama` feature
+//! in your `Cargo.toml`. List the blocks in `#[template(blocks = [...])]` and implement [`askama::HxBlocks`] to look up
+//! the sub-templates askama generates by name. [`askama::HxTemplate`] renders the whole template for requests that
+//! expect a full page, and the block named after the id of the `hx-target` header (or an explicit block) for partial
+//! htmx requests. It can be returned from `axum` handlers.
+//!
+//! ```rust,ignore
+//! use askama::Template;
+//! use axum_core::response::IntoResponse;
+//! use htmxtools::{
+//!     askama::{HxBlocks, HxTemplate},
+//!     request::{HxRequestKind, HxTarget},
+//! };
+//!
+//! #[derive(Template)]
+//! #[template(path = "items.html", blocks = ["items"])]
+//! struct ItemsPage {
+//!     items: Vec<String>,
+//! }
+//!
+//! impl HxBlocks for ItemsPage {
+//!     fn render_block(&self, name: &str) -> Option<askama::Result<String>> {
+//!         match name {
+//!             "items" => Some(self.as_items().render()),
+//!             _ => None,
+//!         }
+//!     }
+//! }
+//!
+//! async fn items(kind: HxRequestKind, target: Option<HxTarget>) -> impl IntoResponse {
+//!     HxTemplate::new(ItemsPage { items: vec!["Milk".to_string()] }, kind, target)
+//! }
+//! ```
+//!
+//! ## Maud
+//!
+//! To keep the `hx-*` attributes of `maud` templates in sync with the server, enable the `maud` feature in your
+//! `Cargo.toml`. The types of the [`attribute`] module, [`attribute::HxSwap`] for swap strategies with modifiers and
+//! [`attribute::HxTriggerSpec`] for trigger specs, as well as the selector, swap and push URL types in [`request`] and
+//! [`response`], implement `Render` and can be spliced as attribute values. [`maud::element`] renders an element with a
+//! whole [`attribute::HxAttributes`] set. All values are escaped.
+//!
+//! ```rust,ignore
+//! use std::time::Duration;
+//!
+//! use htmxtools::{
+//!     attribute::{HxAttributes, HxSwap, HxTriggerSpec},
+//!     maud::element,
+//!     response::{HxPushUrl, HxReswap, HxRetarget},
+//! };
+//! use maud::{Markup, html};
+//!
+//! fn search() -> Markup {
+//!     html! {
+//!         input
+//!             name="q"
+//!             hx-get="/search"
+//!             hx-trigger=(HxTriggerSpec::new("keyup").changed().delay(Duration::from_millis(300)))
+//!             hx-target=(HxRetarget::from_static("#results"))
+//!             hx-swap=(HxSwap::new(HxReswap::OuterHtml).transition())
+//!             hx-push-url=(HxPushUrl::False);
+//!         (element("button", &HxAttributes::new().post("/items").target("#items"), "Add"))
+//!     }
+//! }
+//! ```
+//!
+//! ## MiniJinja
+//!
+//! To render `minijinja` templates for htmx requests, enable the `minijinja` feature in your `Cargo.toml`.
+//! [`minijinja::HxRenderer`] is an `axum` extractor that renders the whole template for requests that expect a full
+//! page, and only the block named after the id of the `hx-target` header for partial htmx requests, using minijinja's
+//! block rendering. Templates can also use the `is_htmx`, `target`, `source` and `boosted` variables.
+//! [`minijinja::HxRenderer::render_http`] renders into a response with the `Vary` header set.
+//!
+//! ```rust,ignore
+//! use htmxtools::minijinja::HxRenderer;
+//! use minijinja::{Environment, context};
+//!
+//! // `items.html` extends a layout and defines `{% block items %}<ul id="items">...</ul>{% endblock %}`.
+//! async fn items(htmx: HxRenderer) -> String {
+//!     let env: &Environment = environment();
+//!     htmx.render(env, "items.html", context! { items => ["Milk"] })
+//!         .unwrap_or_else(|error| error.to_string())
+//! }
+//! ```
+//!
+//! ## Tera
+//!
+//! To use htmx with `tera` templates, enable the `tera` feature in your `Cargo.toml`. [`tera::register`] adds the
+//! `hx_swap` and `hx_trigger` filters and the `hx_attrs` function, which render swap strategies, trigger specs and
+//! attribute sets with selectors as escaped attribute values. The types of the [`attribute`] module and the selector,
+//! swap and push URL types also convert into a `tera::Value`. [`tera::HxRenderer`] is an `axum` extractor that inserts
+//! the `htmx` object (`is_htmx`, `partial`, `boosted`, `target` and `source`) into a `Context`, and
+//! [`tera::HxTemplate`] renders the full template for requests that expect a full page and its partial template, such
+//! as `items.partial.html` for `items.html`, for partial htmx requests.
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//!
+//! use axum::extract::State;
+//! use htmxtools::tera::{HxRenderer, HxTemplate};
+//! use tera::{Context, Tera};
+//!
+//! // `items.html` renders the page, with `<ul id="items"{{ hx_attrs(get="/items", trigger="every 10s") }}>`,
+//! // and `items.partial.html` renders only the list.
+//! async fn items(State(tera): State<Arc<Tera>>, htmx: HxRenderer) -> HxTemplate<Arc<Tera>> {
+//!     let mut context = Context::new();
+//!     context.insert("items", &["Milk"]);
+//!     htmx.template(tera, "items.html", context)
+//! }
+//! ```
+#[cfg(feature = "actix")]
+#[cfg_attr(docsrs, doc(cfg(feature = "actix")))]
+pub mod actix;
+#[cfg(feature = "askama")]
+#[cfg_attr(docsrs, doc(cfg(feature = "askama")))]
+pub mod askama;
+pub mod attribute;
 #[cfg(feature = "auto-vary")]
 mod auto_vary;
+#[cfg(feature = "cache")]
+mod cache;
+#[cfg(feature = "cache-control")]
+mod cache_control;
+#[cfg(feature = "etag")]
+mod etag;
+#[cfg(feature = "auto-vary")]
+mod htmx_layer;
+#[cfg(feature = "maud")]
+#[cfg_attr(docsrs, doc(cfg(feature = "maud")))]
+pub mod maud;
+#[cfg(feature = "minijinja")]
+#[cfg_attr(docsrs, doc(cfg(feature = "minijinja")))]
+pub mod minijinja;
+#[cfg(feature = "poem")]
+#[cfg_attr(docsrs, doc(cfg(feature = "poem")))]
+pub mod poem;
+#[cfg(feature = "proptest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+pub mod proptest;
 pub mod request;
+#[cfg(feature = "reqwest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
+pub mod reqwest;
 pub mod response;
+#[cfg(feature = "rocket")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rocket")))]
+pub mod rocket;
+#[cfg(feature = "salvo")]
+#[cfg_attr(docsrs, doc(cfg(feature = "salvo")))]
+pub mod salvo;
+#[cfg(feature = "tera")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tera")))]
+pub mod tera;
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
+#[cfg(feature = "tracing")]
+mod trace;
 mod util;
+#[cfg(feature = "warp")]
+#[cfg_attr(docsrs, doc(cfg(feature = "warp")))]
+pub mod warp;
 
 #[cfg(feature = "auto-vary")]
 #[cfg_attr(docsrs, doc(cfg(feature = "auto-vary")))]
-pub use self::auto_vary::{HxAutoVary, HxAutoVaryLayer, HxAutoVaryResponseFuture};
+pub use self::auto_vary::{
+    HxAutoVary, HxAutoVaryExt, HxAutoVaryLayer, HxAutoVaryResponseFuture, HxHeaders,
+    HxRequestHeaderSet,
+};
+#[cfg(all(feature = "auto-vary", feature = "tracing"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "auto-vary", feature = "tracing"))))]
+pub use self::auto_vary::{HxAutoVaryDebug, HxAutoVaryDebugLayer, HxAutoVaryDebugResponseFuture};
+#[cfg(feature = "cache")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cache")))]
+pub use self::cache::{HxCache, HxCacheLayer, HxCacheResponseFuture, HxCacheStore};
+#[cfg(feature = "cache-control")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cache-control")))]
+pub use self::cache_control::{HxCacheControl, HxCacheControlLayer, HxCacheControlResponseFuture};
+#[cfg(feature = "etag")]
+#[cfg_attr(docsrs, doc(cfg(feature = "etag")))]
+pub use self::etag::{HxETag, HxETagLayer, HxETagResponseFuture};
+#[cfg(feature = "auto-vary")]
+#[cfg_attr(docsrs, doc(cfg(feature = "auto-vary")))]
+pub use self::htmx_layer::{Htmx, HtmxLayer, HxAutoVarySlot};
+#[cfg(feature = "tracing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+pub use self::trace::{HxTrace, HxTraceLayer, HxTraceResponseFuture};
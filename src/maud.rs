@@ -0,0 +1,108 @@
+//! Integration with `maud`, for rendering typed htmx attribute values in `html!` blocks.
+//!
+//! - The types of [`crate::attribute`] and the request and response types that share their syntax with an htmx
+//!   attribute implement `Render`, so they can be spliced as attribute values and are escaped by `maud`.
+//! - [`element`] renders an element with a whole [`HxAttributes`] set, because `maud` cannot splice attribute names.
+//!
+//! ```rust
+//! use htmxtools::{
+//!     attribute::{HxAttributes, HxSwap, HxTriggerSpec},
+//!     maud::element,
+//!     response::{HxPushUrl, HxReswap, HxRetarget},
+//! };
+//! use maud::html;
+//!
+//! let markup = html! {
+//!     input
+//!         name="q"
+//!         hx-get="/search"
+//!         hx-trigger=(HxTriggerSpec::new("keyup").changed())
+//!         hx-target=(HxRetarget::from_static("#results"))
+//!         hx-swap=(HxSwap::new(HxReswap::OuterHtml).transition())
+//!         hx-push-url=(HxPushUrl::False);
+//!     (element("button", &HxAttributes::new().post("/items").target("#items"), "Add"))
+//! };
+//!
+//! assert_eq!(
+//!     markup.into_string(),
+//!     concat!(
+//!         r##"<input name="q" hx-get="/search" hx-trigger="keyup changed" hx-target="#results" "##,
+//!         r##"hx-swap="outerHTML transition:true" hx-push-url="false">"##,
+//!         r##"<button hx-post="/items" hx-target="#items">Add</button>"##,
+//!     ),
+//! );
+//! ```
+use std::fmt::Write;
+
+use maud::{Escaper, Markup, PreEscaped, Render};
+
+use crate::{
+    attribute::{self, HxAttributes, HxSwap, HxTriggerSpec},
+    request::{HxSource, HxTarget},
+    response::{HxPushUrl, HxReplaceUrl, HxReselect, HxReswap, HxRetarget},
+};
+
+/// The HTML elements that have no content and no closing tag.
+const VOID_ELEMENTS: [&str; 13] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Renders an element with the htmx attributes `attributes`, escaped, and `content`. Void elements, such as `input`
+/// and `img`, are rendered without a closing tag, so their content must be empty, such as `""`.
+///
+/// # Panic
+///
+/// Panics if `tag` is empty or contains characters other than ASCII letters, digits and `-`, or if `tag` is a void
+/// element and `content` is not empty.
+pub fn element(tag: &str, attributes: &HxAttributes, content: impl Render) -> Markup {
+    assert!(
+        !tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'),
+        "`{tag}` is not a valid tag name"
+    );
+
+    let mut buffer = String::new();
+    let _ = write!(buffer, "<{tag}{attributes}>");
+
+    if VOID_ELEMENTS
+        .iter()
+        .any(|void| void.eq_ignore_ascii_case(tag))
+    {
+        let start = buffer.len();
+        content.render_to(&mut buffer);
+        assert!(
+            buffer.len() == start,
+            "`{tag}` is a void element and cannot have content"
+        );
+    } else {
+        content.render_to(&mut buffer);
+        let _ = write!(buffer, "</{tag}>");
+    }
+
+    PreEscaped(buffer)
+}
+
+macro_rules! impl_render {
+    ($($ty:ty => |$this:ident| $value:expr),* $(,)?) => {
+        $(
+            impl Render for $ty {
+                fn render_to(&self, buffer: &mut String) {
+                    let $this = self;
+                    let _ = write!(Escaper::new(buffer), "{}", $value);
+                }
+            }
+        )*
+    };
+}
+
+impl_render!(
+    HxSwap => |swap| swap,
+    HxTriggerSpec => |trigger| trigger,
+    HxReswap => |reswap| reswap.as_str(),
+    HxPushUrl => |push_url| attribute::push_url_value(push_url),
+    HxReplaceUrl => |replace_url| attribute::replace_url_value(replace_url),
+    HxReselect => |reselect| reselect.as_str(),
+    HxRetarget => |retarget| retarget.as_str(),
+    HxSource => |source| source.as_str(),
+    HxTarget => |target| target.as_str(),
+);
@@ -0,0 +1,164 @@
+//! Integration with `minijinja`, for rendering the block of a template that matches the target of an htmx request.
+//!
+//! [`HxRenderer`] holds the htmx information of a request. It renders a template whole for requests that expect a full
+//! page, and only the block named after the id of the `hx-target` header for partial htmx requests. It also exposes
+//! the request to the template as the variables `is_htmx`, `target`, `source` and `boosted`.
+//!
+//! ```rust,ignore
+//! use htmxtools::minijinja::HxRenderer;
+//! use minijinja::{Environment, context};
+//!
+//! // `items.html` extends a layout and defines `{% block items %}<ul id="items">...</ul>{% endblock %}`.
+//! async fn items(htmx: HxRenderer) -> String {
+//!     let env: &Environment = environment();
+//!     htmx.render(env, "items.html", context! { items => ["Milk"] })
+//!         .unwrap_or_else(|error| error.to_string())
+//! }
+//! ```
+#[cfg(feature = "axum")]
+use std::convert::Infallible;
+
+#[cfg(feature = "axum")]
+use axum_core::extract::FromRequestParts;
+use headers_core::Header;
+#[cfg(feature = "axum")]
+use http::request::Parts;
+use http::{HeaderValue, Response, header::CONTENT_TYPE};
+use minijinja::{Environment, Error, ErrorKind, Value, context, value::merge_maps};
+
+#[cfg(feature = "axum")]
+use crate::request::HxContext;
+use crate::{
+    request::{HtmxRequestExt, HxRequestKind, HxSource, HxTarget},
+    response::hx_page::add_vary,
+    util::header_list,
+};
+
+/// Renders `minijinja` templates for an htmx request.
+///
+/// [`HxRenderer::render`] chooses what to render like this:
+///
+/// 1. For requests that expect a full page (see [`HxRequestKind::is_full_page`]), the whole template.
+/// 2. For partial htmx requests, the block named after the id of the `hx-target` header. Block names are identifiers,
+///    so the id with `-` replaced by `_` is tried too: an `hx-target` of `div#item-list` renders the block `item-list`
+///    or `item_list`.
+/// 3. Otherwise, for example without a target id or without a matching block, the whole template.
+///
+/// The context of the template is merged with these variables, which the context can override:
+///
+/// - `is_htmx`: `true` if the request was made by htmx.
+/// - `target`: the `hx-target` header, such as `div#results`, or `none`.
+/// - `source`: the `hx-source` header, such as `button#save`, or `none`.
+/// - `boosted`: `true` if the request was made by an element using `hx-boost`.
+///
+/// [`HxRenderer::render_http`] renders into an `http::Response` whose `Vary` header lists the headers the rendering
+/// depends on, for caches in front of handlers that do not use the auto vary middleware.
+///
+/// With the `axum` feature, `HxRenderer` is an extractor. Like the other extractors, it records the headers it reads
+/// for auto vary.
+#[derive(Debug, Clone)]
+pub struct HxRenderer {
+    kind: HxRequestKind,
+    target: Option<HxTarget>,
+    source: Option<HxSource>,
+}
+
+impl HxRenderer {
+    /// Creates a new `HxRenderer` from the HTMX headers of a request.
+    pub fn new(kind: HxRequestKind, target: Option<HxTarget>, source: Option<HxSource>) -> Self {
+        Self {
+            kind,
+            target,
+            source,
+        }
+    }
+
+    /// Creates a new `HxRenderer` from an `http::Request` or `http::request::Parts`, recording the headers it reads for
+    /// auto vary.
+    pub fn from_request(request: &impl HtmxRequestExt) -> Self {
+        Self::new(
+            request.hx_request_kind(),
+            request.hx_target(),
+            request.hx_source(),
+        )
+    }
+
+    /// Returns the kind of the request.
+    pub fn kind(&self) -> HxRequestKind {
+        self.kind
+    }
+
+    /// Returns the variables exposed to templates: `is_htmx`, `target`, `source` and `boosted`.
+    pub fn values(&self) -> Value {
+        context! {
+            is_htmx => self.kind.is_htmx(),
+            target => self.target.as_ref().map(HxTarget::as_str),
+            source => self.source.as_ref().map(HxSource::as_str),
+            boosted => self.kind == HxRequestKind::Boosted,
+        }
+    }
+
+    /// Renders the template `name` of `env`, or the block that matches the target, with `ctx` and the htmx variables.
+    pub fn render(
+        &self,
+        env: &Environment<'_>,
+        name: &str,
+        ctx: impl Into<Value>,
+    ) -> Result<String, Error> {
+        let template = env.get_template(name)?;
+        let ctx = merge_maps([self.values(), ctx.into()]);
+
+        let id = match self.target.as_ref().and_then(HxTarget::id) {
+            Some(id) if !self.kind.is_full_page() => id,
+            _ => return template.render(ctx),
+        };
+
+        let mut captured = template.render_captured(ctx)?;
+        for block in [id.to_string(), id.replace('-', "_")] {
+            match captured.with_state_mut(|state| state.render_block(&block)) {
+                Err(error) if error.kind() == ErrorKind::UnknownBlock => {}
+                result => return result,
+            }
+        }
+        Ok(captured.into_output())
+    }
+
+    /// Renders like [`HxRenderer::render`] into an `http::Response` with the `Content-Type` and `Vary` headers.
+    ///
+    /// `Vary` lists the headers consulted to classify the request, and `hx-target` and `hx-source` since the template
+    /// can read them.
+    pub fn render_http(
+        &self,
+        env: &Environment<'_>,
+        name: &str,
+        ctx: impl Into<Value>,
+    ) -> Result<Response<String>, Error> {
+        let mut response = Response::new(self.render(env, name, ctx)?);
+
+        let headers = response.headers_mut();
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("text/html; charset=utf-8"),
+        );
+        add_vary(headers, self.kind);
+        header_list::append_vary(headers, HxTarget::name());
+        header_list::append_vary(headers, HxSource::name());
+
+        Ok(response)
+    }
+}
+
+#[cfg(feature = "axum")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+impl<S> FromRequestParts<S> for HxRenderer
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get_or_insert_with(HxContext::default);
+
+        Ok(Self::from_request(&*parts))
+    }
+}
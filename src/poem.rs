@@ -0,0 +1,194 @@
+//! Integration with `poem`.
+//!
+//! - Every type in [`crate::request`] implements `FromRequest`. `Option<T>` resolves to `None` if the header is missing
+//!   (or invalid). Headers are decoded exactly like the `axum` extractors do, and recorded for auto vary.
+//! - Every type in [`crate::response`] implements `IntoResponse`, and [`HxIntoResponseExt`] adds HTMX response headers
+//!   to any `IntoResponse`.
+//! - [`HxAutoVary`] is a middleware that adds the `Vary` header to responses based on the extracted HTMX headers, like
+//!   [`HxAutoVaryLayer`](crate::HxAutoVaryLayer) does for `tower`.
+//!
+//! ```rust,no_run
+//! use htmxtools::{
+//!     poem::{HxAutoVary, HxIntoResponseExt},
+//!     request::HxTarget,
+//!     response::HxTrigger,
+//! };
+//! use poem::{EndpointExt, IntoResponse, Route, get, handler};
+//!
+//! #[handler]
+//! fn index(hx_target: Option<HxTarget>) -> impl IntoResponse {
+//!     let body = match hx_target {
+//!         Some(target) => format!("The target tag is: {}", target.tag()),
+//!         None => "No target specified".to_string(),
+//!     };
+//!
+//!     body.with_hx_header(HxTrigger::new().with_event("loaded".to_string()))
+//! }
+//!
+//! let app = Route::new().at("/", get(index)).with(HxAutoVary::new());
+//! ```
+use headers_core::Header;
+use poem::{
+    Endpoint, Error, FromRequest, IntoResponse, Middleware, Request, RequestBody, Response, Result,
+    error::ParseTypedHeaderError,
+};
+
+use crate::{
+    auto_vary::SharedSet,
+    request::{
+        HxBoosted, HxCurrentUrl, HxHistoryRestoreRequest, HxRequest, HxRequestKind, HxRequestType,
+        HxSource, HxTarget, hx_header::decode,
+    },
+    response::{
+        HxLocation, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl, HxReselect, HxReswap,
+        HxRetarget, HxTrigger, HxTriggerAfterSettle, HxTriggerAfterSwap,
+    },
+};
+
+macro_rules! impl_from_request {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<'a> FromRequest<'a> for $ty {
+                async fn from_request(req: &'a Request, _: &mut RequestBody) -> Result<Self> {
+                    decode::<Self>(req.headers(), req.extensions())
+                        .map_err(ParseTypedHeaderError::TypedHeader)?
+                        .ok_or_else(|| {
+                            ParseTypedHeaderError::HeaderRequired(<Self as Header>::name().to_string())
+                                .into()
+                        })
+                }
+            }
+        )*
+    };
+}
+
+impl_from_request!(
+    HxBoosted,
+    HxCurrentUrl,
+    HxHistoryRestoreRequest,
+    HxRequest,
+    HxRequestType,
+    HxSource,
+    HxTarget,
+);
+
+impl<'a> FromRequest<'a> for HxRequestKind {
+    async fn from_request(req: &'a Request, _: &mut RequestBody) -> Result<Self> {
+        Ok(HxRequestKind::classify(req.headers(), req.extensions()))
+    }
+}
+
+macro_rules! impl_into_response {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl IntoResponse for $ty {
+                fn into_response(self) -> Response {
+                    Response::builder().typed_header(self).finish()
+                }
+            }
+        )*
+    };
+}
+
+impl_into_response!(
+    HxLocation,
+    HxPushUrl,
+    HxRedirect,
+    HxRefresh,
+    HxReplaceUrl,
+    HxReselect,
+    HxReswap,
+    HxRetarget,
+    HxTrigger,
+    HxTriggerAfterSettle,
+    HxTriggerAfterSwap,
+);
+
+/// Extension trait to add HTMX response headers to any `IntoResponse`.
+pub trait HxIntoResponseExt: IntoResponse + Sized {
+    /// Wraps the response to append the values of an HTMX response header.
+    fn with_hx_header<H: Header + Send>(self, header: H) -> WithHxHeader<Self, H> {
+        WithHxHeader {
+            inner: self,
+            header,
+        }
+    }
+}
+
+impl<T: IntoResponse> HxIntoResponseExt for T {}
+
+/// A response with an HTMX response header. See [`HxIntoResponseExt::with_hx_header`].
+#[derive(Debug)]
+pub struct WithHxHeader<T, H> {
+    inner: T,
+    header: H,
+}
+
+impl<T: IntoResponse, H: Header + Send> IntoResponse for WithHxHeader<T, H> {
+    fn into_response(self) -> Response {
+        let mut response = self.inner.into_response();
+
+        let mut values = Vec::new();
+        self.header.encode(&mut values);
+        for value in values {
+            response.headers_mut().append(H::name(), value);
+        }
+
+        response
+    }
+}
+
+/// A middleware that automatically adds the `Vary` header to responses based on the extracted HTMX headers. This is
+/// the `poem` equivalent of [`HxAutoVaryLayer`](crate::HxAutoVaryLayer).
+///
+/// Errors returned by the inner endpoint are still returned as errors, so that outer middleware (such as
+/// `catch_error` or `catch_all_error`) see them. Rejections of the extractors need the `Vary` header too, so errors are
+/// converted to their response, the `Vary` header is added, and the response is returned with
+/// [`Error::from_response`]: the status is kept, but the error can no longer be downcast to its original type.
+#[derive(Debug, Clone, Default)]
+pub struct HxAutoVary;
+
+impl HxAutoVary {
+    /// Creates a new `HxAutoVary`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for HxAutoVary {
+    type Output = HxAutoVaryEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        HxAutoVaryEndpoint { inner: ep }
+    }
+}
+
+/// The endpoint created by [`HxAutoVary`].
+#[derive(Debug)]
+pub struct HxAutoVaryEndpoint<E> {
+    inner: E,
+}
+
+impl<E: Endpoint> Endpoint for HxAutoVaryEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let set = SharedSet::default();
+        req.extensions_mut().insert(set.clone());
+
+        let (mut response, is_error) = match self.inner.call(req).await {
+            Ok(output) => (output.into_response(), false),
+            Err(error) => (error.into_response(), true),
+        };
+
+        if let Ok(lock) = set.lock() {
+            lock.add_to_headers(response.headers_mut());
+        }
+
+        if is_error {
+            Err(Error::from_response(response))
+        } else {
+            Ok(response)
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! `proptest` strategies for the HTMX headers.
+//!
+//! - Every type in [`crate::request`] and [`crate::response`] implements `Arbitrary`. The generated values round trip
+//!   through the `Header` implementation: decoding the encoded values of a header returns the same header.
+//! - [`header_value`] generates header values that are mostly not valid HTMX headers, to check that decoding never
+//!   panics. It mixes random bytes with fragments of valid HTMX headers.
+//! - [`encoded`] generates the encoded values of valid headers.
+//!
+//! ```rust
+//! use headers_core::Header;
+//! use htmxtools::{proptest::header_value, response::HxTrigger};
+//! use proptest::prelude::*;
+//!
+//! proptest!(|(trigger in any::<HxTrigger>())| {
+//!     let mut values = Vec::new();
+//!     trigger.encode(&mut values);
+//!     prop_assert_eq!(HxTrigger::decode(&mut values.iter()).ok(), Some(trigger));
+//! });
+//!
+//! proptest!(|(value in header_value())| {
+//!     let _ = HxTrigger::decode(&mut std::iter::once(&value));
+//! });
+//! ```
+use headers_core::Header;
+use http::{HeaderMap, HeaderName, HeaderValue, Uri};
+use proptest::{
+    arbitrary::{Arbitrary, any},
+    collection::{btree_map, vec},
+    option,
+    prelude::{BoxedStrategy, Just, Strategy},
+    prop_oneof,
+    sample::select,
+};
+use serde_json::Value;
+
+use crate::{
+    request::{
+        HxBoosted, HxCurrentUrl, HxHistoryRestoreRequest, HxRequest, HxRequestType, HxSource,
+        HxTarget,
+    },
+    response::{
+        HxLocation, HxLocationSwapOption, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl,
+        HxReselect, HxReswap, HxRetarget, HxTrigger, HxTriggerAfterSettle, HxTriggerAfterSwap,
+    },
+};
+
+/// Generates header values, most of which are not valid HTMX headers.
+pub fn header_value() -> impl Strategy<Value = HeaderValue> {
+    let byte = prop_oneof![Just(b'\t'), 0x20u8..0x7f, 0x80u8..=0xff];
+    let token = select(vec![
+        "true",
+        "false",
+        "partial",
+        "full",
+        "outerHTML",
+        "null",
+        "{",
+        "}",
+        "[",
+        "]",
+        "\"",
+        ":",
+        ",",
+        "#",
+        "/",
+        " ",
+        "http://",
+        "?",
+        "%",
+        "\\u00e9",
+    ]);
+
+    prop_oneof![
+        vec(byte, 0..32).prop_filter_map("not a header value", |bytes| HeaderValue::from_bytes(
+            &bytes
+        )
+        .ok()),
+        vec(token, 0..12).prop_filter_map("not a header value", |tokens| HeaderValue::from_str(
+            &tokens.concat()
+        )
+        .ok()),
+    ]
+}
+
+/// Generates the encoded values of a valid header `H`.
+pub fn encoded<H: Header + Arbitrary>() -> impl Strategy<Value = Vec<HeaderValue>> {
+    any::<H>().prop_map(|header| {
+        let mut values = Vec::new();
+        header.encode(&mut values);
+        values
+    })
+}
+
+fn ident() -> impl Strategy<Value = String> + Clone {
+    "[A-Za-z][A-Za-z0-9_-]{0,11}"
+}
+
+/// A CSS selector.
+fn selector() -> impl Strategy<Value = String> + Clone {
+    prop_oneof![
+        ident().prop_map(|id| format!("#{id}")),
+        ident().prop_map(|class| format!(".{class}")),
+        "[a-z][a-z0-9]{0,7}",
+        ("[a-z][a-z0-9]{0,7}", ident()).prop_map(|(tag, class)| format!("{tag}.{class} > {tag}")),
+    ]
+}
+
+/// An element in format `tag#id`, where `#id` is optional.
+fn element() -> impl Strategy<Value = String> {
+    ("[a-z][a-z0-9]{0,7}", option::of(ident())).prop_map(|(tag, id)| match id {
+        Some(id) => format!("{tag}#{id}"),
+        None => tag,
+    })
+}
+
+fn uri() -> impl Strategy<Value = Uri> + Clone {
+    let path = "(/[a-z0-9_-]{0,8}){1,3}(\\?[a-z]{1,5}=[a-z0-9]{0,5})?";
+
+    prop_oneof![
+        path.prop_map(|path| path.to_string()),
+        ("https?", "[a-z]{1,8}\\.(com|org|dev)", path)
+            .prop_map(|(scheme, host, path)| format!("{scheme}://{host}{path}")),
+    ]
+    .prop_filter_map("not a URI", |uri| uri.parse().ok())
+}
+
+/// JSON without floats (which may not round trip) and without `null` (which decodes as no data).
+fn json() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        any::<bool>().prop_map(Value::from),
+        any::<i64>().prop_map(Value::from),
+        "[ -~]{0,8}".prop_map(Value::from),
+    ];
+
+    leaf.prop_recursive(2, 8, 4, |inner| {
+        prop_oneof![
+            vec(inner.clone(), 0..4).prop_map(Value::from),
+            btree_map("[a-z]{1,6}", inner, 0..4)
+                .prop_map(|map| Value::Object(map.into_iter().collect())),
+        ]
+    })
+}
+
+fn header_map() -> impl Strategy<Value = HeaderMap> {
+    vec(("x-[a-z]{1,8}", "[!-~]{1,12}"), 0..4).prop_map(|headers| {
+        headers
+            .into_iter()
+            .filter_map(|(name, value)| {
+                Some((
+                    HeaderName::from_bytes(name.as_bytes()).ok()?,
+                    HeaderValue::from_str(&value).ok()?,
+                ))
+            })
+            .collect()
+    })
+}
+
+/// Events with unique identifier names, sorted by name. The JSON form of [`HxTrigger`] is an object sorted by name and
+/// the comma separated form cannot hold every name, so these are the events that round trip through either form.
+fn events() -> impl Strategy<Value = Vec<(String, Option<Value>)>> {
+    btree_map(ident(), option::of(json()), 0..5).prop_map(|events| events.into_iter().collect())
+}
+
+macro_rules! impl_arbitrary {
+    ($($ty:ty => $strategy:expr),* $(,)?) => {
+        $(
+            impl Arbitrary for $ty {
+                type Parameters = ();
+                type Strategy = BoxedStrategy<Self>;
+
+                fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+                    $strategy.boxed()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_arbitrary_trigger {
+    ($($ty:ty),* $(,)?) => {
+        impl_arbitrary!(
+            $(
+                $ty => events().prop_map(|events| {
+                    let mut trigger = <$ty>::with_capacity(events.len());
+                    for (name, data) in events {
+                        match data {
+                            Some(data) => trigger.push_event_data(name, data),
+                            None => trigger.push_event(name),
+                        };
+                    }
+                    trigger
+                }),
+            )*
+        );
+    };
+}
+
+impl_arbitrary!(
+    HxBoosted => Just(HxBoosted),
+    HxCurrentUrl => uri().prop_map(HxCurrentUrl::from),
+    HxHistoryRestoreRequest => Just(HxHistoryRestoreRequest),
+    HxRequest => Just(HxRequest),
+    HxRequestType => prop_oneof![Just(HxRequestType::Partial), Just(HxRequestType::Full)],
+    HxSource => element().prop_filter_map("not a header value", HxSource::from_string),
+    HxTarget => element().prop_filter_map("not a header value", HxTarget::from_string),
+    HxLocation => (
+        uri(),
+        option::of(selector()),
+        option::of(selector()),
+        option::of(any::<HxLocationSwapOption>()),
+        option::of(selector()),
+        option::of(any::<bool>()),
+        option::of(header_map()),
+        option::of(json()),
+    )
+        .prop_map(|(path, target, source, swap, select, push, headers, values)| HxLocation {
+            path,
+            target,
+            source,
+            swap,
+            select,
+            push,
+            headers,
+            values,
+        }),
+    HxLocationSwapOption => select(vec![
+        HxLocationSwapOption::InnerHtml,
+        HxLocationSwapOption::OuterHtml,
+        HxLocationSwapOption::InnerMorph,
+        HxLocationSwapOption::OuterMorph,
+        HxLocationSwapOption::TextContent,
+        HxLocationSwapOption::BeforeBegin,
+        HxLocationSwapOption::AfterBegin,
+        HxLocationSwapOption::BeforeEnd,
+        HxLocationSwapOption::AfterEnd,
+        HxLocationSwapOption::Delete,
+        HxLocationSwapOption::None,
+        HxLocationSwapOption::Upsert,
+    ]),
+    HxPushUrl => prop_oneof![Just(HxPushUrl::True), Just(HxPushUrl::False), uri().prop_map(HxPushUrl::Uri)],
+    HxRedirect => uri().prop_map(HxRedirect),
+    HxRefresh => Just(HxRefresh),
+    HxReplaceUrl => prop_oneof![
+        Just(HxReplaceUrl::True),
+        Just(HxReplaceUrl::False),
+        uri().prop_map(HxReplaceUrl::Uri),
+    ],
+    HxReselect => selector().prop_filter_map("not a header value", HxReselect::from_string),
+    HxReswap => select(vec![
+        HxReswap::InnerHtml,
+        HxReswap::OuterHtml,
+        HxReswap::InnerMorph,
+        HxReswap::OuterMorph,
+        HxReswap::TextContent,
+        HxReswap::BeforeBegin,
+        HxReswap::AfterBegin,
+        HxReswap::BeforeEnd,
+        HxReswap::AfterEnd,
+        HxReswap::Delete,
+        HxReswap::None,
+        HxReswap::Upsert,
+    ]),
+    HxRetarget => selector().prop_filter_map("not a header value", HxRetarget::from_string),
+);
+
+impl_arbitrary_trigger!(HxTrigger, HxTriggerAfterSettle, HxTriggerAfterSwap);
@@ -1,14 +1,27 @@
 //! HTMX request headers.
 mod hx_boosted;
+mod hx_context;
 mod hx_current_url;
+pub(crate) mod hx_header;
 mod hx_history_restore_request;
 mod hx_request;
+mod hx_request_header;
+mod hx_request_kind;
 mod hx_request_type;
 mod hx_source;
 mod hx_target;
 
 pub use self::{
-    hx_boosted::HxBoosted, hx_current_url::HxCurrentUrl,
-    hx_history_restore_request::HxHistoryRestoreRequest, hx_request::HxRequest,
-    hx_request_type::HxRequestType, hx_source::HxSource, hx_target::HxTarget,
+    hx_boosted::HxBoosted,
+    hx_current_url::HxCurrentUrl,
+    hx_header::{HtmxRequestExt, HxHeader},
+    hx_history_restore_request::HxHistoryRestoreRequest,
+    hx_request::HxRequest,
+    hx_request_header::HxRequestHeader,
+    hx_request_kind::HxRequestKind,
+    hx_request_type::HxRequestType,
+    hx_source::HxSource,
+    hx_target::HxTarget,
 };
+
+pub(crate) use self::hx_context::HxContext;
@@ -1,3 +1,5 @@
+use std::iter::once;
+
 #[cfg(feature = "axum")]
 use axum_core::extract::{FromRequestParts, OptionalFromRequestParts};
 #[cfg(feature = "axum")]
@@ -6,8 +8,9 @@ use headers_core::{Error, Header, HeaderName, HeaderValue};
 #[cfg(feature = "axum")]
 use http::request::Parts;
 
-#[cfg(all(feature = "axum", feature = "auto-vary"))]
-use crate::auto_vary::{HxAutoVaryAdd, HxRequestHeader};
+#[cfg(feature = "axum")]
+use crate::request::hx_header::decode_cached;
+use crate::request::{HxHeader, HxRequestHeader, hx_header::sealed::Sealed};
 use crate::util::iter::IterExt;
 
 static HX_BOOSTED: HeaderName = HeaderName::from_static("hx-boosted");
@@ -25,8 +28,9 @@ where
     type Rejection = <TypedHeader<Self> as FromRequestParts<S>>::Rejection;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        #[cfg(feature = "auto-vary")]
-        parts.hx_auto_vary_add(HxRequestHeader::Boosted);
+        if let Ok(Some(header)) = decode_cached(parts) {
+            return Ok(header);
+        }
 
         <TypedHeader<Self> as FromRequestParts<S>>::from_request_parts(parts, state)
             .await
@@ -46,8 +50,9 @@ where
         parts: &mut Parts,
         state: &S,
     ) -> Result<Option<Self>, Self::Rejection> {
-        #[cfg(feature = "auto-vary")]
-        parts.hx_auto_vary_add(HxRequestHeader::Boosted);
+        if let Ok(header) = decode_cached(parts) {
+            return Ok(header);
+        }
 
         <TypedHeader<Self> as OptionalFromRequestParts<S>>::from_request_parts(parts, state)
             .await
@@ -71,8 +76,13 @@ impl Header for HxBoosted {
             .ok_or_else(Error::invalid)
     }
 
-    fn encode<E: Extend<HeaderValue>>(&self, _: &mut E) {
-        // This is a request header, so encoding it is not valid.
-        // Do nothing
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(once(HeaderValue::from_static("true")));
     }
 }
+
+impl HxHeader for HxBoosted {
+    const KIND: HxRequestHeader = HxRequestHeader::Boosted;
+}
+
+impl Sealed for HxBoosted {}
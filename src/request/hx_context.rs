@@ -0,0 +1,98 @@
+use std::{any::Any, sync::OnceLock};
+
+use headers_core::{Error, Header};
+use http::HeaderMap;
+
+use crate::request::{
+    HxBoosted, HxCurrentUrl, HxHeader, HxHistoryRestoreRequest, HxRequest, HxRequestHeader,
+    HxRequestKind, HxRequestType, HxSource, HxTarget, hx_header::decode_untracked,
+};
+
+/// The HTMX request headers of a request, each decoded at most once.
+///
+/// The first extractor (or [`HtmxLayer`](crate::HtmxLayer)) that reads an HTMX header stores an empty `HxContext` in
+/// the request extensions. Headers are decoded on first use, and later extractors and middleware reuse the decoded
+/// values. Changes made to the HTMX headers of a request after they are decoded are not reflected in the context.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HxContext {
+    boosted: OnceLock<Slot<HxBoosted>>,
+    current_url: OnceLock<Slot<HxCurrentUrl>>,
+    history_restore_request: OnceLock<Slot<HxHistoryRestoreRequest>>,
+    request: OnceLock<Slot<HxRequest>>,
+    request_type: OnceLock<Slot<HxRequestType>>,
+    source: OnceLock<Slot<HxSource>>,
+    target: OnceLock<Slot<HxTarget>>,
+    kind: OnceLock<HxRequestKind>,
+}
+
+impl HxContext {
+    /// Returns the header `H`, decoding it from `headers` if it was not decoded yet.
+    pub(crate) fn get<H: HxHeader>(&self, headers: &HeaderMap) -> Result<Option<H>, Error> {
+        let cell: &dyn Any = match H::KIND {
+            HxRequestHeader::Boosted => &self.boosted,
+            HxRequestHeader::CurrentUrl => &self.current_url,
+            HxRequestHeader::HistoryRestoreRequest => &self.history_restore_request,
+            HxRequestHeader::Request => &self.request,
+            HxRequestHeader::RequestType => &self.request_type,
+            HxRequestHeader::Source => &self.source,
+            HxRequestHeader::Target => &self.target,
+        };
+
+        match cell.downcast_ref::<OnceLock<Slot<H>>>() {
+            Some(cell) => cell.get_or_init(|| Slot::decode(headers)).get(),
+            // `H` is not the built-in type for `H::KIND`, so it is not cached.
+            None => decode_untracked(headers),
+        }
+    }
+
+    /// Classifies the request, if it was not classified yet. See [`HxRequestKind`] for the precedence rules.
+    pub(crate) fn kind(&self, headers: &HeaderMap) -> HxRequestKind {
+        *self.kind.get_or_init(|| {
+            if !Self::present(&self.request, headers) {
+                HxRequestKind::Page
+            } else if Self::present(&self.history_restore_request, headers) {
+                HxRequestKind::HistoryRestore
+            } else if Self::present(&self.boosted, headers) {
+                HxRequestKind::Boosted
+            } else if let Slot::Present(HxRequestType::Full) =
+                self.request_type.get_or_init(|| Slot::decode(headers))
+            {
+                HxRequestKind::Full
+            } else {
+                HxRequestKind::Partial
+            }
+        })
+    }
+
+    fn present<H: Header>(cell: &OnceLock<Slot<H>>, headers: &HeaderMap) -> bool {
+        matches!(cell.get_or_init(|| Slot::decode(headers)), Slot::Present(_))
+    }
+}
+
+/// The result of decoding a single header.
+#[derive(Debug, Clone)]
+enum Slot<H> {
+    Missing,
+    Invalid,
+    Present(H),
+}
+
+impl<H: Header> Slot<H> {
+    fn decode(headers: &HeaderMap) -> Self {
+        match decode_untracked(headers) {
+            Ok(Some(header)) => Slot::Present(header),
+            Ok(None) => Slot::Missing,
+            Err(_) => Slot::Invalid,
+        }
+    }
+}
+
+impl<H: Clone> Slot<H> {
+    fn get(&self) -> Result<Option<H>, Error> {
+        match self {
+            Slot::Missing => Ok(None),
+            Slot::Invalid => Err(Error::invalid()),
+            Slot::Present(header) => Ok(Some(header.clone())),
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    iter::once,
+    ops::{Deref, DerefMut},
+};
 
 #[cfg(feature = "axum")]
 use axum_core::extract::{FromRequestParts, OptionalFromRequestParts};
@@ -9,8 +12,9 @@ use headers_core::{Error, Header, HeaderName};
 use http::request::Parts;
 use http::{HeaderValue, Uri};
 
-#[cfg(all(feature = "axum", feature = "auto-vary"))]
-use crate::auto_vary::{HxAutoVaryAdd, HxRequestHeader};
+#[cfg(feature = "axum")]
+use crate::request::hx_header::decode_cached;
+use crate::request::{HxHeader, HxRequestHeader, hx_header::sealed::Sealed};
 use crate::util::{iter::IterExt, uri::UriExt};
 
 static HX_CURRENT_URL: HeaderName = HeaderName::from_static("hx-current-url");
@@ -61,8 +65,9 @@ where
     type Rejection = <TypedHeader<Self> as FromRequestParts<S>>::Rejection;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        #[cfg(feature = "auto-vary")]
-        parts.hx_auto_vary_add(HxRequestHeader::CurrentUrl);
+        if let Ok(Some(header)) = decode_cached(parts) {
+            return Ok(header);
+        }
 
         <TypedHeader<Self> as FromRequestParts<S>>::from_request_parts(parts, state)
             .await
@@ -82,8 +87,9 @@ where
         parts: &mut Parts,
         state: &S,
     ) -> Result<Option<Self>, Self::Rejection> {
-        #[cfg(feature = "auto-vary")]
-        parts.hx_auto_vary_add(HxRequestHeader::CurrentUrl);
+        if let Ok(header) = decode_cached(parts) {
+            return Ok(header);
+        }
 
         <TypedHeader<Self> as OptionalFromRequestParts<S>>::from_request_parts(parts, state)
             .await
@@ -108,8 +114,15 @@ impl Header for HxCurrentUrl {
             .map(Self)
     }
 
-    fn encode<E: Extend<HeaderValue>>(&self, _: &mut E) {
-        // This is a request header, so encoding it is not valid.
-        // Do nothing
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        if let Some(value) = HeaderValue::from_uri(&self.0) {
+            values.extend(once(value));
+        }
     }
 }
+
+impl HxHeader for HxCurrentUrl {
+    const KIND: HxRequestHeader = HxRequestHeader::CurrentUrl;
+}
+
+impl Sealed for HxCurrentUrl {}
@@ -0,0 +1,147 @@
+use headers_core::{Error, Header};
+use http::{Extensions, HeaderMap, Request, request::Parts};
+
+#[cfg(feature = "auto-vary")]
+use crate::HxAutoVaryExt;
+use crate::request::{
+    HxBoosted, HxContext, HxCurrentUrl, HxHistoryRestoreRequest, HxRequest, HxRequestHeader,
+    HxRequestKind, HxRequestType, HxSource, HxTarget,
+};
+
+/// An HTMX request header that can be read using [`HtmxRequestExt`].
+///
+/// This trait is sealed and implemented for the built-in HTMX request headers only.
+pub trait HxHeader: Header + Clone + Send + Sync + 'static + sealed::Sealed {
+    /// The built-in HTMX request header this type decodes.
+    const KIND: HxRequestHeader;
+}
+
+pub(crate) mod sealed {
+    pub trait Sealed {}
+}
+
+/// Extension trait to read HTMX request headers from `http::Request` and `http::request::Parts` without `axum`.
+///
+/// Reading a header with this trait records it for [`HxAutoVary`](crate::HxAutoVary) (when the `auto-vary` feature
+/// is enabled), exactly like the `axum` extractors do. This makes auto vary usable with plain `tower` or `hyper`
+/// services.
+///
+/// ```rust
+/// use htmxtools::request::{HtmxRequestExt, HxTarget};
+/// use http::Request;
+///
+/// let request = Request::builder()
+///     .header("hx-target", "div#results")
+///     .body(())
+///     .unwrap();
+///
+/// let target = request.hx_header::<HxTarget>().unwrap().unwrap();
+/// assert_eq!(target.id(), Some("results"));
+///
+/// assert!(!request.is_htmx());
+/// assert_eq!(request.hx_target().map(|target| target.tag().to_string()), Some("div".to_string()));
+/// ```
+pub trait HtmxRequestExt {
+    /// Decodes the HTMX request header `H` and records it for auto vary.
+    ///
+    /// Returns `Ok(None)` if the header is not present and an error if it is present but invalid. The header is
+    /// recorded in both cases, as the response depends on its absence too.
+    fn hx_header<H: HxHeader>(&self) -> Result<Option<H>, Error>;
+
+    /// Classifies the request from its HTMX headers. See [`HxRequestKind`] for the precedence rules.
+    ///
+    /// Only the headers consulted to classify the request are recorded for auto vary.
+    fn hx_request_kind(&self) -> HxRequestKind;
+
+    /// Returns `true` if the request was made by htmx, i.e. it has the `hx-request` header.
+    fn is_htmx(&self) -> bool {
+        matches!(self.hx_header::<HxRequest>(), Ok(Some(_)))
+    }
+
+    /// Returns `true` if the request was made by an element using `hx-boost`.
+    fn is_boosted(&self) -> bool {
+        matches!(self.hx_header::<HxBoosted>(), Ok(Some(_)))
+    }
+
+    /// Returns `true` if the request is for history restoration after a miss in the local history cache.
+    fn is_history_restore_request(&self) -> bool {
+        matches!(self.hx_header::<HxHistoryRestoreRequest>(), Ok(Some(_)))
+    }
+
+    /// Returns the current URL of the browser, or `None` if the header is missing or invalid.
+    fn hx_current_url(&self) -> Option<HxCurrentUrl> {
+        self.hx_header().ok().flatten()
+    }
+
+    /// Returns the type of the request, or `None` if the header is missing or invalid.
+    fn hx_request_type(&self) -> Option<HxRequestType> {
+        self.hx_header().ok().flatten()
+    }
+
+    /// Returns the triggering element, or `None` if the header is missing or invalid.
+    fn hx_source(&self) -> Option<HxSource> {
+        self.hx_header().ok().flatten()
+    }
+
+    /// Returns the target element, or `None` if the header is missing or invalid.
+    fn hx_target(&self) -> Option<HxTarget> {
+        self.hx_header().ok().flatten()
+    }
+}
+
+impl<B> HtmxRequestExt for Request<B> {
+    fn hx_header<H: HxHeader>(&self) -> Result<Option<H>, Error> {
+        decode(self.headers(), self.extensions())
+    }
+
+    fn hx_request_kind(&self) -> HxRequestKind {
+        HxRequestKind::classify(self.headers(), self.extensions())
+    }
+}
+
+impl HtmxRequestExt for Parts {
+    fn hx_header<H: HxHeader>(&self) -> Result<Option<H>, Error> {
+        decode(&self.headers, &self.extensions)
+    }
+
+    fn hx_request_kind(&self) -> HxRequestKind {
+        HxRequestKind::classify(&self.headers, &self.extensions)
+    }
+}
+
+#[cfg_attr(not(feature = "auto-vary"), allow(unused_variables))]
+pub(crate) fn decode<H: HxHeader>(
+    headers: &HeaderMap,
+    extensions: &Extensions,
+) -> Result<Option<H>, Error> {
+    #[cfg(feature = "auto-vary")]
+    extensions.hx_auto_vary_add(H::KIND);
+
+    match extensions.get::<HxContext>() {
+        Some(context) => context.get(headers),
+        None => decode_untracked(headers),
+    }
+}
+
+/// Decodes the header `H` through the [`HxContext`] in the request extensions, storing a new context first if there is
+/// none. The header is recorded for auto vary.
+#[cfg(feature = "axum")]
+pub(crate) fn decode_cached<H: HxHeader>(parts: &mut Parts) -> Result<Option<H>, Error> {
+    #[cfg(feature = "auto-vary")]
+    parts.hx_auto_vary_add(H::KIND);
+
+    parts
+        .extensions
+        .get_or_insert_with(HxContext::default)
+        .get(&parts.headers)
+}
+
+pub(crate) fn decode_untracked<H: Header>(headers: &HeaderMap) -> Result<Option<H>, Error> {
+    let mut values = headers.get_all(H::name()).iter();
+
+    if values.size_hint() == (0, Some(0)) {
+        Ok(None)
+    } else {
+        H::decode(&mut values).map(Some)
+    }
+}
@@ -1,3 +1,5 @@
+use std::iter::once;
+
 #[cfg(feature = "axum")]
 use axum_core::extract::{FromRequestParts, OptionalFromRequestParts};
 #[cfg(feature = "axum")]
@@ -6,8 +8,9 @@ use headers_core::{Error, Header, HeaderName, HeaderValue};
 #[cfg(feature = "axum")]
 use http::request::Parts;
 
-#[cfg(all(feature = "axum", feature = "auto-vary"))]
-use crate::auto_vary::{HxAutoVaryAdd, HxRequestHeader};
+#[cfg(feature = "axum")]
+use crate::request::hx_header::decode_cached;
+use crate::request::{HxHeader, HxRequestHeader, hx_header::sealed::Sealed};
 use crate::util::iter::IterExt;
 
 static HX_HISTORY_RESTORE_REQUEST: HeaderName =
@@ -26,8 +29,9 @@ where
     type Rejection = <TypedHeader<Self> as FromRequestParts<S>>::Rejection;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        #[cfg(feature = "auto-vary")]
-        parts.hx_auto_vary_add(HxRequestHeader::HistoryRestoreRequest);
+        if let Ok(Some(header)) = decode_cached(parts) {
+            return Ok(header);
+        }
 
         <TypedHeader<Self> as FromRequestParts<S>>::from_request_parts(parts, state)
             .await
@@ -47,8 +51,9 @@ where
         parts: &mut Parts,
         state: &S,
     ) -> Result<Option<Self>, Self::Rejection> {
-        #[cfg(feature = "auto-vary")]
-        parts.hx_auto_vary_add(HxRequestHeader::HistoryRestoreRequest);
+        if let Ok(header) = decode_cached(parts) {
+            return Ok(header);
+        }
 
         <TypedHeader<Self> as OptionalFromRequestParts<S>>::from_request_parts(parts, state)
             .await
@@ -72,8 +77,13 @@ impl Header for HxHistoryRestoreRequest {
             .ok_or_else(Error::invalid)
     }
 
-    fn encode<E: Extend<HeaderValue>>(&self, _: &mut E) {
-        // This is a request header, so encoding it is not valid.
-        // Do nothing
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(once(HeaderValue::from_static("true")));
     }
 }
+
+impl HxHeader for HxHistoryRestoreRequest {
+    const KIND: HxRequestHeader = HxRequestHeader::HistoryRestoreRequest;
+}
+
+impl Sealed for HxHistoryRestoreRequest {}
@@ -0,0 +1,83 @@
+use headers_core::{Header, HeaderName};
+
+use crate::request::{
+    HxBoosted, HxCurrentUrl, HxHistoryRestoreRequest, HxRequest, HxRequestType, HxSource, HxTarget,
+};
+
+/// A built-in HTMX request header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HxRequestHeader {
+    /// The `hx-boosted` header.
+    Boosted,
+
+    /// The `hx-current-url` header.
+    CurrentUrl,
+
+    /// The `hx-history-restore-request` header.
+    HistoryRestoreRequest,
+
+    /// The `hx-request` header.
+    Request,
+
+    /// The `hx-request-type` header.
+    RequestType,
+
+    /// The `hx-source` header.
+    Source,
+
+    /// The `hx-target` header.
+    Target,
+}
+
+impl HxRequestHeader {
+    /// Returns an iterator over all the built-in HTMX request headers.
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [
+            HxRequestHeader::Boosted,
+            HxRequestHeader::CurrentUrl,
+            HxRequestHeader::HistoryRestoreRequest,
+            HxRequestHeader::Request,
+            HxRequestHeader::RequestType,
+            HxRequestHeader::Source,
+            HxRequestHeader::Target,
+        ]
+        .into_iter()
+    }
+
+    /// Returns the name of the header.
+    pub fn name(self) -> &'static HeaderName {
+        match self {
+            HxRequestHeader::Boosted => HxBoosted::name(),
+            HxRequestHeader::CurrentUrl => HxCurrentUrl::name(),
+            HxRequestHeader::HistoryRestoreRequest => HxHistoryRestoreRequest::name(),
+            HxRequestHeader::Request => HxRequest::name(),
+            HxRequestHeader::RequestType => HxRequestType::name(),
+            HxRequestHeader::Source => HxSource::name(),
+            HxRequestHeader::Target => HxTarget::name(),
+        }
+    }
+
+    /// Returns the built-in HTMX request header with the given name, if there is one.
+    pub fn from_name(name: &HeaderName) -> Option<Self> {
+        Self::iter().find(|header| header.name() == name)
+    }
+
+    #[cfg_attr(not(feature = "auto-vary"), allow(dead_code))]
+    pub(crate) fn mask(self) -> u8 {
+        match self {
+            HxRequestHeader::Boosted => 1 << 0,
+            HxRequestHeader::CurrentUrl => 1 << 1,
+            HxRequestHeader::HistoryRestoreRequest => 1 << 2,
+            HxRequestHeader::Request => 1 << 3,
+            HxRequestHeader::RequestType => 1 << 4,
+            HxRequestHeader::Source => 1 << 5,
+            HxRequestHeader::Target => 1 << 6,
+        }
+    }
+}
+
+impl From<HxRequestHeader> for HeaderName {
+    fn from(header: HxRequestHeader) -> Self {
+        header.name().clone()
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(feature = "axum")]
+use std::convert::Infallible;
+
+#[cfg(feature = "axum")]
+use axum_core::extract::FromRequestParts;
+#[cfg(feature = "axum")]
+use http::request::Parts;
+use http::{Extensions, HeaderMap};
+
+use headers_core::Header;
+
+#[cfg(feature = "auto-vary")]
+use crate::HxAutoVaryExt;
+use crate::request::{
+    HxBoosted, HxContext, HxHistoryRestoreRequest, HxRequest, HxRequestHeader, HxRequestType,
+    hx_header::decode_untracked,
+};
+
+/// The kind of an incoming request, classified from its HTMX headers.
+///
+/// A request is classified using the following precedence rules:
+///
+/// 1. Requests without a valid `hx-request` header are [`HxRequestKind::Page`].
+/// 2. Requests with an `hx-history-restore-request` header are [`HxRequestKind::HistoryRestore`].
+/// 3. Requests with an `hx-boosted` header are [`HxRequestKind::Boosted`].
+/// 4. Requests with `hx-request-type: full` are [`HxRequestKind::Full`].
+/// 5. All other htmx requests are [`HxRequestKind::Partial`].
+///
+/// Invalid header values are treated as if the header was not present.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HxRequestKind {
+    /// A regular (non-htmx) request for a full page.
+    Page,
+
+    /// An htmx request to restore history after a miss in the local history cache. Expects a full page.
+    HistoryRestore,
+
+    /// An htmx request via an element using [`hx-boost`](https://four.htmx.org/attributes/hx-boost/). Expects a full
+    /// page.
+    Boosted,
+
+    /// An htmx request that targets the entire body element or uses `hx-select` to extract content.
+    Full,
+
+    /// An htmx request that targets a specific element on the page.
+    Partial,
+}
+
+impl HxRequestKind {
+    /// Returns `true` if the request expects a full page (i.e. it is not [`HxRequestKind::Partial`]).
+    pub fn is_full_page(self) -> bool {
+        !matches!(self, HxRequestKind::Partial)
+    }
+
+    /// Returns `true` if the request was made by htmx (i.e. it is not [`HxRequestKind::Page`]).
+    pub fn is_htmx(self) -> bool {
+        !matches!(self, HxRequestKind::Page)
+    }
+
+    /// Classifies a request from its headers.
+    ///
+    /// Unlike [`HtmxRequestExt::hx_request_kind`](crate::request::HtmxRequestExt::hx_request_kind), this does not
+    /// record the headers for auto vary.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        fn present<H: Header>(headers: &HeaderMap) -> bool {
+            matches!(decode_untracked::<H>(headers), Ok(Some(_)))
+        }
+
+        if !present::<HxRequest>(headers) {
+            HxRequestKind::Page
+        } else if present::<HxHistoryRestoreRequest>(headers) {
+            HxRequestKind::HistoryRestore
+        } else if present::<HxBoosted>(headers) {
+            HxRequestKind::Boosted
+        } else if let Ok(Some(HxRequestType::Full)) = decode_untracked(headers) {
+            HxRequestKind::Full
+        } else {
+            HxRequestKind::Partial
+        }
+    }
+
+    /// Returns the headers that were consulted to classify a request as this kind.
+    pub fn consulted_headers(self) -> &'static [HxRequestHeader] {
+        match self {
+            HxRequestKind::Page => &[HxRequestHeader::Request],
+            HxRequestKind::HistoryRestore => &[
+                HxRequestHeader::Request,
+                HxRequestHeader::HistoryRestoreRequest,
+            ],
+            HxRequestKind::Boosted => &[
+                HxRequestHeader::Request,
+                HxRequestHeader::HistoryRestoreRequest,
+                HxRequestHeader::Boosted,
+            ],
+            HxRequestKind::Full | HxRequestKind::Partial => &[
+                HxRequestHeader::Request,
+                HxRequestHeader::HistoryRestoreRequest,
+                HxRequestHeader::Boosted,
+                HxRequestHeader::RequestType,
+            ],
+        }
+    }
+
+    /// Classifies a request, reusing the [`HxContext`] stored in the request extensions if there is one. Nothing is
+    /// recorded for auto vary.
+    pub(crate) fn cached(headers: &HeaderMap, extensions: &Extensions) -> Self {
+        match extensions.get::<HxContext>() {
+            Some(context) => context.kind(headers),
+            None => Self::from_headers(headers),
+        }
+    }
+
+    /// Classifies a request like [`HxRequestKind::cached`] and records the consulted headers for auto vary.
+    pub(crate) fn classify(headers: &HeaderMap, extensions: &Extensions) -> Self {
+        let kind = Self::cached(headers, extensions);
+
+        #[cfg(feature = "auto-vary")]
+        for header in kind.consulted_headers() {
+            extensions.hx_auto_vary_add(*header);
+        }
+
+        kind
+    }
+}
+
+#[cfg(feature = "axum")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+impl<S> FromRequestParts<S> for HxRequestKind
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get_or_insert_with(HxContext::default);
+
+        Ok(Self::classify(&parts.headers, &parts.extensions))
+    }
+}
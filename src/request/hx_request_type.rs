@@ -1,3 +1,5 @@
+use std::iter::once;
+
 #[cfg(feature = "axum")]
 use axum_core::extract::{FromRequestParts, OptionalFromRequestParts};
 #[cfg(feature = "axum")]
@@ -6,8 +8,9 @@ use headers_core::{Error, Header, HeaderName, HeaderValue};
 #[cfg(feature = "axum")]
 use http::request::Parts;
 
-#[cfg(all(feature = "axum", feature = "auto-vary"))]
-use crate::auto_vary::{HxAutoVaryAdd, HxRequestHeader};
+#[cfg(feature = "axum")]
+use crate::request::hx_header::decode_cached;
+use crate::request::{HxHeader, HxRequestHeader, hx_header::sealed::Sealed};
 use crate::util::iter::IterExt;
 
 static HX_REQUEST_TYPE: HeaderName = HeaderName::from_static("hx-request-type");
@@ -32,8 +35,9 @@ where
     type Rejection = <TypedHeader<Self> as FromRequestParts<S>>::Rejection;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        #[cfg(feature = "auto-vary")]
-        parts.hx_auto_vary_add(HxRequestHeader::RequestType);
+        if let Ok(Some(header)) = decode_cached(parts) {
+            return Ok(header);
+        }
 
         <TypedHeader<Self> as FromRequestParts<S>>::from_request_parts(parts, state)
             .await
@@ -53,8 +57,9 @@ where
         parts: &mut Parts,
         state: &S,
     ) -> Result<Option<Self>, Self::Rejection> {
-        #[cfg(feature = "auto-vary")]
-        parts.hx_auto_vary_add(HxRequestHeader::RequestType);
+        if let Ok(header) = decode_cached(parts) {
+            return Ok(header);
+        }
 
         <TypedHeader<Self> as OptionalFromRequestParts<S>>::from_request_parts(parts, state)
             .await
@@ -86,8 +91,17 @@ impl Header for HxRequestType {
             .ok_or_else(Error::invalid)
     }
 
-    fn encode<E: Extend<HeaderValue>>(&self, _: &mut E) {
-        // This is a request header, so encoding it is not valid.
-        // Do nothing
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let value = match self {
+            Self::Partial => HeaderValue::from_static("partial"),
+            Self::Full => HeaderValue::from_static("full"),
+        };
+        values.extend(once(value));
     }
 }
+
+impl HxHeader for HxRequestType {
+    const KIND: HxRequestHeader = HxRequestHeader::RequestType;
+}
+
+impl Sealed for HxRequestType {}
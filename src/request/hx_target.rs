@@ -1,3 +1,5 @@
+use std::iter::once;
+
 #[cfg(feature = "axum")]
 use axum_core::extract::{FromRequestParts, OptionalFromRequestParts};
 #[cfg(feature = "axum")]
@@ -6,8 +8,9 @@ use headers_core::{Error, Header, HeaderName, HeaderValue};
 #[cfg(feature = "axum")]
 use http::request::Parts;
 
-#[cfg(all(feature = "axum", feature = "auto-vary"))]
-use crate::auto_vary::{HxAutoVaryAdd, HxRequestHeader};
+#[cfg(feature = "axum")]
+use crate::request::hx_header::decode_cached;
+use crate::request::{HxHeader, HxRequestHeader, hx_header::sealed::Sealed};
 use crate::util::{iter::IterExt, value_string::HeaderValueString};
 
 static HX_TARGET: HeaderName = HeaderName::from_static("hx-target");
@@ -17,22 +20,42 @@ static HX_TARGET: HeaderName = HeaderName::from_static("hx-target");
 pub struct HxTarget(HeaderValueString);
 
 impl HxTarget {
+    /// Create a new `HxTarget` from a static string.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the static string is not a legal header value.
+    pub const fn from_static(src: &'static str) -> Self {
+        Self(HeaderValueString::from_static(src))
+    }
+
+    /// Create a new `HxTarget` from a `&str`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(src: &str) -> Option<Self> {
+        HeaderValueString::from_str(src).map(Self)
+    }
+
+    /// Create a new `HxTarget` from a `String`.
+    pub fn from_string(src: String) -> Option<Self> {
+        HeaderValueString::from_string(src).map(Self)
+    }
+
+    /// Returns the identifier of the target element in format `tag#id`.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
     /// Returns the tag name of the target element.
     pub fn tag(&self) -> &str {
-        if self.0.as_str().contains('#') {
-            self.0.as_str().split_once('#').unwrap().0
-        } else {
-            self.0.as_str()
+        match self.0.as_str().split_once('#') {
+            Some((tag, _)) => tag,
+            None => self.0.as_str(),
         }
     }
 
     /// Returns the id of the target element if it exists.
     pub fn id(&self) -> Option<&str> {
-        if self.0.as_str().contains('#') {
-            Some(self.0.as_str().split_once('#').unwrap().1)
-        } else {
-            None
-        }
+        self.0.as_str().split_once('#').map(|(_, id)| id)
     }
 }
 
@@ -45,8 +68,9 @@ where
     type Rejection = <TypedHeader<Self> as FromRequestParts<S>>::Rejection;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        #[cfg(feature = "auto-vary")]
-        parts.hx_auto_vary_add(HxRequestHeader::Target);
+        if let Ok(Some(header)) = decode_cached(parts) {
+            return Ok(header);
+        }
 
         <TypedHeader<Self> as FromRequestParts<S>>::from_request_parts(parts, state)
             .await
@@ -66,8 +90,9 @@ where
         parts: &mut Parts,
         state: &S,
     ) -> Result<Option<Self>, Self::Rejection> {
-        #[cfg(feature = "auto-vary")]
-        parts.hx_auto_vary_add(HxRequestHeader::Target);
+        if let Ok(header) = decode_cached(parts) {
+            return Ok(header);
+        }
 
         <TypedHeader<Self> as OptionalFromRequestParts<S>>::from_request_parts(parts, state)
             .await
@@ -92,8 +117,13 @@ impl Header for HxTarget {
             .ok_or_else(Error::invalid)
     }
 
-    fn encode<E: Extend<HeaderValue>>(&self, _: &mut E) {
-        // This is a request header, so encoding it is not valid.
-        // Do nothing
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(once(self.0.as_header_value().clone()));
     }
 }
+
+impl HxHeader for HxTarget {
+    const KIND: HxRequestHeader = HxRequestHeader::Target;
+}
+
+impl Sealed for HxTarget {}
@@ -0,0 +1,168 @@
+//! Integration with `reqwest`, for calling htmx endpoints from Rust.
+//!
+//! - [`HxRequestBuilderExt`] attaches HTMX request headers to a `RequestBuilder`.
+//! - [`HxResponseExt`] parses the HTMX response headers of a `Response`.
+//!
+//! Both rely on the request headers encoding their values and the response headers decoding theirs, see the crate
+//! documentation.
+//!
+//! ```rust,no_run
+//! use htmxtools::{
+//!     request::HxTarget,
+//!     reqwest::{HxRequestBuilderExt, HxResponseExt},
+//! };
+//!
+//! async fn search(client: &reqwest::Client) -> reqwest::Result<()> {
+//!     let response = client
+//!         .get("http://localhost:3000/search")
+//!         .hx_request()
+//!         .hx_target(HxTarget::from_static("div#results"))
+//!         .send()
+//!         .await?;
+//!
+//!     if let Some(trigger) = response.hx_trigger() {
+//!         println!("Triggered events: {trigger:?}");
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+use headers_core::{Error, Header};
+use reqwest::{RequestBuilder, Response};
+
+use crate::{
+    request::{
+        HxBoosted, HxCurrentUrl, HxHistoryRestoreRequest, HxRequest, HxRequestType, HxSource,
+        HxTarget, hx_header::decode_untracked,
+    },
+    response::{
+        HxLocation, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl, HxReselect, HxReswap,
+        HxRetarget, HxTrigger, HxTriggerAfterSettle, HxTriggerAfterSwap,
+    },
+};
+
+/// Extension trait to attach HTMX request headers to a `reqwest::RequestBuilder`.
+pub trait HxRequestBuilderExt: Sized {
+    /// Appends the values of an HTMX request header.
+    fn hx_header<H: Header>(self, header: H) -> Self;
+
+    /// Adds the `hx-boosted` header.
+    fn hx_boosted(self) -> Self {
+        self.hx_header(HxBoosted)
+    }
+
+    /// Adds the `hx-current-url` header.
+    fn hx_current_url(self, current_url: HxCurrentUrl) -> Self {
+        self.hx_header(current_url)
+    }
+
+    /// Adds the `hx-history-restore-request` header.
+    fn hx_history_restore_request(self) -> Self {
+        self.hx_header(HxHistoryRestoreRequest)
+    }
+
+    /// Adds the `hx-request` header.
+    fn hx_request(self) -> Self {
+        self.hx_header(HxRequest)
+    }
+
+    /// Adds the `hx-request-type` header.
+    fn hx_request_type(self, request_type: HxRequestType) -> Self {
+        self.hx_header(request_type)
+    }
+
+    /// Adds the `hx-source` header.
+    fn hx_source(self, source: HxSource) -> Self {
+        self.hx_header(source)
+    }
+
+    /// Adds the `hx-target` header.
+    fn hx_target(self, target: HxTarget) -> Self {
+        self.hx_header(target)
+    }
+}
+
+impl HxRequestBuilderExt for RequestBuilder {
+    fn hx_header<H: Header>(mut self, header: H) -> Self {
+        let mut values = Vec::new();
+        header.encode(&mut values);
+
+        for value in values {
+            self = self.header(H::name(), value);
+        }
+
+        self
+    }
+}
+
+/// Extension trait to parse the HTMX response headers of a `reqwest::Response`.
+///
+/// The shortcut methods return `None` if the header is missing or invalid. Use [`HxResponseExt::hx_header`] to tell
+/// them apart.
+pub trait HxResponseExt {
+    /// Decodes the HTMX response header `H`.
+    ///
+    /// Returns `Ok(None)` if the header is not present and an error if it is present but invalid.
+    fn hx_header<H: Header>(&self) -> Result<Option<H>, Error>;
+
+    /// Returns the `hx-location` header.
+    fn hx_location(&self) -> Option<HxLocation> {
+        self.hx_header().ok().flatten()
+    }
+
+    /// Returns the `hx-push-url` header.
+    fn hx_push_url(&self) -> Option<HxPushUrl> {
+        self.hx_header().ok().flatten()
+    }
+
+    /// Returns the `hx-redirect` header.
+    fn hx_redirect(&self) -> Option<HxRedirect> {
+        self.hx_header().ok().flatten()
+    }
+
+    /// Returns `true` if the response has the `hx-refresh` header.
+    fn hx_refresh(&self) -> bool {
+        matches!(self.hx_header::<HxRefresh>(), Ok(Some(_)))
+    }
+
+    /// Returns the `hx-replace-url` header.
+    fn hx_replace_url(&self) -> Option<HxReplaceUrl> {
+        self.hx_header().ok().flatten()
+    }
+
+    /// Returns the `hx-reselect` header.
+    fn hx_reselect(&self) -> Option<HxReselect> {
+        self.hx_header().ok().flatten()
+    }
+
+    /// Returns the `hx-reswap` header.
+    fn hx_reswap(&self) -> Option<HxReswap> {
+        self.hx_header().ok().flatten()
+    }
+
+    /// Returns the `hx-retarget` header.
+    fn hx_retarget(&self) -> Option<HxRetarget> {
+        self.hx_header().ok().flatten()
+    }
+
+    /// Returns the `hx-trigger` header.
+    fn hx_trigger(&self) -> Option<HxTrigger> {
+        self.hx_header().ok().flatten()
+    }
+
+    /// Returns the `hx-trigger-after-settle` header.
+    fn hx_trigger_after_settle(&self) -> Option<HxTriggerAfterSettle> {
+        self.hx_header().ok().flatten()
+    }
+
+    /// Returns the `hx-trigger-after-swap` header.
+    fn hx_trigger_after_swap(&self) -> Option<HxTriggerAfterSwap> {
+        self.hx_header().ok().flatten()
+    }
+}
+
+impl HxResponseExt for Response {
+    fn hx_header<H: Header>(&self) -> Result<Option<H>, Error> {
+        decode_untracked(self.headers())
+    }
+}
@@ -1,5 +1,7 @@
 //! HTMX response headers.
+mod htmx_response_ext;
 mod hx_location;
+pub(crate) mod hx_page;
 mod hx_push_url;
 mod hx_redirect;
 mod hx_refresh;
@@ -12,7 +14,9 @@ mod hx_trigger_after_settle;
 mod hx_trigger_after_swap;
 
 pub use self::{
+    htmx_response_ext::HtmxResponseExt,
     hx_location::{HxLocation, HxLocationSwapOption},
+    hx_page::{HxPage, HxRender},
     hx_push_url::HxPushUrl,
     hx_redirect::HxRedirect,
     hx_refresh::HxRefresh,
@@ -0,0 +1,107 @@
+use headers_core::Header;
+use http::{HeaderMap, Response, response::Parts};
+
+use crate::response::{
+    HxLocation, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl, HxReselect, HxReswap, HxRetarget,
+    HxTrigger, HxTriggerAfterSettle, HxTriggerAfterSwap,
+};
+
+/// Extension trait to set HTMX response headers on `http::Response` and `http::response::Parts` without `axum`.
+///
+/// Every method replaces the existing values of its header, so calling it twice keeps only the last value.
+///
+/// ```rust
+/// use htmxtools::response::{HtmxResponseExt, HxReswap, HxTrigger};
+/// use http::Response;
+///
+/// let mut response = Response::new(());
+/// response
+///     .hx_trigger(HxTrigger::new().with_event("saved".to_string()))
+///     .hx_reswap(HxReswap::OuterHtml);
+///
+/// assert_eq!(response.headers()["hx-trigger"], "saved");
+/// assert_eq!(response.headers()["hx-reswap"], "outerHTML");
+/// ```
+pub trait HtmxResponseExt {
+    /// Sets an HTMX response header, replacing its existing values.
+    fn hx_header<H: Header>(&mut self, header: H) -> &mut Self;
+
+    /// Sets the `hx-location` header.
+    fn hx_location(&mut self, location: HxLocation) -> &mut Self {
+        self.hx_header(location)
+    }
+
+    /// Sets the `hx-push-url` header.
+    fn hx_push_url(&mut self, push_url: HxPushUrl) -> &mut Self {
+        self.hx_header(push_url)
+    }
+
+    /// Sets the `hx-redirect` header.
+    fn hx_redirect(&mut self, redirect: HxRedirect) -> &mut Self {
+        self.hx_header(redirect)
+    }
+
+    /// Sets the `hx-refresh` header.
+    fn hx_refresh(&mut self, refresh: HxRefresh) -> &mut Self {
+        self.hx_header(refresh)
+    }
+
+    /// Sets the `hx-replace-url` header.
+    fn hx_replace_url(&mut self, replace_url: HxReplaceUrl) -> &mut Self {
+        self.hx_header(replace_url)
+    }
+
+    /// Sets the `hx-reselect` header.
+    fn hx_reselect(&mut self, reselect: HxReselect) -> &mut Self {
+        self.hx_header(reselect)
+    }
+
+    /// Sets the `hx-reswap` header.
+    fn hx_reswap(&mut self, reswap: HxReswap) -> &mut Self {
+        self.hx_header(reswap)
+    }
+
+    /// Sets the `hx-retarget` header.
+    fn hx_retarget(&mut self, retarget: HxRetarget) -> &mut Self {
+        self.hx_header(retarget)
+    }
+
+    /// Sets the `hx-trigger` header.
+    fn hx_trigger(&mut self, trigger: HxTrigger) -> &mut Self {
+        self.hx_header(trigger)
+    }
+
+    /// Sets the `hx-trigger-after-settle` header.
+    fn hx_trigger_after_settle(&mut self, trigger: HxTriggerAfterSettle) -> &mut Self {
+        self.hx_header(trigger)
+    }
+
+    /// Sets the `hx-trigger-after-swap` header.
+    fn hx_trigger_after_swap(&mut self, trigger: HxTriggerAfterSwap) -> &mut Self {
+        self.hx_header(trigger)
+    }
+}
+
+impl<B> HtmxResponseExt for Response<B> {
+    fn hx_header<H: Header>(&mut self, header: H) -> &mut Self {
+        insert(self.headers_mut(), header);
+        self
+    }
+}
+
+impl HtmxResponseExt for Parts {
+    fn hx_header<H: Header>(&mut self, header: H) -> &mut Self {
+        insert(&mut self.headers, header);
+        self
+    }
+}
+
+fn insert<H: Header>(headers: &mut HeaderMap, header: H) {
+    let mut values = Vec::new();
+    header.encode(&mut values);
+
+    headers.remove(H::name());
+    for value in values {
+        headers.append(H::name(), value);
+    }
+}
@@ -6,14 +6,14 @@ use axum_core::response::{IntoResponse, IntoResponseParts, Response, ResponsePar
 use axum_extra::TypedHeader;
 use headers_core::{Error, Header};
 use http::{HeaderMap, HeaderName, HeaderValue, Uri};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::util::uri::UriExt;
+use crate::util::{iter::IterExt, uri::UriExt};
 
 static HX_LOCATION: HeaderName = HeaderName::from_static("hx-location");
 
 /// Allows you to do a client-side redirect that does not do a full page reload.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HxLocation {
     /// The URL to navigate to.
     #[serde(with = "http_serde::uri")]
@@ -40,7 +40,7 @@ pub struct HxLocation {
     pub push: Option<bool>,
 
     /// Extra request headers.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     #[serde(with = "http_serde::option::header_map")]
     pub headers: Option<HeaderMap>,
 
@@ -128,7 +128,7 @@ impl HxLocation {
 }
 
 /// Swap options for the `hx-location` header.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum HxLocationSwapOption {
     /// Replace the inner html of the target element.
     #[default]
@@ -212,13 +212,18 @@ impl Header for HxLocation {
         &HX_LOCATION
     }
 
-    fn decode<'i, I>(_: &mut I) -> Result<Self, Error>
+    fn decode<'i, I>(values: &mut I) -> Result<Self, Error>
     where
         Self: Sized,
         I: Iterator<Item = &'i HeaderValue>,
     {
-        // This is a response header, so decoding it is not valid.
-        Err(Error::invalid())
+        let value = values.just_one().ok_or_else(Error::invalid)?;
+
+        if value.as_bytes().starts_with(b"{") {
+            serde_json::from_slice(value.as_bytes()).map_err(|_| Error::invalid())
+        } else {
+            value.to_uri().map(Self::new)
+        }
     }
 
     fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
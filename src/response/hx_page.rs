@@ -0,0 +1,146 @@
+#[cfg(feature = "axum")]
+use axum_core::response::{IntoResponse, Response as AxumResponse};
+use http::{HeaderMap, Response};
+
+use crate::{request::HxRequestKind, util::header_list};
+
+/// A page that can be rendered either as a full page, with its layout, or as the fragment htmx swaps into the page.
+///
+/// This trait is template-agnostic: implement it for the data of a page and render it with any template engine.
+/// [`HxRender`] picks the rendering that matches the request.
+///
+/// ```rust
+/// use htmxtools::response::HxPage;
+///
+/// struct Items(Vec<String>);
+///
+/// impl Items {
+///     fn list(&self) -> String {
+///         let items: String = self.0.iter().map(|item| format!("<li>{item}</li>")).collect();
+///         format!(r#"<ul id="items">{items}</ul>"#)
+///     }
+/// }
+///
+/// impl HxPage for Items {
+///     type Output = String;
+///
+///     fn full(self) -> String {
+///         format!("<html><body><h1>Items</h1>{}</body></html>", self.list())
+///     }
+///
+///     fn partial(self) -> String {
+///         self.list()
+///     }
+/// }
+/// ```
+pub trait HxPage {
+    /// The rendered page, such as a `String` or a response.
+    type Output;
+
+    /// Renders the full page, with its layout.
+    fn full(self) -> Self::Output;
+
+    /// Renders only the fragment swapped by htmx.
+    fn partial(self) -> Self::Output;
+}
+
+/// Renders an [`HxPage`] as a full page or as a fragment, depending on the kind of the request, and adds the `Vary`
+/// header.
+///
+/// The page is rendered with [`HxPage::partial`] for [`HxRequestKind::Partial`] requests, and with [`HxPage::full`]
+/// for all other kinds. The kind follows the precedence rules of [`HxRequestKind`]:
+///
+/// 1. Requests without `hx-request` (regular page loads) render the full page.
+/// 2. History restore requests (`hx-history-restore-request`) render the full page, because htmx replaces the whole
+///    body with the response.
+/// 3. Boosted requests (`hx-boosted`) render the full page.
+/// 4. Requests with `hx-request-type: full` render the full page.
+/// 5. All other htmx requests render the fragment.
+///
+/// The response varies by the headers consulted to classify the request (see [`HxRequestKind::consulted_headers`]).
+/// Names already listed in `Vary`, for example by `HxAutoVaryLayer`, are not added again.
+///
+/// With the `axum` feature, `HxRender` implements `IntoResponse` when the rendered page does:
+///
+#[cfg_attr(feature = "axum", doc = "```rust,no_run")]
+#[cfg_attr(not(feature = "axum"), doc = "```rust,ignore")]
+/// use axum_core::response::IntoResponse;
+/// use htmxtools::{
+///     request::HxRequestKind,
+///     response::{HxPage, HxRender},
+/// };
+///
+/// struct Items(Vec<String>);
+///
+/// impl HxPage for Items {
+///     type Output = String;
+///
+///     fn full(self) -> String {
+///         format!("<html><body>{}</body></html>", self.partial())
+///     }
+///
+///     fn partial(self) -> String {
+///         self.0.iter().map(|item| format!("<li>{item}</li>")).collect()
+///     }
+/// }
+///
+/// async fn handler(kind: HxRequestKind) -> impl IntoResponse {
+///     HxRender::new(Items(vec!["Milk".to_string()]), kind)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HxRender<P> {
+    page: P,
+    kind: HxRequestKind,
+}
+
+impl<P: HxPage> HxRender<P> {
+    /// Creates a new `HxRender` for a request of the given kind.
+    pub fn new(page: P, kind: HxRequestKind) -> Self {
+        Self { page, kind }
+    }
+
+    /// Returns the kind of the request.
+    pub fn kind(&self) -> HxRequestKind {
+        self.kind
+    }
+
+    /// Renders the page, without adding the `Vary` header.
+    pub fn render(self) -> P::Output {
+        if self.kind.is_full_page() {
+            self.page.full()
+        } else {
+            self.page.partial()
+        }
+    }
+
+    /// Renders the page into an `http::Response` with the `Vary` header.
+    pub fn into_http(self) -> Response<P::Output> {
+        let kind = self.kind;
+        let mut response = Response::new(self.render());
+        add_vary(response.headers_mut(), kind);
+        response
+    }
+}
+
+#[cfg(feature = "axum")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+impl<P> IntoResponse for HxRender<P>
+where
+    P: HxPage,
+    P::Output: IntoResponse,
+{
+    fn into_response(self) -> AxumResponse {
+        let kind = self.kind;
+        let mut response = self.render().into_response();
+        add_vary(response.headers_mut(), kind);
+        response
+    }
+}
+
+/// Adds the headers consulted to classify a request of this kind to `Vary`.
+pub(crate) fn add_vary(headers: &mut HeaderMap, kind: HxRequestKind) {
+    for header in kind.consulted_headers() {
+        header_list::append_vary(headers, header.name());
+    }
+}
@@ -7,7 +7,7 @@ use axum_extra::TypedHeader;
 use headers_core::{Error, Header};
 use http::{HeaderName, HeaderValue, Uri};
 
-use crate::util::uri::UriExt;
+use crate::util::{iter::IterExt, uri::UriExt};
 
 const FALSE: HeaderValue = HeaderValue::from_static("false");
 const TRUE: HeaderValue = HeaderValue::from_static("true");
@@ -72,13 +72,20 @@ impl Header for HxPushUrl {
         &HX_PUSH_URL
     }
 
-    fn decode<'i, I>(_: &mut I) -> Result<Self, Error>
+    fn decode<'i, I>(values: &mut I) -> Result<Self, Error>
     where
         Self: Sized,
         I: Iterator<Item = &'i HeaderValue>,
     {
-        // This is a response header, so decoding it is not valid.
-        Err(Error::invalid())
+        let value = values.just_one().ok_or_else(Error::invalid)?;
+
+        if value == TRUE {
+            Ok(Self::True)
+        } else if value == FALSE {
+            Ok(Self::False)
+        } else {
+            value.to_uri().map(Self::Uri)
+        }
     }
 
     fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
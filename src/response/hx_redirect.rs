@@ -7,7 +7,7 @@ use axum_extra::TypedHeader;
 use headers_core::{Error, Header, HeaderName};
 use http::{HeaderValue, Uri};
 
-use crate::util::uri::UriExt;
+use crate::util::{iter::IterExt, uri::UriExt};
 
 static HX_REDIRECT: HeaderName = HeaderName::from_static("hx-redirect");
 
@@ -51,13 +51,16 @@ impl Header for HxRedirect {
         &HX_REDIRECT
     }
 
-    fn decode<'i, I>(_: &mut I) -> Result<Self, Error>
+    fn decode<'i, I>(values: &mut I) -> Result<Self, Error>
     where
         Self: Sized,
         I: Iterator<Item = &'i HeaderValue>,
     {
-        // This is a response header, so decoding it is not valid.
-        Err(Error::invalid())
+        values
+            .just_one()
+            .ok_or_else(Error::invalid)?
+            .to_uri()
+            .map(Self)
     }
 
     fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
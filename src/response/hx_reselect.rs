@@ -6,7 +6,7 @@ use axum_core::response::{IntoResponse, IntoResponseParts, Response, ResponsePar
 use axum_extra::TypedHeader;
 use headers_core::{Error, Header, HeaderName, HeaderValue};
 
-use crate::util::value_string::HeaderValueString;
+use crate::util::{iter::IterExt, value_string::HeaderValueString};
 
 static HX_RESELECT: HeaderName = HeaderName::from_static("hx-reselect");
 
@@ -35,6 +35,11 @@ impl HxReselect {
     pub fn from_string(src: String) -> Option<Self> {
         HeaderValueString::from_string(src).map(Self)
     }
+
+    /// Returns the CSS selector.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
 }
 
 #[cfg(feature = "axum")]
@@ -60,13 +65,16 @@ impl Header for HxReselect {
         &HX_RESELECT
     }
 
-    fn decode<'i, I>(_: &mut I) -> Result<Self, Error>
+    fn decode<'i, I>(values: &mut I) -> Result<Self, Error>
     where
         Self: Sized,
         I: Iterator<Item = &'i HeaderValue>,
     {
-        // This is a response header, so decoding it is not valid.
-        Err(Error::invalid())
+        values
+            .just_one()
+            .map(|value| HeaderValueString::try_from_header_value(value).map(Self))
+            .transpose()?
+            .ok_or_else(Error::invalid)
     }
 
     fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
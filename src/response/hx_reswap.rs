@@ -7,18 +7,7 @@ use axum_extra::TypedHeader;
 use headers_core::{Error, Header};
 use http::{HeaderName, HeaderValue};
 
-const INNER_HTML: HeaderValue = HeaderValue::from_static("innerHTML");
-const OUTER_HTML: HeaderValue = HeaderValue::from_static("outerHTML");
-const INNER_MORPH: HeaderValue = HeaderValue::from_static("innerMorph");
-const OUTER_MORPH: HeaderValue = HeaderValue::from_static("outerMorph");
-const TEXT_CONTENT: HeaderValue = HeaderValue::from_static("textContent");
-const BEFORE_BEGIN: HeaderValue = HeaderValue::from_static("beforebegin");
-const AFTER_BEGIN: HeaderValue = HeaderValue::from_static("afterbegin");
-const BEFORE_END: HeaderValue = HeaderValue::from_static("beforeend");
-const AFTER_END: HeaderValue = HeaderValue::from_static("afterend");
-const DELETE: HeaderValue = HeaderValue::from_static("delete");
-const NONE: HeaderValue = HeaderValue::from_static("none");
-const UPSERT: HeaderValue = HeaderValue::from_static("upsert");
+use crate::util::iter::IterExt;
 
 static HX_RESWAP: HeaderName = HeaderName::from_static("hx-reswap");
 
@@ -66,20 +55,21 @@ pub enum HxReswap {
 }
 
 impl HxReswap {
-    fn to_header_value(self) -> HeaderValue {
+    /// Returns the swap strategy as used in the header and in the `hx-swap` attribute.
+    pub fn as_str(self) -> &'static str {
         match self {
-            HxReswap::InnerHtml => INNER_HTML,
-            HxReswap::OuterHtml => OUTER_HTML,
-            HxReswap::InnerMorph => INNER_MORPH,
-            HxReswap::OuterMorph => OUTER_MORPH,
-            HxReswap::TextContent => TEXT_CONTENT,
-            HxReswap::BeforeBegin => BEFORE_BEGIN,
-            HxReswap::AfterBegin => AFTER_BEGIN,
-            HxReswap::BeforeEnd => BEFORE_END,
-            HxReswap::AfterEnd => AFTER_END,
-            HxReswap::Delete => DELETE,
-            HxReswap::None => NONE,
-            HxReswap::Upsert => UPSERT,
+            HxReswap::InnerHtml => "innerHTML",
+            HxReswap::OuterHtml => "outerHTML",
+            HxReswap::InnerMorph => "innerMorph",
+            HxReswap::OuterMorph => "outerMorph",
+            HxReswap::TextContent => "textContent",
+            HxReswap::BeforeBegin => "beforebegin",
+            HxReswap::AfterBegin => "afterbegin",
+            HxReswap::BeforeEnd => "beforeend",
+            HxReswap::AfterEnd => "afterend",
+            HxReswap::Delete => "delete",
+            HxReswap::None => "none",
+            HxReswap::Upsert => "upsert",
         }
     }
 }
@@ -107,16 +97,33 @@ impl Header for HxReswap {
         &HX_RESWAP
     }
 
-    fn decode<'i, I>(_: &mut I) -> Result<Self, Error>
+    fn decode<'i, I>(values: &mut I) -> Result<Self, Error>
     where
         Self: Sized,
         I: Iterator<Item = &'i HeaderValue>,
     {
-        // This is a response header, so decoding it is not valid.
-        Err(Error::invalid())
+        let value = values.just_one().ok_or_else(Error::invalid)?;
+
+        [
+            HxReswap::InnerHtml,
+            HxReswap::OuterHtml,
+            HxReswap::InnerMorph,
+            HxReswap::OuterMorph,
+            HxReswap::TextContent,
+            HxReswap::BeforeBegin,
+            HxReswap::AfterBegin,
+            HxReswap::BeforeEnd,
+            HxReswap::AfterEnd,
+            HxReswap::Delete,
+            HxReswap::None,
+            HxReswap::Upsert,
+        ]
+        .into_iter()
+        .find(|swap| value == swap.as_str())
+        .ok_or_else(Error::invalid)
     }
 
     fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
-        values.extend(once(self.to_header_value()));
+        values.extend(once(HeaderValue::from_static(self.as_str())));
     }
 }
@@ -8,6 +8,8 @@ use headers_core::{Error, Header};
 use http::{HeaderName, HeaderValue};
 use serde_json::Value;
 
+use crate::util::iter::IterExt;
+
 static HX_TRIGGER: HeaderName = HeaderName::from_static("hx-trigger");
 
 /// Allows you to trigger client-side events.
@@ -55,9 +57,21 @@ impl HxTrigger {
         self
     }
 
+    /// Returns an iterator over the names and data of the events.
+    pub fn events(&self) -> impl Iterator<Item = (&str, Option<&Value>)> {
+        self.0
+            .events
+            .iter()
+            .map(|event| (event.name.as_str(), event.data.as_ref()))
+    }
+
     pub(crate) fn to_header_value(&self) -> Option<HeaderValue> {
         self.0.to_header_value()
     }
+
+    pub(crate) fn from_header_value(value: &HeaderValue) -> Result<Self, Error> {
+        Events::from_header_value(value).map(Self)
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -80,6 +94,33 @@ impl Events {
         self.events.iter().any(|event| event.has_data())
     }
 
+    fn from_header_value(value: &HeaderValue) -> Result<Self, Error> {
+        let value = value.to_str().map_err(|_| Error::invalid())?.trim();
+
+        let events = if value.starts_with('{') {
+            serde_json::from_str::<BTreeMap<String, Value>>(value)
+                .map_err(|_| Error::invalid())?
+                .into_iter()
+                .map(|(name, data)| Event {
+                    name,
+                    data: (!data.is_null()).then_some(data),
+                })
+                .collect()
+        } else {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(|name| Event {
+                    name: name.to_string(),
+                    data: None,
+                })
+                .collect()
+        };
+
+        Ok(Self { events })
+    }
+
     fn to_header_value(&self) -> Option<HeaderValue> {
         if self.events.is_empty() {
             return Some(HeaderValue::from_static(""));
@@ -144,13 +185,15 @@ impl Header for HxTrigger {
         &HX_TRIGGER
     }
 
-    fn decode<'i, I>(_: &mut I) -> Result<Self, Error>
+    fn decode<'i, I>(values: &mut I) -> Result<Self, Error>
     where
         Self: Sized,
         I: Iterator<Item = &'i HeaderValue>,
     {
-        // This is a response header, so decoding it is not valid.
-        Err(Error::invalid())
+        values
+            .just_one()
+            .ok_or_else(Error::invalid)
+            .and_then(Self::from_header_value)
     }
 
     fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
@@ -48,6 +48,11 @@ impl HxTriggerAfterSettle {
     pub fn with_event_data(self, name: String, data: Value) -> Self {
         Self(self.0.with_event_data(name, data))
     }
+
+    /// Returns an iterator over the names and data of the events.
+    pub fn events(&self) -> impl Iterator<Item = (&str, Option<&Value>)> {
+        self.0.events()
+    }
 }
 
 #[cfg(feature = "axum")]
@@ -73,13 +78,12 @@ impl Header for HxTriggerAfterSettle {
         &HX_TRIGGER_AFTER_SETTLE
     }
 
-    fn decode<'i, I>(_: &mut I) -> Result<Self, Error>
+    fn decode<'i, I>(values: &mut I) -> Result<Self, Error>
     where
         Self: Sized,
         I: Iterator<Item = &'i HeaderValue>,
     {
-        // This is a response header, so decoding it is not valid.
-        Err(Error::invalid())
+        HxTrigger::decode(values).map(Self)
     }
 
     fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
@@ -0,0 +1,263 @@
+//! Integration with `rocket`.
+//!
+//! `rocket` does not use the `http` 1.x header types, so this module implements its traits directly:
+//!
+//! - Every type in [`crate::request`] is a request guard that fails with `400 Bad Request` if the header is missing or
+//!   invalid. `Option<T>` resolves to `None` instead, and [`HxForward<T>`] forwards the request to the next matching
+//!   route if the header is missing, which makes it easy to serve full pages and partials from the same path.
+//! - Every type in [`crate::response`] implements `Responder`, and [`HxResponderExt`] adds HTMX response headers to
+//!   any `Responder`.
+//! - [`HxAutoVary`] is a fairing that adds the `Vary` header to responses based on the headers consumed by the
+//!   request guards, like [`HxAutoVaryLayer`](crate::HxAutoVaryLayer) does for `tower`.
+//!
+//! The HTMX headers of a request are decoded at most once and cached with `Request::local_cache`.
+//!
+//! ```rust,no_run
+//! use htmxtools::{
+//!     request::{HxRequest, HxTarget},
+//!     response::HxTrigger,
+//!     rocket::{HxAutoVary, HxForward, HxResponderExt, WithHxHeader},
+//! };
+//! use rocket::{get, routes};
+//!
+//! #[get("/")]
+//! fn partial(_hx_request: HxForward<HxRequest>, hx_target: Option<HxTarget>) -> WithHxHeader<String, HxTrigger> {
+//!     let body = match hx_target {
+//!         Some(target) => format!("The target tag is: {}", target.tag()),
+//!         None => "No target specified".to_string(),
+//!     };
+//!
+//!     body.with_hx_header(HxTrigger::new().with_event("loaded".to_string()))
+//! }
+//!
+//! #[get("/", rank = 2)]
+//! fn page() -> &'static str {
+//!     "<html><body>Full page</body></html>"
+//! }
+//!
+//! let rocket = rocket::build()
+//!     .mount("/", routes![partial, page])
+//!     .attach(HxAutoVary::new());
+//! ```
+use headers_core::Header;
+use http::{Extensions, HeaderMap};
+use rocket::{
+    Request, Response,
+    fairing::{Fairing, Info, Kind},
+    http::Status,
+    request::{FromRequest, Outcome},
+    response::{self, Responder},
+};
+
+use crate::{
+    HxAutoVaryExt,
+    auto_vary::SharedSet,
+    request::{
+        HxBoosted, HxContext, HxCurrentUrl, HxHeader, HxHistoryRestoreRequest, HxRequest,
+        HxRequestHeader, HxRequestKind, HxRequestType, HxSource, HxTarget, hx_header::decode,
+    },
+    response::{
+        HxLocation, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl, HxReselect, HxReswap,
+        HxRetarget, HxTrigger, HxTriggerAfterSettle, HxTriggerAfterSwap,
+    },
+    util::compat,
+};
+
+/// The HTMX request headers of a request as an `http` 1.x `HeaderMap`, with the extensions used for decoding and
+/// auto vary. Stored in the request's local cache.
+struct HxState {
+    headers: HeaderMap,
+    extensions: Extensions,
+}
+
+fn state<'r>(req: &'r Request<'_>) -> &'r HxState {
+    req.local_cache(|| {
+        let headers: Vec<_> = req.headers().iter().collect();
+        let headers = compat::hx_header_map(
+            headers
+                .iter()
+                .map(|header| (header.name().as_str(), header.value().as_bytes())),
+        );
+
+        let mut extensions = Extensions::new();
+        extensions.insert(HxContext::default());
+        extensions.insert(SharedSet::default());
+
+        HxState {
+            headers,
+            extensions,
+        }
+    })
+}
+
+/// The error returned by the request guards in this module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HxGuardError {
+    /// The header is missing.
+    Missing(HxRequestHeader),
+
+    /// The header is present but invalid.
+    Invalid(HxRequestHeader),
+}
+
+fn guard<H: HxHeader>(req: &Request<'_>) -> Result<Option<H>, HxGuardError> {
+    let state = state(req);
+    decode::<H>(&state.headers, &state.extensions).map_err(|_| HxGuardError::Invalid(H::KIND))
+}
+
+macro_rules! impl_from_request {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            #[rocket::async_trait]
+            impl<'r> FromRequest<'r> for $ty {
+                type Error = HxGuardError;
+
+                async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+                    match guard::<Self>(req) {
+                        Ok(Some(header)) => Outcome::Success(header),
+                        Ok(None) => Outcome::Error((Status::BadRequest, HxGuardError::Missing(Self::KIND))),
+                        Err(error) => Outcome::Error((Status::BadRequest, error)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_request!(
+    HxBoosted,
+    HxCurrentUrl,
+    HxHistoryRestoreRequest,
+    HxRequest,
+    HxRequestType,
+    HxSource,
+    HxTarget,
+);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for HxRequestKind {
+    type Error = HxGuardError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let state = state(req);
+        Outcome::Success(HxRequestKind::classify(&state.headers, &state.extensions))
+    }
+}
+
+/// A request guard that forwards the request to the next matching route if the HTMX header `H` is missing, instead of
+/// failing. If no other route matches, the request fails with `404 Not Found`, as for any unmatched path. An invalid
+/// header still fails with `400 Bad Request`.
+#[derive(Debug, Clone)]
+pub struct HxForward<H>(pub H);
+
+#[rocket::async_trait]
+impl<'r, H: HxHeader> FromRequest<'r> for HxForward<H> {
+    type Error = HxGuardError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match guard::<H>(req) {
+            Ok(Some(header)) => Outcome::Success(HxForward(header)),
+            Ok(None) => Outcome::Forward(Status::NotFound),
+            Err(error) => Outcome::Error((Status::BadRequest, error)),
+        }
+    }
+}
+
+/// Appends the values of an HTMX response header to a response.
+fn append<H: Header>(response: &mut Response<'_>, header: &H) {
+    for value in compat::encode(header) {
+        response.adjoin_raw_header(
+            H::name().as_str(),
+            String::from_utf8_lossy(value.as_bytes()).into_owned(),
+        );
+    }
+}
+
+macro_rules! impl_responder {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<'r> Responder<'r, 'static> for $ty {
+                fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+                    let mut response = Response::new();
+                    append(&mut response, &self);
+                    Ok(response)
+                }
+            }
+        )*
+    };
+}
+
+impl_responder!(
+    HxLocation,
+    HxPushUrl,
+    HxRedirect,
+    HxRefresh,
+    HxReplaceUrl,
+    HxReselect,
+    HxReswap,
+    HxRetarget,
+    HxTrigger,
+    HxTriggerAfterSettle,
+    HxTriggerAfterSwap,
+);
+
+/// Extension trait to add HTMX response headers to any `Responder`.
+pub trait HxResponderExt<'r, 'o: 'r>: Responder<'r, 'o> + Sized {
+    /// Wraps the responder to append the values of an HTMX response header.
+    fn with_hx_header<H: Header>(self, header: H) -> WithHxHeader<Self, H> {
+        WithHxHeader {
+            inner: self,
+            header,
+        }
+    }
+}
+
+impl<'r, 'o: 'r, T: Responder<'r, 'o>> HxResponderExt<'r, 'o> for T {}
+
+/// A responder with an HTMX response header. See [`HxResponderExt::with_hx_header`].
+#[derive(Debug)]
+pub struct WithHxHeader<T, H> {
+    inner: T,
+    header: H,
+}
+
+impl<'r, 'o: 'r, T: Responder<'r, 'o>, H: Header> Responder<'r, 'o> for WithHxHeader<T, H> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        let mut response = self.inner.respond_to(req)?;
+        append(&mut response, &self.header);
+        Ok(response)
+    }
+}
+
+/// A fairing that automatically adds the `Vary` header to responses based on the HTMX headers consumed by the request
+/// guards. This is the `rocket` equivalent of [`HxAutoVaryLayer`](crate::HxAutoVaryLayer).
+#[derive(Debug, Clone, Default)]
+pub struct HxAutoVary;
+
+impl HxAutoVary {
+    /// Creates a new `HxAutoVary`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for HxAutoVary {
+    fn info(&self) -> Info {
+        Info {
+            name: "HTMX Auto Vary",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        if let Some(set) = state(req).extensions.hx_auto_vary_headers() {
+            let existing = res.headers().get("Vary").map(str::as_bytes);
+            for value in compat::vary_values(existing, &set) {
+                if let Ok(value) = value.to_str() {
+                    res.adjoin_raw_header("Vary", value.to_owned());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Integration with `salvo`.
+//!
+//! - Every type in [`crate::request`] implements `Extractible`, so it can be used as a `#[handler]` argument. Headers
+//!   are decoded exactly like the `axum` extractors do, and recorded for auto vary. Optional headers are read with
+//!   [`HtmxRequestExt`], which is implemented for `salvo::Request`.
+//! - Every type in [`crate::response`] implements `Scribe`, so it can be rendered to a `salvo::Response` (or returned
+//!   from a handler) to add its HTMX response header.
+//! - [`HxAutoVary`] is a hoop that adds the `Vary` header to responses based on the extracted HTMX headers, like
+//!   [`HxAutoVaryLayer`](crate::HxAutoVaryLayer) does for `tower`.
+//!
+//! ```rust,no_run
+//! use htmxtools::{
+//!     request::{HtmxRequestExt, HxTarget},
+//!     response::HxTrigger,
+//!     salvo::HxAutoVary,
+//! };
+//! use salvo_core::prelude::*;
+//!
+//! #[handler]
+//! async fn index(req: &mut Request, res: &mut Response) {
+//!     let body = match req.hx_header::<HxTarget>() {
+//!         Ok(Some(target)) => format!("The target tag is: {}", target.tag()),
+//!         _ => "No target specified".to_string(),
+//!     };
+//!
+//!     res.render(HxTrigger::new().with_event("loaded".to_string()));
+//!     res.render(body);
+//! }
+//!
+//! let router = Router::new().hoop(HxAutoVary::new()).get(index);
+//! ```
+use headers_core::Header;
+use salvo_core::{
+    Depot, FlowCtrl, Handler, Request, Response, async_trait,
+    extract::{Extractible, Metadata},
+    http::StatusError,
+    writing::Scribe,
+};
+
+use crate::{
+    auto_vary::SharedSet,
+    request::{
+        HtmxRequestExt, HxBoosted, HxCurrentUrl, HxHeader, HxHistoryRestoreRequest, HxRequest,
+        HxRequestKind, HxRequestType, HxSource, HxTarget, hx_header::decode,
+    },
+    response::{
+        HxLocation, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl, HxReselect, HxReswap,
+        HxRetarget, HxTrigger, HxTriggerAfterSettle, HxTriggerAfterSwap,
+    },
+};
+
+impl HtmxRequestExt for Request {
+    fn hx_header<H: HxHeader>(&self) -> Result<Option<H>, headers_core::Error> {
+        decode(self.headers(), self.extensions())
+    }
+
+    fn hx_request_kind(&self) -> HxRequestKind {
+        HxRequestKind::classify(self.headers(), self.extensions())
+    }
+}
+
+macro_rules! impl_extractible {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl<'ex> Extractible<'ex> for $ty {
+                fn metadata() -> &'static Metadata {
+                    static METADATA: Metadata = Metadata::new(stringify!($ty));
+                    &METADATA
+                }
+
+                #[allow(refining_impl_trait)]
+                async fn extract(req: &'ex mut Request, _: &'ex mut Depot) -> Result<Self, StatusError> {
+                    let name = <Self as Header>::name();
+                    match req.hx_header::<Self>() {
+                        Ok(Some(header)) => Ok(header),
+                        Ok(None) => Err(StatusError::bad_request()
+                            .brief(format!("Header of type `{name}` was missing"))),
+                        Err(_) => Err(StatusError::bad_request()
+                            .brief(format!("Header of type `{name}` is invalid"))),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_extractible!(
+    HxBoosted,
+    HxCurrentUrl,
+    HxHistoryRestoreRequest,
+    HxRequest,
+    HxRequestType,
+    HxSource,
+    HxTarget,
+);
+
+impl<'ex> Extractible<'ex> for HxRequestKind {
+    fn metadata() -> &'static Metadata {
+        static METADATA: Metadata = Metadata::new("HxRequestKind");
+        &METADATA
+    }
+
+    #[allow(refining_impl_trait)]
+    async fn extract(req: &'ex mut Request, _: &'ex mut Depot) -> Result<Self, StatusError> {
+        Ok(req.hx_request_kind())
+    }
+}
+
+macro_rules! impl_scribe {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Scribe for $ty {
+                fn render(self, res: &mut Response) {
+                    let mut values = Vec::new();
+                    self.encode(&mut values);
+                    for value in values {
+                        res.headers_mut().append(<Self as Header>::name(), value);
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_scribe!(
+    HxLocation,
+    HxPushUrl,
+    HxRedirect,
+    HxRefresh,
+    HxReplaceUrl,
+    HxReselect,
+    HxReswap,
+    HxRetarget,
+    HxTrigger,
+    HxTriggerAfterSettle,
+    HxTriggerAfterSwap,
+);
+
+/// A hoop that automatically adds the `Vary` header to responses based on the extracted HTMX headers. This is the
+/// `salvo` equivalent of [`HxAutoVaryLayer`](crate::HxAutoVaryLayer).
+#[derive(Debug, Clone, Default)]
+pub struct HxAutoVary;
+
+impl HxAutoVary {
+    /// Creates a new `HxAutoVary`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Handler for HxAutoVary {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let set = SharedSet::default();
+        req.extensions_mut().insert(set.clone());
+
+        ctrl.call_next(req, depot, res).await;
+
+        if let Ok(lock) = set.lock() {
+            lock.add_to_headers(res.headers_mut());
+        }
+    }
+}
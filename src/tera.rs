@@ -0,0 +1,421 @@
+//! Integration with `tera`, for rendering htmx attributes and choosing between a full and a partial template.
+//!
+//! - [`register`] adds the `hx_swap` and `hx_trigger` filters and the `hx_attrs` function to a `Tera` instance. The
+//!   types of [`crate::attribute`] and the request and response types that share their syntax with an htmx attribute
+//!   also convert into a `tera::Value`, to be inserted into a `Context`.
+//! - [`HxRenderer`] holds the htmx information of a request and inserts it into a `Context` as the `htmx` object.
+//! - [`HxTemplate`] renders the full template for requests that expect a full page, and its partial template, named
+//!   by convention, for partial htmx requests.
+//!
+//! ```rust
+//! use htmxtools::tera::register;
+//! use tera::{Context, Tera};
+//!
+//! let mut tera = Tera::default();
+//! register(&mut tera);
+//! tera.add_raw_template(
+//!     "search.html",
+//!     r##"<input name="q" hx-trigger="{{ "keyup" | hx_trigger(changed=true, delay=300) }}"
+//!     hx-swap="{{ "outerHTML" | hx_swap(transition=true) }}"{{ hx_attrs(get="/search", target="#results") }}>"##,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(
+//!     tera.render("search.html", &Context::new()).unwrap(),
+//!     r##"<input name="q" hx-trigger="keyup changed delay:300ms"
+//!     hx-swap="outerHTML transition:true" hx-get="/search" hx-target="#results">"##,
+//! );
+//! ```
+use std::{borrow::Borrow, collections::BTreeMap, iter::once, time::Duration};
+
+#[cfg(feature = "axum")]
+use std::convert::Infallible;
+
+#[cfg(feature = "axum")]
+use axum_core::{
+    extract::FromRequestParts,
+    response::{IntoResponse, Response as AxumResponse},
+};
+use headers_core::Header;
+use http::{HeaderMap, HeaderValue, Response, header::CONTENT_TYPE};
+#[cfg(feature = "axum")]
+use http::{StatusCode, request::Parts};
+use tera::{Context, Error, Kwargs, State, Tera, Value};
+
+#[cfg(feature = "axum")]
+use crate::request::HxContext;
+use crate::{
+    attribute::{self, HxAttributes, HxSwap, HxTriggerSpec},
+    request::{HtmxRequestExt, HxRequestKind, HxSource, HxTarget},
+    response::{HxPushUrl, HxReplaceUrl, HxReselect, HxReswap, HxRetarget, hx_page},
+};
+
+/// Registers the htmx filters and functions with `tera`.
+///
+/// - `hx_swap` is a filter that renders the value of `hx-swap` from a swap strategy, such as
+///   `{{ "outerHTML" | hx_swap(transition=true, settle=100) }}`. It takes the arguments `transition`, `ignore_title`
+///   and `focus_scroll` (booleans), `swap` and `settle` (delays in milliseconds), and `scroll` and `show` (`"top"` or
+///   `"bottom"`).
+/// - `hx_trigger` is a filter that renders the value of `hx-trigger` from an event, such as
+///   `{{ "keyup" | hx_trigger(changed=true, delay=300) }}`. It takes the arguments `filter`, `from` and `target`
+///   (strings), `once`, `changed` and `consume` (booleans), and `delay` and `throttle` (delays in milliseconds).
+/// - `hx_attrs` is a function that renders a set of `hx-*` attributes, such as
+///   `{{ hx_attrs(post="/items", target="#items", push_url=false) }}`. Each argument is an attribute, named with `_`
+///   replaced by `-` and prefixed with `hx-`, and arguments set to `none` are skipped. The attributes are sorted by
+///   name and their values are escaped.
+///
+/// The filters render plain strings, which are escaped like any other value when autoescaping is on.
+pub fn register(tera: &mut Tera) {
+    tera.register_filter("hx_swap", swap_filter);
+    tera.register_filter("hx_trigger", trigger_filter);
+    tera.register_function("hx_attrs", attrs_function);
+}
+
+fn swap_filter(strategy: &str, kwargs: Kwargs, _: &State) -> Result<String, Error> {
+    let reswap = HeaderValue::from_str(strategy)
+        .ok()
+        .and_then(|value| HxReswap::decode(&mut once(&value)).ok())
+        .ok_or_else(|| Error::message(format!("`{strategy}` is not a valid swap strategy")))?;
+
+    let mut swap = HxSwap::new(reswap);
+    if kwargs.get::<bool>("transition")? == Some(true) {
+        swap = swap.transition();
+    }
+    if let Some(delay) = kwargs.get::<u64>("swap")? {
+        swap = swap.swap(Duration::from_millis(delay));
+    }
+    if let Some(delay) = kwargs.get::<u64>("settle")? {
+        swap = swap.settle(Duration::from_millis(delay));
+    }
+    if kwargs.get::<bool>("ignore_title")? == Some(true) {
+        swap = swap.ignore_title();
+    }
+    swap = match kwargs.get::<&str>("scroll")? {
+        Some("top") => swap.scroll_top(),
+        Some("bottom") => swap.scroll_bottom(),
+        Some(other) => {
+            return Err(Error::message(format!(
+                "`{other}` is not a valid scroll position"
+            )));
+        }
+        None => swap,
+    };
+    swap = match kwargs.get::<&str>("show")? {
+        Some("top") => swap.show_top(),
+        Some("bottom") => swap.show_bottom(),
+        Some(other) => {
+            return Err(Error::message(format!(
+                "`{other}` is not a valid show position"
+            )));
+        }
+        None => swap,
+    };
+    if let Some(focus_scroll) = kwargs.get::<bool>("focus_scroll")? {
+        swap = swap.focus_scroll(focus_scroll);
+    }
+    Ok(swap.to_string())
+}
+
+fn trigger_filter(event: &str, kwargs: Kwargs, _: &State) -> Result<String, Error> {
+    let mut trigger = HxTriggerSpec::new(event);
+    if let Some(expression) = kwargs.get::<&str>("filter")? {
+        trigger = trigger.filter(expression);
+    }
+    if kwargs.get::<bool>("once")? == Some(true) {
+        trigger = trigger.once();
+    }
+    if kwargs.get::<bool>("changed")? == Some(true) {
+        trigger = trigger.changed();
+    }
+    if let Some(delay) = kwargs.get::<u64>("delay")? {
+        trigger = trigger.delay(Duration::from_millis(delay));
+    }
+    if let Some(interval) = kwargs.get::<u64>("throttle")? {
+        trigger = trigger.throttle(Duration::from_millis(interval));
+    }
+    if let Some(selector) = kwargs.get::<&str>("from")? {
+        trigger = trigger.from_element(selector);
+    }
+    if let Some(selector) = kwargs.get::<&str>("target")? {
+        trigger = trigger.target(selector);
+    }
+    if kwargs.get::<bool>("consume")? == Some(true) {
+        trigger = trigger.consume();
+    }
+    Ok(trigger.to_string())
+}
+
+fn attrs_function(kwargs: Kwargs, _: &State) -> Value {
+    let mut arguments: Vec<_> = kwargs
+        .iter()
+        .filter(|(_, value)| !value.is_none() && !value.is_undefined())
+        .map(|(name, value)| {
+            let value = value
+                .as_str()
+                .map_or_else(|| value.to_string(), str::to_string);
+            (format!("hx-{}", name.to_string().replace('_', "-")), value)
+        })
+        .collect();
+    arguments.sort();
+
+    let attributes = arguments
+        .into_iter()
+        .fold(HxAttributes::new(), |attributes, (name, value)| {
+            attributes.attribute(name, value)
+        });
+    Value::safe_string(&attributes.to_string())
+}
+
+macro_rules! impl_into_value {
+    ($($ty:ty => |$this:ident| $value:expr),* $(,)?) => {
+        $(
+            impl From<$ty> for Value {
+                fn from($this: $ty) -> Self {
+                    Value::from($value.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_into_value!(
+    HxSwap => |swap| swap,
+    HxTriggerSpec => |trigger| trigger,
+    HxReswap => |reswap| reswap.as_str(),
+    HxPushUrl => |push_url| attribute::push_url_value(&push_url),
+    HxReplaceUrl => |replace_url| attribute::replace_url_value(&replace_url),
+    HxReselect => |reselect| reselect.as_str(),
+    HxRetarget => |retarget| retarget.as_str(),
+    HxSource => |source| source.as_str(),
+    HxTarget => |target| target.as_str(),
+);
+
+impl From<HxAttributes> for Value {
+    /// Converts the attributes into a safe string, which is not escaped again.
+    fn from(attributes: HxAttributes) -> Self {
+        Value::safe_string(&attributes.to_string())
+    }
+}
+
+/// Holds the htmx information of a request, for `tera` templates.
+///
+/// [`HxRenderer::insert_into`] inserts the `htmx` object into a `Context`, with these fields:
+///
+/// - `is_htmx`: `true` if the request was made by htmx.
+/// - `partial`: `true` if the request is a partial htmx request (see [`HxRequestKind::is_full_page`]).
+/// - `boosted`: `true` if the request was made by an element using `hx-boost`.
+/// - `target`: the `hx-target` header, such as `div#results`, or `none`.
+/// - `source`: the `hx-source` header, such as `button#save`, or `none`.
+///
+/// With the `axum` feature, `HxRenderer` is an extractor. Like the other extractors, it records the headers it reads
+/// for auto vary.
+///
+/// ```rust,ignore
+/// use htmxtools::tera::{HxRenderer, HxTemplate};
+/// use tera::{Context, Tera};
+///
+/// // `items.html` extends a layout, and `items.partial.html` renders only the list.
+/// async fn items(htmx: HxRenderer) -> HxTemplate<&'static Tera> {
+///     let mut context = Context::new();
+///     context.insert("items", &["Milk"]);
+///     htmx.template(tera(), "items.html", context)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HxRenderer {
+    kind: HxRequestKind,
+    target: Option<HxTarget>,
+    source: Option<HxSource>,
+}
+
+impl HxRenderer {
+    /// Creates a new `HxRenderer` from the HTMX headers of a request.
+    pub fn new(kind: HxRequestKind, target: Option<HxTarget>, source: Option<HxSource>) -> Self {
+        Self {
+            kind,
+            target,
+            source,
+        }
+    }
+
+    /// Creates a new `HxRenderer` from an `http::Request` or `http::request::Parts`, recording the headers it reads for
+    /// auto vary.
+    pub fn from_request(request: &impl HtmxRequestExt) -> Self {
+        Self::new(
+            request.hx_request_kind(),
+            request.hx_target(),
+            request.hx_source(),
+        )
+    }
+
+    /// Returns the kind of the request.
+    pub fn kind(&self) -> HxRequestKind {
+        self.kind
+    }
+
+    /// Returns the `htmx` object: `is_htmx`, `partial`, `boosted`, `target` and `source`.
+    pub fn value(&self) -> Value {
+        Value::from(BTreeMap::from([
+            ("is_htmx", Value::from(self.kind.is_htmx())),
+            ("partial", Value::from(!self.kind.is_full_page())),
+            ("boosted", Value::from(self.kind == HxRequestKind::Boosted)),
+            ("target", Value::from(self.target.clone())),
+            ("source", Value::from(self.source.clone())),
+        ]))
+    }
+
+    /// Inserts the `htmx` object into `context`, replacing any value named `htmx`.
+    pub fn insert_into(&self, context: &mut Context) {
+        context.insert_value("htmx", self.value());
+    }
+
+    /// Creates an [`HxTemplate`] for this request, with the `htmx` object inserted into `context`.
+    pub fn template<T: Borrow<Tera>>(
+        &self,
+        tera: T,
+        name: impl Into<String>,
+        mut context: Context,
+    ) -> HxTemplate<T> {
+        self.insert_into(&mut context);
+        HxTemplate::new(tera, name, context, self.kind)
+    }
+}
+
+#[cfg(feature = "axum")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+impl<S> FromRequestParts<S> for HxRenderer
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get_or_insert_with(HxContext::default);
+
+        Ok(Self::from_request(&*parts))
+    }
+}
+
+/// Renders a `tera` template, or its partial template for partial htmx requests.
+///
+/// The partial template of a template is named by inserting `.partial` before its extension: the partial template of
+/// `items.html` is `items.partial.html`, and that of `items` is `items.partial`. The template is chosen like this:
+///
+/// 1. For requests that expect a full page (see [`HxRequestKind::is_full_page`]), the full template. The kind is
+///    driven by the `hx-request-type` header, and boosted and history restore requests expect a full page too.
+/// 2. For partial htmx requests, the partial template set with [`HxTemplate::with_partial`], which must exist.
+/// 3. Otherwise, the partial template named by convention, if it exists.
+/// 4. Otherwise, the full template.
+///
+/// `tera` can be a `&Tera`, such as a static one, or an `Arc<Tera>` from the state of the application.
+///
+/// [`HxTemplate::into_http`] and, with the `axum` feature, `IntoResponse` add the `Content-Type` and `Vary` headers.
+/// `IntoResponse` responds with `500 Internal Server Error` if the template fails to render, and logs the error with
+/// the `tracing` feature.
+#[derive(Debug, Clone)]
+pub struct HxTemplate<T> {
+    tera: T,
+    name: String,
+    partial: Option<String>,
+    context: Context,
+    kind: HxRequestKind,
+}
+
+impl<T: Borrow<Tera>> HxTemplate<T> {
+    /// Creates a new `HxTemplate` for a request of the given kind.
+    pub fn new(tera: T, name: impl Into<String>, context: Context, kind: HxRequestKind) -> Self {
+        Self {
+            tera,
+            name: name.into(),
+            partial: None,
+            context,
+            kind,
+        }
+    }
+
+    /// Sets the partial template rendered for partial htmx requests, instead of the one named by convention.
+    pub fn with_partial(mut self, name: impl Into<String>) -> Self {
+        self.partial = Some(name.into());
+        self
+    }
+
+    /// Returns the kind of the request.
+    pub fn kind(&self) -> HxRequestKind {
+        self.kind
+    }
+
+    /// Returns the name of the template that is rendered.
+    pub fn template_name(&self) -> String {
+        if self.kind.is_full_page() {
+            return self.name.clone();
+        }
+        if let Some(partial) = &self.partial {
+            return partial.clone();
+        }
+
+        let partial = partial_name(&self.name);
+        if self
+            .tera
+            .borrow()
+            .get_template_names()
+            .any(|name| name == partial)
+        {
+            partial
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// Renders the template, without adding headers.
+    pub fn render(&self) -> Result<String, Error> {
+        self.tera
+            .borrow()
+            .render(&self.template_name(), &self.context)
+    }
+
+    /// Renders the template into an `http::Response` with the `Content-Type` and `Vary` headers.
+    pub fn into_http(self) -> Result<Response<String>, Error> {
+        let mut response = Response::new(self.render()?);
+        add_headers(response.headers_mut(), self.kind);
+        Ok(response)
+    }
+}
+
+#[cfg(feature = "axum")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+impl<T: Borrow<Tera>> IntoResponse for HxTemplate<T> {
+    fn into_response(self) -> AxumResponse {
+        match self.render() {
+            Ok(body) => {
+                let mut response = body.into_response();
+                add_headers(response.headers_mut(), self.kind);
+                response
+            }
+            #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+            Err(error) => {
+                #[cfg(feature = "tracing")]
+                tracing::error!(%error, template = %self.name, "failed to render the template");
+
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
+
+/// Inserts `.partial` before the extension of the template `name`.
+fn partial_name(name: &str) -> String {
+    match name.rfind('.') {
+        Some(dot) if !name[dot..].contains('/') => {
+            format!("{}.partial{}", &name[..dot], &name[dot..])
+        }
+        _ => format!("{name}.partial"),
+    }
+}
+
+fn add_headers(headers: &mut HeaderMap, kind: HxRequestKind) {
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/html; charset=utf-8"),
+    );
+    hx_page::add_vary(headers, kind);
+}
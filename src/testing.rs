@@ -0,0 +1,35 @@
+//! Utilities for testing htmx handlers without a browser.
+//!
+//! [`HtmxTestRequest`] builds `http::Request`s with exactly the headers an htmx client sends for common interactions,
+//! so tests don't have to spell out header names and values by hand. [`HtmxResponseAssertions`] checks the HTMX headers
+//! of a response without decoding them by hand. [`HtmxTestClient`] goes further and simulates htmx itself on top of
+//! a `tower::Service`, to test flows of several requests in-process. With the `testing-html` feature,
+//! `HtmxFragmentAssertions` checks the HTML body of a response against the swap it is used for.
+//!
+//! ```rust
+//! use htmxtools::{request::HxSource, testing::HtmxTestRequest};
+//! use http::Request;
+//!
+//! let request: Request<String> = HtmxTestRequest::submit("/items", HxSource::from_static("form#new-item"))
+//!     .with_form(&[("name", "Milk")])
+//!     .build();
+//!
+//! assert_eq!(request.method(), "POST");
+//! assert_eq!(request.headers()["hx-request"], "true");
+//! assert_eq!(request.headers()["hx-source"], "form#new-item");
+//! assert_eq!(request.body(), "name=Milk");
+//! ```
+mod assertions;
+#[cfg(feature = "testing-html")]
+mod fragment;
+mod htmx_test_client;
+mod htmx_test_request;
+
+#[cfg(feature = "testing-html")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing-html")))]
+pub use self::fragment::HtmxFragmentAssertions;
+pub use self::{
+    assertions::HtmxResponseAssertions,
+    htmx_test_client::{HtmxTestClient, HxEvent, HxEventTiming},
+    htmx_test_request::{HtmxTestRequest, HtmxVersion},
+};
@@ -0,0 +1,328 @@
+use std::fmt::{Debug, Write};
+
+use headers_core::Header;
+use http::{HeaderMap, Response, header::VARY, response::Parts};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{
+    request::hx_header::decode_untracked,
+    response::{
+        HxLocation, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl, HxReselect, HxReswap,
+        HxRetarget, HxTrigger, HxTriggerAfterSettle, HxTriggerAfterSwap,
+    },
+    util::header_list,
+};
+
+/// Assertions on the HTMX response headers of `http::Response` (and therefore `axum::response::Response`),
+/// `http::response::Parts` and `http::HeaderMap`.
+///
+/// Every assertion panics with a message that lists the decoded HTMX response headers if it fails, and returns `self`
+/// otherwise so that assertions can be chained.
+///
+/// ```rust
+/// use htmxtools::{
+///     response::{HtmxResponseExt, HxReswap, HxTrigger},
+///     testing::HtmxResponseAssertions,
+/// };
+/// use http::Response;
+/// use serde_json::json;
+///
+/// let mut response = Response::new(());
+/// response
+///     .hx_trigger(HxTrigger::new().with_event_data("showToast".to_string(), json!({ "level": "info" })))
+///     .hx_reswap(HxReswap::OuterHtml);
+///
+/// response
+///     .assert_triggered("showToast")
+///     .assert_not_triggered("closeModal")
+///     .assert_reswap(HxReswap::OuterHtml);
+///
+/// let payload: serde_json::Value = response.trigger_payload("showToast");
+/// assert_eq!(payload["level"], "info");
+/// ```
+pub trait HtmxResponseAssertions {
+    /// Returns the headers of the response.
+    fn htmx_headers(&self) -> &HeaderMap;
+
+    /// Asserts that the `hx-trigger` header fires `event`.
+    #[track_caller]
+    fn assert_triggered(&self, event: &str) -> &Self {
+        if find_event::<HxTrigger>(self.htmx_headers(), event).is_none() {
+            fail(
+                self.htmx_headers(),
+                format_args!("expected `hx-trigger` to fire `{event}`"),
+            );
+        }
+        self
+    }
+
+    /// Asserts that the `hx-trigger` header does not fire `event`.
+    #[track_caller]
+    fn assert_not_triggered(&self, event: &str) -> &Self {
+        if find_event::<HxTrigger>(self.htmx_headers(), event).is_some() {
+            fail(
+                self.htmx_headers(),
+                format_args!("expected `hx-trigger` not to fire `{event}`"),
+            );
+        }
+        self
+    }
+
+    /// Asserts that the `hx-trigger-after-settle` header fires `event`.
+    #[track_caller]
+    fn assert_triggered_after_settle(&self, event: &str) -> &Self {
+        if find_event::<HxTriggerAfterSettle>(self.htmx_headers(), event).is_none() {
+            fail(
+                self.htmx_headers(),
+                format_args!("expected `hx-trigger-after-settle` to fire `{event}`"),
+            );
+        }
+        self
+    }
+
+    /// Asserts that the `hx-trigger-after-swap` header fires `event`.
+    #[track_caller]
+    fn assert_triggered_after_swap(&self, event: &str) -> &Self {
+        if find_event::<HxTriggerAfterSwap>(self.htmx_headers(), event).is_none() {
+            fail(
+                self.htmx_headers(),
+                format_args!("expected `hx-trigger-after-swap` to fire `{event}`"),
+            );
+        }
+        self
+    }
+
+    /// Returns the data of `event` in the `hx-trigger` header, deserialized into `T`.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `event` is not fired, has no data, or its data cannot be deserialized into `T`.
+    #[track_caller]
+    fn trigger_payload<T: DeserializeOwned>(&self, event: &str) -> T {
+        let headers = self.htmx_headers();
+
+        let data = match find_event::<HxTrigger>(headers, event) {
+            Some(Some(data)) => data,
+            Some(None) => fail(
+                headers,
+                format_args!("expected `{event}` in `hx-trigger` to have data"),
+            ),
+            None => fail(
+                headers,
+                format_args!("expected `hx-trigger` to fire `{event}`"),
+            ),
+        };
+
+        match serde_json::from_value(data) {
+            Ok(payload) => payload,
+            Err(error) => fail(
+                headers,
+                format_args!(
+                    "expected the data of `{event}` in `hx-trigger` to deserialize into `{}`: {error}",
+                    std::any::type_name::<T>()
+                ),
+            ),
+        }
+    }
+
+    /// Asserts that the `hx-location` header equals `location`.
+    #[track_caller]
+    fn assert_location(&self, location: HxLocation) -> &Self {
+        assert_header(self.htmx_headers(), location);
+        self
+    }
+
+    /// Asserts that the `hx-push-url` header equals `push_url`.
+    #[track_caller]
+    fn assert_push_url(&self, push_url: HxPushUrl) -> &Self {
+        assert_header(self.htmx_headers(), push_url);
+        self
+    }
+
+    /// Asserts that the `hx-redirect` header equals `redirect`.
+    #[track_caller]
+    fn assert_redirect(&self, redirect: HxRedirect) -> &Self {
+        assert_header(self.htmx_headers(), redirect);
+        self
+    }
+
+    /// Asserts that the response has the `hx-refresh` header.
+    #[track_caller]
+    fn assert_refresh(&self) -> &Self {
+        assert_header(self.htmx_headers(), HxRefresh);
+        self
+    }
+
+    /// Asserts that the `hx-replace-url` header equals `replace_url`.
+    #[track_caller]
+    fn assert_replace_url(&self, replace_url: HxReplaceUrl) -> &Self {
+        assert_header(self.htmx_headers(), replace_url);
+        self
+    }
+
+    /// Asserts that the `hx-reselect` header equals `reselect`.
+    #[track_caller]
+    fn assert_reselect(&self, reselect: HxReselect) -> &Self {
+        assert_header(self.htmx_headers(), reselect);
+        self
+    }
+
+    /// Asserts that the `hx-reswap` header equals `reswap`.
+    #[track_caller]
+    fn assert_reswap(&self, reswap: HxReswap) -> &Self {
+        assert_header(self.htmx_headers(), reswap);
+        self
+    }
+
+    /// Asserts that the `hx-retarget` header equals `retarget`.
+    #[track_caller]
+    fn assert_retarget(&self, retarget: HxRetarget) -> &Self {
+        assert_header(self.htmx_headers(), retarget);
+        self
+    }
+
+    /// Asserts that the `Vary` header contains `name`, compared case-insensitively. A `Vary: *` header contains every
+    /// name.
+    #[track_caller]
+    fn assert_vary_contains(&self, name: &str) -> &Self {
+        let headers = self.htmx_headers();
+
+        let found = header_list::elements(headers, VARY)
+            .any(|vary| vary == "*" || vary.eq_ignore_ascii_case(name));
+
+        if !found {
+            let vary: Vec<_> = header_list::elements(headers, VARY).collect();
+            fail(
+                headers,
+                format_args!("expected `vary` to contain `{name}`, found {vary:?}"),
+            );
+        }
+        self
+    }
+}
+
+impl<B> HtmxResponseAssertions for Response<B> {
+    fn htmx_headers(&self) -> &HeaderMap {
+        self.headers()
+    }
+}
+
+impl HtmxResponseAssertions for Parts {
+    fn htmx_headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+}
+
+impl HtmxResponseAssertions for HeaderMap {
+    fn htmx_headers(&self) -> &HeaderMap {
+        self
+    }
+}
+
+/// The headers that fire events.
+pub(super) trait Trigger: Header {
+    fn trigger_events(&self) -> Vec<(&str, Option<&Value>)>;
+}
+
+macro_rules! impl_trigger {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Trigger for $ty {
+                fn trigger_events(&self) -> Vec<(&str, Option<&Value>)> {
+                    self.events().collect()
+                }
+            }
+        )*
+    };
+}
+
+impl_trigger!(HxTrigger, HxTriggerAfterSettle, HxTriggerAfterSwap);
+
+/// Finds `event` in the header `H`. Returns `Some(data)` if the event is fired.
+fn find_event<H: Trigger>(headers: &HeaderMap, event: &str) -> Option<Option<Value>> {
+    let header = decode_untracked::<H>(headers).ok().flatten()?;
+    header
+        .trigger_events()
+        .into_iter()
+        .find(|(name, _)| *name == event)
+        .map(|(_, data)| data.cloned())
+}
+
+#[track_caller]
+fn assert_header<H: Header + Debug + PartialEq>(headers: &HeaderMap, expected: H) {
+    match decode_untracked::<H>(headers) {
+        Ok(Some(actual)) if actual == expected => {}
+        Ok(Some(actual)) => fail(
+            headers,
+            format_args!(
+                "expected `{}` to be {expected:?}, found {actual:?}",
+                H::name()
+            ),
+        ),
+        Ok(None) => fail(
+            headers,
+            format_args!("expected `{}` to be {expected:?}, found nothing", H::name()),
+        ),
+        Err(_) => fail(
+            headers,
+            format_args!(
+                "expected `{}` to be {expected:?}, found an invalid value",
+                H::name()
+            ),
+        ),
+    }
+}
+
+#[track_caller]
+fn fail(headers: &HeaderMap, message: std::fmt::Arguments<'_>) -> ! {
+    panic!("{message}\n{}", describe(headers))
+}
+
+/// Lists the decoded HTMX response headers for failure messages.
+pub(super) fn describe(headers: &HeaderMap) -> String {
+    fn line<H: Header>(headers: &HeaderMap, out: &mut String, show: fn(&H) -> String) {
+        let _ = match decode_untracked::<H>(headers) {
+            Ok(Some(header)) => writeln!(out, "  {}: {}", H::name(), show(&header)),
+            Ok(None) => Ok(()),
+            Err(_) => {
+                let values: Vec<_> = headers.get_all(H::name()).iter().collect();
+                writeln!(out, "  {}: invalid {values:?}", H::name())
+            }
+        };
+    }
+
+    fn debug<H: Debug>(header: &H) -> String {
+        format!("{header:?}")
+    }
+
+    fn events<H: Trigger>(header: &H) -> String {
+        let events: Vec<_> = header
+            .trigger_events()
+            .into_iter()
+            .map(|(name, data)| match data {
+                Some(data) => format!("{name} {data}"),
+                None => name.to_string(),
+            })
+            .collect();
+        events.join(", ")
+    }
+
+    let mut out = String::from("htmx response headers:\n");
+    line::<HxLocation>(headers, &mut out, debug);
+    line::<HxPushUrl>(headers, &mut out, debug);
+    line::<HxRedirect>(headers, &mut out, debug);
+    line::<HxRefresh>(headers, &mut out, debug);
+    line::<HxReplaceUrl>(headers, &mut out, debug);
+    line::<HxReselect>(headers, &mut out, debug);
+    line::<HxReswap>(headers, &mut out, debug);
+    line::<HxRetarget>(headers, &mut out, debug);
+    line::<HxTrigger>(headers, &mut out, events);
+    line::<HxTriggerAfterSettle>(headers, &mut out, events);
+    line::<HxTriggerAfterSwap>(headers, &mut out, events);
+    for vary in headers.get_all(VARY) {
+        let _ = writeln!(out, "  vary: {vary:?}");
+    }
+
+    out
+}
@@ -0,0 +1,319 @@
+use std::iter;
+
+use headers_core::Header;
+use http::{HeaderMap, HeaderValue, Response};
+use scraper::{ElementRef, Html, Selector};
+
+use crate::{
+    request::{HxTarget, hx_header::decode_untracked},
+    response::{HxReselect, HxReswap, HxRetarget},
+    testing::assertions::describe,
+};
+
+/// Assertions on the HTML body of an htmx response, checked against the swap it will be used for.
+///
+/// Implemented for `http::Response` with a buffered body, such as the responses of
+/// [`HtmxTestClient`](crate::testing::HtmxTestClient). Every assertion panics with a message that shows the body and
+/// the decoded HTMX response headers if it fails, and returns `self` otherwise.
+///
+/// ```rust
+/// use htmxtools::{
+///     request::HxTarget,
+///     response::{HtmxResponseExt, HxReselect, HxReswap},
+///     testing::HtmxFragmentAssertions,
+/// };
+/// use http::Response;
+///
+/// let mut response = Response::new(
+///     r#"<ul id="items"><li>Milk</li></ul><span id="count" hx-swap-oob="true">1</span>"#.to_string(),
+/// );
+/// response
+///     .hx_reswap(HxReswap::OuterHtml)
+///     .hx_reselect(HxReselect::from_static("#items"));
+///
+/// response
+///     .assert_swaps_into(&HxTarget::from_static("ul#items"))
+///     .assert_selects("li")
+///     .assert_oob("count");
+/// ```
+pub trait HtmxFragmentAssertions {
+    /// Asserts that `selector` matches at least one element of the body.
+    ///
+    /// # Panic
+    ///
+    /// Also panics if `selector` is not a valid CSS selector.
+    #[track_caller]
+    fn assert_selects(&self, selector: &str) -> &Self;
+
+    /// Asserts that the selector of the `hx-reselect` header matches at least one element of the body.
+    #[track_caller]
+    fn assert_reselect_matches(&self) -> &Self;
+
+    /// Asserts that the body can be swapped into the target of a request with the `hx-target` header `target`:
+    ///
+    /// - The selector of the `hx-reselect` header, if any, matches at least one element. The following checks only
+    ///   consider the selected elements.
+    /// - For `outerHTML` and `outerMorph` swaps, a top-level element has the id of the target, so that the target can
+    ///   be found again after the swap. The id is taken from the `hx-retarget` header if it is an id selector, and from
+    ///   `target` otherwise. Without an `hx-reswap` header, the swap is assumed to be `innerHTML`.
+    /// - For `upsert` swaps, every top-level element has an id.
+    /// - The `hx-swap-oob` markers are valid, like [`HtmxFragmentAssertions::assert_oob_valid`] checks.
+    #[track_caller]
+    fn assert_swaps_into(&self, target: &HxTarget) -> &Self;
+
+    /// Asserts that the body has an element with id `id` marked with `hx-swap-oob`.
+    #[track_caller]
+    fn assert_oob(&self, id: &str) -> &Self;
+
+    /// Asserts that every `hx-swap-oob` marker in the body is valid: its value is `true` or a swap strategy, optionally
+    /// followed by `:` and a selector, and elements without a selector have an id.
+    #[track_caller]
+    fn assert_oob_valid(&self) -> &Self;
+}
+
+impl<B: AsRef<[u8]>> HtmxFragmentAssertions for Response<B> {
+    fn assert_selects(&self, selector: &str) -> &Self {
+        let fragment = Fragment::new(self);
+        if fragment
+            .html
+            .select(&fragment.selector(selector))
+            .next()
+            .is_none()
+        {
+            fragment.fail(format_args!(
+                "expected `{selector}` to match an element of the body"
+            ));
+        }
+        self
+    }
+
+    fn assert_reselect_matches(&self) -> &Self {
+        let fragment = Fragment::new(self);
+        match fragment.header::<HxReselect>() {
+            Some(reselect) => {
+                fragment.reselected(&reselect);
+            }
+            None => fragment.fail(format_args!("expected an `hx-reselect` header")),
+        }
+        self
+    }
+
+    fn assert_swaps_into(&self, target: &HxTarget) -> &Self {
+        let fragment = Fragment::new(self);
+
+        let elements = match fragment.header::<HxReselect>() {
+            Some(reselect) => fragment.reselected(&reselect),
+            None => fragment.top_level(),
+        };
+
+        let id = match fragment.header::<HxRetarget>() {
+            Some(retarget) => retarget
+                .as_str()
+                .strip_prefix('#')
+                .filter(|id| is_identifier(id))
+                .map(str::to_string),
+            None => target.id().map(str::to_string),
+        };
+
+        match fragment.header::<HxReswap>() {
+            Some(HxReswap::OuterHtml | HxReswap::OuterMorph) => {
+                if let Some(id) = id
+                    && !elements
+                        .iter()
+                        .any(|element| element.value().id() == Some(&id))
+                {
+                    fragment.fail(format_args!(
+                        "expected a top-level element with id `{id}` for an outer swap, found {}",
+                        summary(&elements)
+                    ));
+                }
+            }
+            Some(HxReswap::Upsert) => {
+                if let Some(element) = elements
+                    .iter()
+                    .find(|element| element.value().id().is_none())
+                {
+                    fragment.fail(format_args!(
+                        "expected every top-level element to have an id for an upsert swap, found `<{}>` without one",
+                        element.value().name()
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        fragment.check_oob();
+        self
+    }
+
+    fn assert_oob(&self, id: &str) -> &Self {
+        let fragment = Fragment::new(self);
+        let found = fragment
+            .oob()
+            .any(|(element, _)| element.value().id() == Some(id));
+
+        if !found {
+            fragment.fail(format_args!(
+                "expected an element with id `{id}` marked with `hx-swap-oob`"
+            ));
+        }
+        self
+    }
+
+    fn assert_oob_valid(&self) -> &Self {
+        Fragment::new(self).check_oob();
+        self
+    }
+}
+
+/// The parsed body of a response with its headers.
+struct Fragment<'a> {
+    html: Html,
+    body: &'a str,
+    headers: &'a HeaderMap,
+}
+
+impl<'a> Fragment<'a> {
+    #[track_caller]
+    fn new<B: AsRef<[u8]>>(response: &'a Response<B>) -> Self {
+        let body = std::str::from_utf8(response.body().as_ref()).unwrap_or_else(|error| {
+            panic!(
+                "expected the body to be UTF-8: {error}\n{}",
+                describe(response.headers())
+            )
+        });
+
+        let start = body.trim_start();
+        let html = if starts_with_ignore_case(start, "<!doctype")
+            || starts_with_ignore_case(start, "<html")
+        {
+            Html::parse_document(body)
+        } else {
+            Html::parse_fragment(body)
+        };
+
+        Self {
+            html,
+            body,
+            headers: response.headers(),
+        }
+    }
+
+    fn header<H: Header>(&self) -> Option<H> {
+        decode_untracked(self.headers).ok().flatten()
+    }
+
+    #[track_caller]
+    fn selector(&self, selector: &str) -> Selector {
+        Selector::parse(selector).unwrap_or_else(|error| {
+            self.fail(format_args!(
+                "expected `{selector}` to be a valid selector: {error}"
+            ))
+        })
+    }
+
+    /// Returns the top-level elements: the children of `body` for documents, and the roots of fragments.
+    fn top_level(&self) -> Vec<ElementRef<'_>> {
+        let root = self.html.root_element();
+        let parent = root
+            .child_elements()
+            .find(|element| element.value().name() == "body")
+            .unwrap_or(root);
+
+        parent.child_elements().collect()
+    }
+
+    /// Returns the elements selected by `hx-reselect`, failing if there are none.
+    #[track_caller]
+    fn reselected(&self, reselect: &HxReselect) -> Vec<ElementRef<'_>> {
+        let selector = reselect.as_str();
+        let elements: Vec<_> = self.html.select(&self.selector(selector)).collect();
+
+        if elements.is_empty() {
+            self.fail(format_args!(
+                "expected `hx-reselect` selector `{selector}` to match an element of the body"
+            ));
+        }
+        elements
+    }
+
+    /// Returns the elements marked with `hx-swap-oob` with the value of the attribute.
+    fn oob(&self) -> impl Iterator<Item = (ElementRef<'_>, &str)> {
+        self.html
+            .root_element()
+            .descendent_elements()
+            .filter_map(|element| element.attr("hx-swap-oob").map(|value| (element, value)))
+    }
+
+    #[track_caller]
+    fn check_oob(&self) {
+        for (element, value) in self.oob() {
+            let (strategy, selector) = match value.split_once(':') {
+                Some((strategy, selector)) => (strategy.trim(), Some(selector.trim())),
+                None => (value.trim(), None),
+            };
+
+            if strategy != "true" && !is_strategy(strategy) {
+                self.fail(format_args!(
+                    "expected `hx-swap-oob` of `<{}>` to be `true` or a swap strategy, found `{value}`",
+                    element.value().name()
+                ));
+            }
+
+            match selector {
+                Some(selector) => {
+                    self.selector(selector);
+                }
+                None if element.value().id().is_none() => self.fail(format_args!(
+                    "expected `<{}>` marked with `hx-swap-oob=\"{value}\"` to have an id",
+                    element.value().name()
+                )),
+                None => {}
+            }
+        }
+    }
+
+    #[track_caller]
+    fn fail(&self, message: std::fmt::Arguments<'_>) -> ! {
+        panic!(
+            "{message}\nbody:\n{}\n{}",
+            self.body,
+            describe(self.headers)
+        )
+    }
+}
+
+fn starts_with_ignore_case(s: &str, prefix: &str) -> bool {
+    s.get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+/// Returns `true` if `strategy` is one of the swap strategies of [`HxReswap`].
+fn is_strategy(strategy: &str) -> bool {
+    HeaderValue::from_str(strategy)
+        .is_ok_and(|value| HxReswap::decode(&mut iter::once(&value)).is_ok())
+}
+
+/// Returns `true` if `id` can be used in an id selector without escaping.
+fn is_identifier(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+fn summary(elements: &[ElementRef<'_>]) -> String {
+    let elements: Vec<_> = elements
+        .iter()
+        .map(|element| match element.value().id() {
+            Some(id) => format!("`{}#{id}`", element.value().name()),
+            None => format!("`{}`", element.value().name()),
+        })
+        .collect();
+
+    if elements.is_empty() {
+        "no elements".to_string()
+    } else {
+        elements.join(", ")
+    }
+}
@@ -0,0 +1,352 @@
+use std::{fmt::Debug, future::poll_fn};
+
+use http::{HeaderMap, Request, Response, Uri, uri::Parts};
+use http_body::Body;
+use serde_json::Value;
+use tower_service::Service;
+
+use crate::{
+    request::{HxCurrentUrl, HxTarget, hx_header::decode_untracked},
+    response::{
+        HxLocation, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl, HxTrigger,
+        HxTriggerAfterSettle, HxTriggerAfterSwap,
+    },
+    testing::{HtmxTestRequest, assertions::Trigger},
+    util::body::to_bytes,
+};
+
+/// The maximum number of `hx-location`, `hx-redirect` and `hx-refresh` responses followed for a single request.
+const MAX_FOLLOWS: usize = 10;
+
+/// When an event is fired by htmx.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HxEventTiming {
+    /// Fired as soon as the response is received, by `hx-trigger`.
+    Received,
+
+    /// Fired after the swap, by `hx-trigger-after-swap`.
+    AfterSwap,
+
+    /// Fired after the settle, by `hx-trigger-after-settle`.
+    AfterSettle,
+}
+
+/// An event fired by an HTMX response header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HxEvent {
+    /// The name of the event.
+    pub name: String,
+
+    /// The data of the event, if any.
+    pub data: Option<Value>,
+
+    /// When the event is fired.
+    pub timing: HxEventTiming,
+}
+
+/// A simulated htmx client that sends requests to a `tower::Service`, such as an `axum::Router`, and reacts to the
+/// HTMX response headers like htmx does.
+///
+/// The client keeps the URL of the current page and a history stack, and records the fired events:
+///
+/// - `hx-trigger`, `hx-trigger-after-swap` and `hx-trigger-after-settle` events are recorded in [`Self::events`].
+/// - `hx-location` is followed with an htmx `GET` request, which pushes its path unless `push` is `false`. The client
+///   has no DOM, so `hx-target` is the `target` selector as is, or `body` if there is none.
+/// - `hx-redirect` is followed with a regular `GET` request, like a full page navigation.
+/// - `hx-refresh` reloads the current page with a regular `GET` request.
+/// - Like htmx, the client follows `hx-location` first, then `hx-redirect`, then `hx-refresh`, and ignores the others
+///   when a response has several of them.
+/// - `hx-push-url` pushes a new history entry and `hx-replace-url` replaces the current one. Requests made with
+///   [`HtmxTestRequest::boosted`] push their URL unless the response says otherwise.
+///
+/// Requests are sent with the client's current URL in `hx-current-url`, which replaces the one set on the
+/// [`HtmxTestRequest`].
+///
+/// ```rust
+/// use axum::{Router, routing::{get, post}};
+/// use htmxtools::{
+///     request::HxSource,
+///     response::{HxRedirect, HxTrigger},
+///     testing::{HtmxTestClient, HtmxTestRequest},
+/// };
+/// use http::Uri;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let app = Router::new()
+///     .route("/login", post(|| async {
+///         (HxTrigger::new().with_event("loggedIn".to_string()), HxRedirect(Uri::from_static("/dashboard")))
+///     }))
+///     .route("/dashboard", get(|| async { "<h1>Dashboard</h1>" }));
+///
+/// let mut client = HtmxTestClient::new(app);
+/// let response = client
+///     .send(HtmxTestRequest::submit("/login", HxSource::from_static("form#login")).with_form(&[("user", "admin")]))
+///     .await;
+///
+/// assert_eq!(response.body(), "<h1>Dashboard</h1>");
+/// assert_eq!(client.current_url(), "http://localhost/dashboard");
+/// assert!(client.fired("loggedIn"));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct HtmxTestClient<S> {
+    service: S,
+    history: Vec<Uri>,
+    events: Vec<HxEvent>,
+}
+
+impl<S> HtmxTestClient<S> {
+    /// Creates a new client on the page `http://localhost/`.
+    pub fn new(service: S) -> Self {
+        Self {
+            service,
+            history: vec![Uri::from_static("http://localhost/")],
+            events: Vec::new(),
+        }
+    }
+
+    /// Sets the page the client starts on, replacing the history.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `url` is not a valid URI.
+    pub fn with_current_url(mut self, url: &str) -> Self {
+        let url = url
+            .parse()
+            .unwrap_or_else(|_| panic!("`{url}` is not a valid URI"));
+        self.history = vec![resolve(&Uri::from_static("http://localhost/"), &url)];
+        self
+    }
+
+    /// Returns the URL of the current page.
+    pub fn current_url(&self) -> &Uri {
+        self.history.last().expect("the history is never empty")
+    }
+
+    /// Returns the history stack, with the current page last.
+    pub fn history(&self) -> &[Uri] {
+        &self.history
+    }
+
+    /// Returns the events fired so far, in order.
+    pub fn events(&self) -> &[HxEvent] {
+        &self.events
+    }
+
+    /// Returns `true` if the event has been fired.
+    pub fn fired(&self, name: &str) -> bool {
+        self.events.iter().any(|event| event.name == name)
+    }
+
+    /// Removes and returns the events fired so far.
+    pub fn take_events(&mut self) -> Vec<HxEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Returns the wrapped service.
+    pub fn into_inner(self) -> S {
+        self.service
+    }
+}
+
+impl<S, B> HtmxTestClient<S>
+where
+    S: Service<Request<String>, Response = Response<B>>,
+    S::Error: Debug,
+    B: Body,
+    B::Error: Debug,
+{
+    /// Sends an htmx request and follows the HTMX response headers. Returns the last response.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the service fails, or if more than 10 redirects are followed.
+    pub async fn send(&mut self, mut request: HtmxTestRequest) -> Response<String> {
+        let mut push = request.boosted;
+
+        for _ in 0..MAX_FOLLOWS {
+            let url = resolve(self.current_url(), &request.uri);
+
+            request.current_url = HxCurrentUrl::from(self.current_url().clone());
+            let response = self.call(request.build()).await;
+
+            match self.process(response, url, push) {
+                Next::Done(response) => return response,
+                Next::Htmx(next, next_push) => (request, push) = (*next, next_push),
+                Next::Page(url, push) => return self.page(url, push).await,
+            }
+        }
+
+        panic!("followed more than {MAX_FOLLOWS} htmx redirects");
+    }
+
+    /// Navigates to a page with a regular (non-htmx) `GET` request, like typing the URL in the location bar.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `url` is not a valid URI or the service fails.
+    pub async fn navigate(&mut self, url: &str) -> Response<String> {
+        let url = url
+            .parse()
+            .unwrap_or_else(|_| panic!("`{url}` is not a valid URI"));
+        let url = resolve(self.current_url(), &url);
+        self.page(url, true).await
+    }
+
+    /// Goes back to the previous page in the history, with a history restore request as if the page was not in the
+    /// local history cache. Returns `None` if there is no previous page.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the service fails.
+    pub async fn back(&mut self) -> Option<Response<String>> {
+        if self.history.len() < 2 {
+            return None;
+        }
+
+        self.history.pop();
+        let url = self.current_url().clone();
+
+        let mut request = HtmxTestRequest::history_restore("/");
+        request.uri = url;
+        request.current_url = HxCurrentUrl::from(self.current_url().clone());
+        Some(self.call(request.build()).await)
+    }
+
+    /// Loads a page with a regular `GET` request, pushing it into the history if `push` is `true`.
+    async fn page(&mut self, url: Uri, push: bool) -> Response<String> {
+        let mut request = Request::new(String::new());
+        *request.uri_mut() = path(&url);
+
+        if push {
+            self.history.push(url);
+        }
+
+        self.call(request).await
+    }
+
+    async fn call(&mut self, mut request: Request<String>) -> Response<String> {
+        *request.uri_mut() = path(request.uri());
+
+        poll_fn(|cx| self.service.poll_ready(cx))
+            .await
+            .expect("the service is ready");
+        let response = self
+            .service
+            .call(request)
+            .await
+            .expect("the service responds");
+
+        let (parts, body) = response.into_parts();
+        let body = to_bytes(body).await.expect("the response body is readable");
+        Response::from_parts(parts, String::from_utf8_lossy(&body).into_owned())
+    }
+
+    /// Reacts to the HTMX headers of a response to a request for `url`.
+    fn process(&mut self, response: Response<String>, url: Uri, push: bool) -> Next {
+        let headers = response.headers();
+        self.record::<HxTrigger>(headers, HxEventTiming::Received);
+
+        if let Ok(Some(location)) = decode_untracked::<HxLocation>(headers) {
+            let push = location.push != Some(false);
+            return Next::Htmx(Box::new(Self::location(location)), push);
+        }
+
+        if let Ok(Some(HxRedirect(redirect))) = decode_untracked(headers) {
+            return Next::Page(resolve(self.current_url(), &redirect), true);
+        }
+
+        if let Ok(Some(HxRefresh)) = decode_untracked(headers) {
+            return Next::Page(self.current_url().clone(), false);
+        }
+
+        let mut push = push.then(|| url.clone());
+        match decode_untracked::<HxPushUrl>(headers) {
+            Ok(Some(HxPushUrl::True)) => push = Some(url.clone()),
+            Ok(Some(HxPushUrl::False)) => push = None,
+            Ok(Some(HxPushUrl::Uri(uri))) => push = Some(resolve(&url, &uri)),
+            _ => {}
+        }
+        if let Some(push) = push {
+            self.history.push(push);
+        }
+
+        let replace = match decode_untracked::<HxReplaceUrl>(headers) {
+            Ok(Some(HxReplaceUrl::True)) => Some(url),
+            Ok(Some(HxReplaceUrl::Uri(uri))) => Some(resolve(&url, &uri)),
+            _ => None,
+        };
+        if let Some(replace) = replace {
+            *self.history.last_mut().expect("the history is never empty") = replace;
+        }
+
+        self.record::<HxTriggerAfterSwap>(headers, HxEventTiming::AfterSwap);
+        self.record::<HxTriggerAfterSettle>(headers, HxEventTiming::AfterSettle);
+
+        Next::Done(response)
+    }
+
+    /// Builds the request htmx makes for an `hx-location` header.
+    fn location(location: HxLocation) -> HtmxTestRequest {
+        let target = location.target.as_deref().and_then(HxTarget::from_str);
+
+        let mut request = match target {
+            Some(target) => HtmxTestRequest::click("/").with_target(target),
+            None => HtmxTestRequest::click("/")
+                .with_target(HxTarget::from_static("body"))
+                .full(),
+        };
+        request.uri = location.path;
+
+        if let Some(headers) = location.headers {
+            for (name, value) in &headers {
+                request = request.with_header(name, value.clone());
+            }
+        }
+
+        request
+    }
+
+    fn record<H: Trigger>(&mut self, headers: &HeaderMap, timing: HxEventTiming) {
+        if let Ok(Some(header)) = decode_untracked::<H>(headers) {
+            self.events.extend(
+                header
+                    .trigger_events()
+                    .into_iter()
+                    .map(|(name, data)| HxEvent {
+                        name: name.to_string(),
+                        data: data.cloned(),
+                        timing,
+                    }),
+            );
+        }
+    }
+}
+
+/// What the client does after processing a response.
+enum Next {
+    Done(Response<String>),
+    Htmx(Box<HtmxTestRequest>, bool),
+    Page(Uri, bool),
+}
+
+/// Resolves `uri` against `base`, keeping only the path and query of relative URIs.
+fn resolve(base: &Uri, uri: &Uri) -> Uri {
+    if uri.scheme().is_some() {
+        return uri.clone();
+    }
+
+    let mut parts = Parts::default();
+    parts.scheme = base.scheme().cloned();
+    parts.authority = base.authority().cloned();
+    parts.path_and_query = uri.path_and_query().cloned();
+    Uri::from_parts(parts).unwrap_or_else(|_| uri.clone())
+}
+
+/// Returns the path and query of a URI, which is what a browser sends in the request line.
+fn path(uri: &Uri) -> Uri {
+    uri.path_and_query()
+        .map(|path| Uri::from(path.clone()))
+        .unwrap_or_else(|| Uri::from_static("/"))
+}
@@ -0,0 +1,278 @@
+use headers_core::Header;
+use http::{
+    HeaderMap, HeaderName, HeaderValue, Method, Request, Uri,
+    header::{CONTENT_TYPE, IntoHeaderName},
+};
+
+use crate::request::{
+    HxBoosted, HxCurrentUrl, HxHistoryRestoreRequest, HxRequest, HxRequestType, HxSource, HxTarget,
+};
+
+static HX_TRIGGER: HeaderName = HeaderName::from_static("hx-trigger");
+
+/// The version of htmx whose request headers [`HtmxTestRequest`] reproduces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum HtmxVersion {
+    /// htmx 2.x. Sends the id of the target element in `hx-target` and the id of the triggering element in
+    /// `hx-trigger`, and neither `hx-source` nor `hx-request-type`.
+    V2,
+
+    /// htmx 4.x. Sends `hx-source` and `hx-target` in format `tag#id`, and `hx-request-type`.
+    #[default]
+    V4,
+}
+
+/// A builder for `http::Request`s that carry the headers an htmx client sends.
+///
+/// Start from one of the presets and adjust it with the `with_*` methods:
+///
+/// - [`HtmxTestRequest::click`]: `GET` from a `button` targeting itself, with request type `partial`.
+/// - [`HtmxTestRequest::boosted`]: `GET` from an `a` targeting `body`, with request type `full` and `hx-boosted`.
+/// - [`HtmxTestRequest::history_restore`]: `GET` targeting `body`, with request type `full` and
+///   `hx-history-restore-request`.
+/// - [`HtmxTestRequest::submit`]: `POST` of form data from `source` targeting itself, with request type `partial`.
+///
+/// Every request also carries `hx-request: true` and `hx-current-url`, which defaults to `http://localhost/`. With
+/// [`HtmxVersion::V2`], history restore requests only carry `hx-request`, `hx-history-restore-request` and
+/// `hx-current-url`, like htmx 2 does.
+///
+/// ```rust
+/// use htmxtools::{request::HxTarget, testing::HtmxTestRequest};
+/// use http::Request;
+///
+/// let request: Request<String> = HtmxTestRequest::click("/items")
+///     .with_target(HxTarget::from_static("ul#items"))
+///     .full()
+///     .build();
+///
+/// assert_eq!(request.headers()["hx-target"], "ul#items");
+/// assert_eq!(request.headers()["hx-request-type"], "full");
+/// assert!(!request.headers().contains_key("hx-boosted"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HtmxTestRequest {
+    version: HtmxVersion,
+    method: Method,
+    pub(super) uri: Uri,
+    pub(super) current_url: HxCurrentUrl,
+    source: Option<HxSource>,
+    target: Option<HxTarget>,
+    request_type: HxRequestType,
+    pub(super) boosted: bool,
+    history_restore: bool,
+    headers: HeaderMap,
+    body: String,
+}
+
+impl HtmxTestRequest {
+    fn new(method: Method, uri: &str) -> Self {
+        Self {
+            version: HtmxVersion::default(),
+            method,
+            uri: parse_uri(uri),
+            current_url: HxCurrentUrl::from(Uri::from_static("http://localhost/")),
+            source: None,
+            target: None,
+            request_type: HxRequestType::Partial,
+            boosted: false,
+            history_restore: false,
+            headers: HeaderMap::new(),
+            body: String::new(),
+        }
+    }
+
+    /// A `GET` request triggered by clicking a plain `<button>` that targets itself.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `uri` is not a valid URI.
+    pub fn click(uri: &str) -> Self {
+        Self::new(Method::GET, uri)
+            .with_source(HxSource::from_static("button"))
+            .with_target(HxTarget::from_static("button"))
+    }
+
+    /// A `GET` request for a link inside an element using [`hx-boost`](https://four.htmx.org/attributes/hx-boost/).
+    ///
+    /// # Panic
+    ///
+    /// Panics if `uri` is not a valid URI.
+    pub fn boosted(uri: &str) -> Self {
+        let mut request = Self::new(Method::GET, uri)
+            .with_source(HxSource::from_static("a"))
+            .with_target(HxTarget::from_static("body"))
+            .full();
+        request.boosted = true;
+        request
+    }
+
+    /// A `GET` request to restore history after a miss in the local history cache.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `uri` is not a valid URI.
+    pub fn history_restore(uri: &str) -> Self {
+        let mut request = Self::new(Method::GET, uri)
+            .with_target(HxTarget::from_static("body"))
+            .full();
+        request.history_restore = true;
+        request
+    }
+
+    /// A `POST` request submitting a form, triggered by `source` and targeting it. Set the form data with
+    /// [`HtmxTestRequest::with_form`].
+    ///
+    /// # Panic
+    ///
+    /// Panics if `uri` is not a valid URI.
+    pub fn submit(uri: &str, source: HxSource) -> Self {
+        let target = HxTarget::from_str(source.as_str()).expect("`HxSource` is a valid `HxTarget`");
+
+        Self::new(Method::POST, uri)
+            .with_source(source)
+            .with_target(target)
+            .with_header(
+                CONTENT_TYPE,
+                HeaderValue::from_static("application/x-www-form-urlencoded"),
+            )
+    }
+
+    /// Sets the htmx version whose headers are reproduced. Defaults to [`HtmxVersion::V4`].
+    pub fn with_version(mut self, version: HtmxVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Sets the HTTP method.
+    pub fn with_method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Sets the URL of the page the request is made from.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `current_url` is not a valid URI.
+    pub fn with_current_url(mut self, current_url: &str) -> Self {
+        self.current_url = HxCurrentUrl::from(parse_uri(current_url));
+        self
+    }
+
+    /// Sets the element that triggered the request.
+    pub fn with_source(mut self, source: HxSource) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Sets the target element of the request.
+    pub fn with_target(mut self, target: HxTarget) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// Sets the request type to [`HxRequestType::Partial`].
+    pub fn partial(mut self) -> Self {
+        self.request_type = HxRequestType::Partial;
+        self
+    }
+
+    /// Sets the request type to [`HxRequestType::Full`], as htmx does for requests that target the body or use
+    /// `hx-select`.
+    pub fn full(mut self) -> Self {
+        self.request_type = HxRequestType::Full;
+        self
+    }
+
+    /// Appends an additional header.
+    pub fn with_header<K: IntoHeaderName>(mut self, name: K, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Sets the body to the URL encoded form data.
+    pub fn with_form(mut self, fields: &[(&str, &str)]) -> Self {
+        self.body = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(fields)
+            .finish();
+        self
+    }
+
+    /// Sets the body.
+    pub fn with_body(mut self, body: String) -> Self {
+        self.body = body;
+        self
+    }
+
+    /// Returns the headers of the request.
+    pub fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+
+        encode(&mut headers, HxRequest);
+        if self.history_restore {
+            encode(&mut headers, HxHistoryRestoreRequest);
+        }
+        if self.boosted {
+            encode(&mut headers, HxBoosted);
+        }
+        encode(&mut headers, self.current_url.clone());
+
+        match self.version {
+            HtmxVersion::V2 => {
+                if !self.history_restore {
+                    if let Some(id) = self.target.as_ref().and_then(HxTarget::id) {
+                        headers.insert(HxTarget::name(), header_value(id));
+                    }
+                    if let Some(id) = self.source.as_ref().and_then(HxSource::id) {
+                        headers.insert(HX_TRIGGER.clone(), header_value(id));
+                    }
+                }
+            }
+            HtmxVersion::V4 => {
+                encode(&mut headers, self.request_type);
+                if let Some(source) = &self.source {
+                    encode(&mut headers, source.clone());
+                }
+                if let Some(target) = &self.target {
+                    encode(&mut headers, target.clone());
+                }
+            }
+        }
+
+        for (name, value) in &self.headers {
+            headers.append(name, value.clone());
+        }
+
+        headers
+    }
+
+    /// Builds the request.
+    pub fn build<B: From<String>>(self) -> Request<B> {
+        let headers = self.headers();
+
+        let mut request = Request::new(B::from(self.body));
+        *request.method_mut() = self.method;
+        *request.uri_mut() = self.uri;
+        *request.headers_mut() = headers;
+
+        request
+    }
+}
+
+fn encode<H: Header>(headers: &mut HeaderMap, header: H) {
+    let mut values = Vec::new();
+    header.encode(&mut values);
+
+    for value in values {
+        headers.append(H::name(), value);
+    }
+}
+
+fn header_value(value: &str) -> HeaderValue {
+    HeaderValue::from_str(value).expect("a part of a header value is a valid header value")
+}
+
+fn parse_uri(uri: &str) -> Uri {
+    uri.parse()
+        .unwrap_or_else(|_| panic!("`{uri}` is not a valid URI"))
+}
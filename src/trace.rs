@@ -0,0 +1,143 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+
+use http::{HeaderMap, Request, Response};
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+use tracing::{Span, field};
+
+use crate::request::{HxRequestHeader, HxRequestKind};
+
+/// A layer that wraps every request in a `tracing` span describing its HTMX headers, and logs the HTMX headers of the
+/// response.
+///
+/// The span is named `htmx` and has the fields `kind` (the [`HxRequestKind`] of the request), `target`, `source` and
+/// `current_url`. Both the span and the event are emitted at the `DEBUG` level. Reading headers for tracing does not
+/// record them for auto vary.
+#[derive(Debug, Clone, Default)]
+pub struct HxTraceLayer;
+
+impl HxTraceLayer {
+    /// Creates a new `HxTraceLayer`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for HxTraceLayer {
+    type Service = HxTrace<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HxTrace { inner }
+    }
+}
+
+/// A service that wraps every request in a `tracing` span describing its HTMX headers. See [`HxTraceLayer`] for more
+/// details.
+#[derive(Debug, Clone)]
+pub struct HxTrace<S> {
+    inner: S,
+}
+
+impl<ReqBody, ResBody, S> Service<Request<ReqBody>> for HxTrace<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+{
+    type Response = S::Response;
+
+    type Error = S::Error;
+
+    type Future = HxTraceResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let span = request_span(&req);
+
+        let fut = {
+            let _guard = span.enter();
+            self.inner.call(req)
+        };
+
+        HxTraceResponseFuture { fut, span }
+    }
+}
+
+pin_project! {
+    /// Future returned by [`HxTrace`].
+    pub struct HxTraceResponseFuture<F> {
+        #[pin]
+        fut: F,
+        span: Span,
+    }
+}
+
+impl<F, B, E> Future for HxTraceResponseFuture<F>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+{
+    type Output = Result<Response<B>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let _guard = this.span.enter();
+
+        let response = ready!(this.fut.poll(cx));
+
+        if let Ok(ref response) = response {
+            tracing::debug!(
+                status = response.status().as_u16(),
+                headers = %htmx_headers(response.headers()),
+                "htmx response"
+            );
+        }
+
+        Poll::Ready(response)
+    }
+}
+
+fn request_span<B>(req: &Request<B>) -> Span {
+    let kind = HxRequestKind::cached(req.headers(), req.extensions());
+
+    let span = tracing::debug_span!(
+        "htmx",
+        kind = ?kind,
+        target = field::Empty,
+        source = field::Empty,
+        current_url = field::Empty,
+    );
+
+    for (field, header) in [
+        ("target", HxRequestHeader::Target),
+        ("source", HxRequestHeader::Source),
+        ("current_url", HxRequestHeader::CurrentUrl),
+    ] {
+        if let Some(value) = req
+            .headers()
+            .get(header.name())
+            .and_then(|value| value.to_str().ok())
+        {
+            span.record(field, value);
+        }
+    }
+
+    span
+}
+
+/// Formats the HTMX headers in a header map as `name: value` pairs separated by `, `.
+fn htmx_headers(headers: &HeaderMap) -> String {
+    headers
+        .iter()
+        .filter(|(name, _)| {
+            name.as_str().starts_with("hx-") && HxRequestHeader::from_name(name).is_none()
+        })
+        .map(|(name, value)| format!("{name}: {}", String::from_utf8_lossy(value.as_bytes())))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -1,3 +1,13 @@
+#[cfg(any(
+    feature = "cache",
+    feature = "etag",
+    feature = "testing",
+    all(feature = "auto-vary", feature = "tracing")
+))]
+pub mod body;
+#[cfg(any(feature = "actix", feature = "rocket"))]
+pub mod compat;
+pub mod header_list;
 pub mod iter;
 pub mod uri;
 pub mod value_string;
@@ -0,0 +1,60 @@
+use bytes::Bytes;
+#[cfg(any(
+    feature = "cache",
+    feature = "etag",
+    all(feature = "auto-vary", feature = "tracing")
+))]
+use http::{HeaderMap, header::CONTENT_TYPE};
+#[cfg(any(feature = "cache", feature = "etag"))]
+use http::{Response, StatusCode};
+use http_body::Body;
+use http_body_util::BodyExt;
+
+pub async fn to_bytes<B: Body>(body: B) -> Result<Bytes, B::Error> {
+    body.collect().await.map(|collected| collected.to_bytes())
+}
+
+#[cfg(any(
+    feature = "cache",
+    feature = "etag",
+    all(feature = "auto-vary", feature = "tracing")
+))]
+/// Returns `true` if the body has an exact size of at most `limit` bytes and is not an event stream, so that it can be
+/// buffered in memory without holding back a streaming response.
+pub fn is_bufferable<B: Body>(headers: &HeaderMap, body: &B, limit: u64) -> bool {
+    let size = body.size_hint();
+
+    size.upper() == Some(size.lower()) && size.lower() <= limit && !is_event_stream(headers)
+}
+
+#[cfg(any(
+    feature = "cache",
+    feature = "etag",
+    all(feature = "auto-vary", feature = "tracing")
+))]
+fn is_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|essence| essence.trim().eq_ignore_ascii_case("text/event-stream"))
+}
+
+/// Logs an error that occurred while reading a response body, and returns an empty `500 Internal Server Error` response
+/// to send in place of the response that could not be read.
+#[cfg(any(feature = "cache", feature = "etag"))]
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub fn read_error<B: From<Bytes>>(
+    error: impl Into<BoxError>,
+    message: &'static str,
+) -> Response<B> {
+    #[cfg(feature = "tracing")]
+    tracing::error!(error = %error.into(), "{message}");
+
+    let mut response = Response::new(B::from(Bytes::new()));
+    *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+    response
+}
+
+#[cfg(any(feature = "cache", feature = "etag"))]
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
@@ -0,0 +1,55 @@
+use headers_core::Header;
+use http::{HeaderMap, HeaderName, HeaderValue, header::VARY};
+
+use crate::HxRequestHeaderSet;
+
+/// Collects the HTMX request headers (`hx-*`) from raw header name and value pairs into a `HeaderMap`.
+pub fn hx_header_map<'a, I>(headers: I) -> HeaderMap
+where
+    I: IntoIterator<Item = (&'a str, &'a [u8])>,
+{
+    let mut map = HeaderMap::new();
+
+    for (name, value) in headers {
+        if name.len() > 3
+            && name[..3].eq_ignore_ascii_case("hx-")
+            && let Ok(name) = HeaderName::from_bytes(name.as_bytes())
+            && let Ok(value) = HeaderValue::from_bytes(value)
+        {
+            map.append(name, value);
+        }
+    }
+
+    map
+}
+
+/// Encodes a header into its raw values.
+pub fn encode<H: Header>(header: &H) -> Vec<HeaderValue> {
+    let mut values = Vec::new();
+    header.encode(&mut values);
+    values
+}
+
+/// Returns the values to append to a `Vary` header with the raw `existing` values so that it lists the headers of
+/// `set`. Names already listed are skipped, and nothing is appended to `Vary: *`.
+pub fn vary_values<'a, I>(existing: I, set: &HxRequestHeaderSet) -> Vec<HeaderValue>
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    let mut headers = HeaderMap::new();
+    for value in existing {
+        if let Ok(value) = HeaderValue::from_bytes(value) {
+            headers.append(VARY, value);
+        }
+    }
+
+    let existing = headers.get_all(VARY).iter().count();
+    set.add_to_headers(&mut headers);
+
+    headers
+        .get_all(VARY)
+        .iter()
+        .skip(existing)
+        .cloned()
+        .collect()
+}
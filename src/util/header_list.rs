@@ -0,0 +1,22 @@
+use http::{HeaderMap, HeaderName, HeaderValue, header::AsHeaderName, header::VARY};
+
+/// Returns the trimmed, non-empty elements of a comma-separated header list such as `Vary` or `If-None-Match`.
+pub fn elements<K: AsHeaderName>(headers: &HeaderMap, name: K) -> impl Iterator<Item = &str> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|element| !element.is_empty())
+}
+
+/// Appends `name` to the `Vary` header, unless it is already listed (compared case-insensitively) or `Vary` is `*`.
+pub fn append_vary(headers: &mut HeaderMap, name: &HeaderName) {
+    if elements(headers, VARY)
+        .any(|element| element == "*" || element.eq_ignore_ascii_case(name.as_str()))
+    {
+        return;
+    }
+    headers.append(VARY, HeaderValue::from(name.clone()));
+}
@@ -19,20 +19,21 @@ impl HeaderValueString {
     }
 
     pub(crate) fn from_str(src: &str) -> Option<Self> {
-        HeaderValue::from_str(src).ok().map(Self)
+        HeaderValue::from_str(src)
+            .ok()
+            .and_then(|value| Self::try_from_header_value(&value).ok())
     }
 
     pub(crate) fn from_string(src: String) -> Option<Self> {
         let bytes = Bytes::from(src);
-        HeaderValue::from_maybe_shared(bytes).ok().map(Self)
+        HeaderValue::from_maybe_shared(bytes)
+            .ok()
+            .and_then(|value| Self::try_from_header_value(&value).ok())
     }
 
     pub(crate) fn as_str(&self) -> &str {
-        // SAFETY: HeaderValueString is only created from HeaderValues that have been
-        // validated to be valid UTF-8 strings. All constructors (`try_from_header_value`,
-        // `from_string`, and `from_static`) ensure the HeaderValue can be represented as
-        // a valid UTF-8 string before wrapping it in HeaderValueString.
-        unsafe { std::str::from_utf8_unchecked(self.0.as_bytes()) }
+        // Every constructor checks that `to_str` succeeds (`from_static` panics otherwise), so this never falls back.
+        self.0.to_str().unwrap_or_default()
     }
 
     pub(crate) fn as_header_value(&self) -> &HeaderValue {
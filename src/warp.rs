@@ -0,0 +1,233 @@
+//! Integration with `warp`.
+//!
+//! - [`header`] and [`optional`] are filters that extract any type in [`crate::request`], and [`request`], [`target`]
+//!   and friends are shortcuts for the individual headers. A missing or invalid header rejects the request like
+//!   `warp::header` does.
+//! - Every type in [`crate::response`] implements `Reply`, and [`with_header`] adds an HTMX response header to any
+//!   `Reply`.
+//! - [`vary`] is a `with`-style wrapper that adds the `Vary` header to replies.
+//!
+//! The filters in this module record the headers they read in a per-request set stored in the request extensions, just
+//! like the `axum` extractors do, and [`vary`] adds these headers to `Vary`. Warp filters cannot add extensions to a
+//! request, so the set is created by [`HxAutoVaryLayer`](crate::HxAutoVaryLayer): serve the routes with
+//! `warp::service` wrapped in the layer for an exact `Vary` header. The layer also adds the recorded headers to the
+//! responses of rejected requests, which [`vary`] never sees. For requests without the set, such as routes served with
+//! `warp::serve`, [`vary`] adds every built-in HTMX request header instead. This is always correct for caches, but may
+//! lower the hit rate.
+//!
+//! ```rust,no_run
+//! use htmxtools::{
+//!     request::{HxSource, HxTarget},
+//!     response::HxTrigger,
+//!     warp as htmx,
+//! };
+//! use warp::Filter;
+//!
+//! let routes = warp::path("search")
+//!     .and(htmx::target())
+//!     .and(htmx::optional::<HxSource>())
+//!     .map(|target: HxTarget, source: Option<HxSource>| {
+//!         let body = format!("target: {}, source: {:?}", target.tag(), source.map(|s| s.tag().to_string()));
+//!         htmx::with_header(body, HxTrigger::new().with_event("searched".to_string()))
+//!     })
+//!     .with(warp::wrap_fn(htmx::vary));
+//! ```
+use std::{convert::Infallible, iter, str::FromStr};
+
+use headers_core::{Error, Header};
+use http::{Extensions, HeaderMap, HeaderValue};
+use warp::{Filter, Rejection, Reply, reply::Response};
+
+use crate::{
+    auto_vary::SharedSet,
+    request::{
+        HxBoosted, HxCurrentUrl, HxHeader, HxHistoryRestoreRequest, HxRequest, HxRequestHeader,
+        HxRequestKind, HxRequestType, HxSource, HxTarget,
+    },
+    response::{
+        HxLocation, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl, HxReselect, HxReswap,
+        HxRetarget, HxTrigger, HxTriggerAfterSettle, HxTriggerAfterSwap,
+    },
+    util::header_list,
+};
+
+/// A header value parsed through `FromStr`, so that `warp::header` produces its usual rejections.
+struct Parsed<H>(H);
+
+impl<H: Header> FromStr for Parsed<H> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = HeaderValue::from_str(s).map_err(|_| Error::invalid())?;
+        H::decode(&mut iter::once(&value)).map(Parsed)
+    }
+}
+
+/// Records the header `H` for auto vary, if the request has an auto vary set.
+fn record<H: HxHeader>() -> impl Filter<Extract = (), Error = Infallible> + Clone {
+    warp::ext::optional::<SharedSet>()
+        .map(|set: Option<SharedSet>| {
+            if let Some(set) = set
+                && let Ok(mut lock) = set.lock()
+            {
+                lock.add(H::KIND);
+            }
+        })
+        .untuple_one()
+}
+
+/// Extracts the HTMX request header `H`, rejecting the request if it is missing or invalid.
+pub fn header<H: HxHeader>() -> impl Filter<Extract = (H,), Error = Rejection> + Clone {
+    record::<H>()
+        .and(warp::header::header::<Parsed<H>>(H::name().as_str()))
+        .map(|Parsed(header)| header)
+}
+
+/// Extracts the HTMX request header `H` if it is present, rejecting the request if it is invalid.
+pub fn optional<H: HxHeader>() -> impl Filter<Extract = (Option<H>,), Error = Rejection> + Clone {
+    record::<H>()
+        .and(warp::header::optional::<Parsed<H>>(H::name().as_str()))
+        .map(|header: Option<Parsed<H>>| header.map(|Parsed(header)| header))
+}
+
+/// Extracts the `hx-boosted` header.
+pub fn boosted() -> impl Filter<Extract = (HxBoosted,), Error = Rejection> + Clone {
+    header::<HxBoosted>()
+}
+
+/// Extracts the `hx-current-url` header.
+pub fn current_url() -> impl Filter<Extract = (HxCurrentUrl,), Error = Rejection> + Clone {
+    header::<HxCurrentUrl>()
+}
+
+/// Extracts the `hx-history-restore-request` header.
+pub fn history_restore_request()
+-> impl Filter<Extract = (HxHistoryRestoreRequest,), Error = Rejection> + Clone {
+    header::<HxHistoryRestoreRequest>()
+}
+
+/// Extracts the `hx-request` header.
+pub fn request() -> impl Filter<Extract = (HxRequest,), Error = Rejection> + Clone {
+    header::<HxRequest>()
+}
+
+/// Extracts the `hx-request-type` header.
+pub fn request_type() -> impl Filter<Extract = (HxRequestType,), Error = Rejection> + Clone {
+    header::<HxRequestType>()
+}
+
+/// Extracts the `hx-source` header.
+pub fn source() -> impl Filter<Extract = (HxSource,), Error = Rejection> + Clone {
+    header::<HxSource>()
+}
+
+/// Extracts the `hx-target` header.
+pub fn target() -> impl Filter<Extract = (HxTarget,), Error = Rejection> + Clone {
+    header::<HxTarget>()
+}
+
+/// Extracts the [`HxRequestKind`] of the request. This filter never rejects.
+pub fn kind() -> impl Filter<Extract = (HxRequestKind,), Error = Infallible> + Clone {
+    warp::header::headers_cloned()
+        .and(warp::ext::optional::<SharedSet>())
+        .map(|headers: HeaderMap, set: Option<SharedSet>| {
+            let mut extensions = Extensions::new();
+            if let Some(set) = set {
+                extensions.insert(set);
+            }
+
+            HxRequestKind::classify(&headers, &extensions)
+        })
+}
+
+macro_rules! impl_reply {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Reply for $ty {
+                fn into_response(self) -> Response {
+                    with_header(warp::reply(), self).into_response()
+                }
+            }
+        )*
+    };
+}
+
+impl_reply!(
+    HxLocation,
+    HxPushUrl,
+    HxRedirect,
+    HxRefresh,
+    HxReplaceUrl,
+    HxReselect,
+    HxReswap,
+    HxRetarget,
+    HxTrigger,
+    HxTriggerAfterSettle,
+    HxTriggerAfterSwap,
+);
+
+/// Wraps a reply to append the values of an HTMX response header.
+pub fn with_header<R: Reply, H: Header + Send>(reply: R, header: H) -> WithHxHeader<R, H> {
+    WithHxHeader { reply, header }
+}
+
+/// A reply with an HTMX response header. See [`with_header`].
+#[derive(Debug)]
+pub struct WithHxHeader<R, H> {
+    reply: R,
+    header: H,
+}
+
+impl<R: Reply, H: Header + Send> Reply for WithHxHeader<R, H> {
+    fn into_response(self) -> Response {
+        let mut response = self.reply.into_response();
+
+        let mut values = Vec::new();
+        self.header.encode(&mut values);
+        for value in values {
+            response.headers_mut().append(H::name(), value);
+        }
+
+        response
+    }
+}
+
+/// Wraps a filter to add the `Vary` header to its replies, listing the HTMX request headers read by the filters of this
+/// module. Use it with `warp::wrap_fn`. See the [module documentation](self) for requests without a recorded set.
+pub fn vary<F, R>(filter: F) -> impl Filter<Extract = (WithVary<R>,), Error = Rejection> + Clone
+where
+    F: Filter<Extract = (R,), Error = Rejection> + Clone + Send,
+    R: Reply,
+{
+    warp::ext::optional::<SharedSet>()
+        .and(filter)
+        .map(|set: Option<SharedSet>, reply| WithVary { reply, set })
+}
+
+/// A reply with the `Vary` header for the HTMX request headers read by the filters of this module. See [`vary`].
+#[derive(Debug)]
+pub struct WithVary<R> {
+    reply: R,
+    set: Option<SharedSet>,
+}
+
+impl<R: Reply> Reply for WithVary<R> {
+    fn into_response(self) -> Response {
+        let mut response = self.reply.into_response();
+
+        match self.set {
+            Some(set) => {
+                if let Ok(lock) = set.lock() {
+                    lock.add_to_headers(response.headers_mut());
+                }
+            }
+            None => {
+                for header in HxRequestHeader::iter() {
+                    header_list::append_vary(response.headers_mut(), header.name());
+                }
+            }
+        }
+
+        response
+    }
+}
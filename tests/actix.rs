@@ -0,0 +1,200 @@
+use actix_web::{
+    App, HttpResponse, Responder,
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    error::ErrorConflict,
+    http::{StatusCode, header::VARY},
+    test, web,
+};
+use htmxtools::{
+    actix::{HxAutoVary, HxResponseBuilderExt},
+    request::{HxRequest, HxRequestKind, HxTarget},
+    response::{HxRetarget, HxTrigger},
+};
+
+async fn target(target: HxTarget) -> String {
+    target.as_str().to_string()
+}
+
+async fn optional_target(target: Option<HxTarget>) -> String {
+    target.map_or_else(
+        || "missing".to_string(),
+        |target| target.as_str().to_string(),
+    )
+}
+
+async fn request(_: HxRequest) -> &'static str {
+    "htmx"
+}
+
+async fn optional_request(request: Option<HxRequest>) -> &'static str {
+    if request.is_some() { "htmx" } else { "page" }
+}
+
+async fn kind(kind: HxRequestKind) -> String {
+    format!("{kind:?}")
+}
+
+async fn declared(_: HxTarget) -> impl Responder {
+    HttpResponse::Ok()
+        .append_header((VARY, "HX-Target"))
+        .finish()
+}
+
+async fn any(_: HxTarget) -> impl Responder {
+    HttpResponse::Ok().append_header((VARY, "*")).finish()
+}
+
+async fn trigger() -> impl Responder {
+    HttpResponse::Ok()
+        .hx_header(HxTrigger::new().with_event("loaded".to_string()))
+        .body("loaded")
+}
+
+async fn retarget() -> HxRetarget {
+    HxRetarget::from_static("#errors")
+}
+
+async fn call(
+    uri: &str,
+    headers: &[(&'static str, &'static str)],
+) -> ServiceResponse<impl MessageBody> {
+    let app = test::init_service(
+        App::new()
+            .wrap(HxAutoVary::new())
+            .route("/target", web::get().to(target))
+            .route("/optional", web::get().to(optional_target))
+            .route("/request", web::get().to(request))
+            .route("/optional-request", web::get().to(optional_request))
+            .route("/kind", web::get().to(kind))
+            .route("/declared", web::get().to(declared))
+            .route("/any", web::get().to(any))
+            .route("/trigger", web::get().to(trigger))
+            .route("/retarget", web::get().to(retarget)),
+    )
+    .await;
+
+    let mut request = test::TestRequest::get().uri(uri);
+    for header in headers {
+        request = request.insert_header(*header);
+    }
+    app.call(request.to_request()).await.unwrap()
+}
+
+fn vary<B>(response: &ServiceResponse<B>) -> Vec<&str> {
+    let mut names: Vec<_> = response
+        .headers()
+        .get_all(VARY)
+        .map(|value| value.to_str().unwrap())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+async fn body<B: MessageBody>(response: ServiceResponse<B>) -> String {
+    String::from_utf8(test::read_body(response).await.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn extracts_headers() {
+    let response = call("/target", &[("hx-target", "div#items")]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(response).await, "div#items");
+
+    let response = call("/optional", &[]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(response).await, "missing");
+
+    let response = call("/kind", &[("hx-request", "true"), ("hx-boosted", "true")]).await;
+    assert_eq!(body(response).await, "Boosted");
+}
+
+#[tokio::test]
+async fn rejects_missing_and_invalid_headers() {
+    let response = call("/target", &[]).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(vary(&response), ["hx-target"]);
+
+    let response = call("/request", &[("hx-request", "false")]).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = call("/optional-request", &[("hx-request", "false")]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(response).await, "page");
+}
+
+#[tokio::test]
+async fn returns_errors_with_vary() {
+    let app = test::init_service(
+        App::new()
+            .wrap_fn(|req, srv| {
+                let fut = srv.call(req);
+                async move {
+                    fut.await?;
+                    Err::<ServiceResponse, _>(ErrorConflict("conflict"))
+                }
+            })
+            .wrap(HxAutoVary::new())
+            .route("/target", web::get().to(target)),
+    )
+    .await;
+
+    let request = test::TestRequest::get()
+        .uri("/target")
+        .insert_header(("hx-target", "div#items"))
+        .to_request();
+    let Err(error) = app.call(request).await else {
+        panic!("the inner error is returned");
+    };
+
+    let response = error.error_response();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(response.headers().get(VARY).unwrap(), "hx-target");
+}
+
+#[tokio::test]
+async fn varies_by_consumed_headers_only() {
+    let headers = [("hx-request", "true"), ("hx-target", "div#items")];
+
+    let response = call("/target", &headers).await;
+    assert_eq!(vary(&response), ["hx-target"]);
+
+    let response = call("/kind", &headers).await;
+    assert_eq!(
+        vary(&response),
+        [
+            "hx-boosted",
+            "hx-history-restore-request",
+            "hx-request",
+            "hx-request-type"
+        ]
+    );
+
+    let response = call("/kind", &[]).await;
+    assert_eq!(vary(&response), ["hx-request"]);
+
+    let response = call("/trigger", &headers).await;
+    assert!(vary(&response).is_empty());
+}
+
+#[tokio::test]
+async fn keeps_declared_vary() {
+    let headers = [("hx-target", "div#items")];
+
+    let response = call("/declared", &headers).await;
+    assert_eq!(vary(&response), ["HX-Target"]);
+
+    let response = call("/any", &headers).await;
+    assert_eq!(vary(&response), ["*"]);
+}
+
+#[tokio::test]
+async fn sets_response_headers() {
+    let response = call("/trigger", &[]).await;
+    assert_eq!(response.headers().get("hx-trigger").unwrap(), "loaded");
+    assert_eq!(body(response).await, "loaded");
+
+    let response = call("/retarget", &[]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("hx-retarget").unwrap(), "#errors");
+}
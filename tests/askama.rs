@@ -0,0 +1,175 @@
+use askama::Template;
+use axum::{Router, routing::get};
+use htmxtools::{
+    askama::{HxBlocks, HxTemplate},
+    request::{HxRequestKind, HxTarget},
+    testing::{HtmxResponseAssertions, HtmxTestClient, HtmxTestRequest},
+};
+use http::{StatusCode, header::CONTENT_TYPE};
+
+#[derive(Template)]
+#[template(
+    source = r#"<html><body>{% block items %}<ul id="items">{% for item in items %}<li>{{ item }}</li>{% endfor %}</ul>{% endblock %}{% block item_count %}<span id="item-count">{{ items.len() }}</span>{% endblock %}</body></html>"#,
+    ext = "html",
+    blocks = ["items", "item_count"]
+)]
+struct ItemsPage {
+    items: Vec<&'static str>,
+}
+
+impl HxBlocks for ItemsPage {
+    fn render_block(&self, name: &str) -> Option<askama::Result<String>> {
+        match name {
+            "items" => Some(self.as_items().render()),
+            "item_count" => Some(self.as_item_count().render()),
+            _ => None,
+        }
+    }
+}
+
+fn page() -> ItemsPage {
+    ItemsPage {
+        items: vec!["Milk", "<Eggs>"],
+    }
+}
+
+const FULL: &str = r#"<html><body><ul id="items"><li>Milk</li><li>&#60;Eggs&#62;</li></ul><span id="item-count">2</span></body></html>"#;
+const ITEMS: &str = r#"<ul id="items"><li>Milk</li><li>&#60;Eggs&#62;</li></ul>"#;
+
+fn render(kind: HxRequestKind, target: Option<&'static str>) -> String {
+    HxTemplate::new(page(), kind, target.map(HxTarget::from_static))
+        .render()
+        .unwrap()
+}
+
+#[test]
+fn renders_full_template_for_full_pages() {
+    for kind in [
+        HxRequestKind::Page,
+        HxRequestKind::HistoryRestore,
+        HxRequestKind::Boosted,
+        HxRequestKind::Full,
+    ] {
+        assert_eq!(render(kind, Some("ul#items")), FULL, "{kind:?}");
+    }
+}
+
+#[test]
+fn renders_block_named_after_target() {
+    assert_eq!(render(HxRequestKind::Partial, Some("ul#items")), ITEMS);
+    assert_eq!(
+        render(HxRequestKind::Partial, Some("span#item-count")),
+        r#"<span id="item-count">2</span>"#
+    );
+}
+
+#[test]
+fn falls_back_to_full_template() {
+    assert_eq!(render(HxRequestKind::Partial, Some("div#missing")), FULL);
+    assert_eq!(render(HxRequestKind::Partial, Some("body")), FULL);
+    assert_eq!(render(HxRequestKind::Partial, None), FULL);
+}
+
+#[test]
+fn renders_explicit_block() {
+    let template = HxTemplate::new(
+        page(),
+        HxRequestKind::Partial,
+        Some(HxTarget::from_static("span#item-count")),
+    )
+    .with_block("items");
+    assert_eq!(template.render().unwrap(), ITEMS);
+
+    let template = HxTemplate::new(page(), HxRequestKind::Partial, None).with_block("missing");
+    assert!(template.render().is_err());
+}
+
+#[test]
+fn into_http_adds_headers() {
+    let response = HxTemplate::new(
+        page(),
+        HxRequestKind::Partial,
+        Some(HxTarget::from_static("ul#items")),
+    )
+    .into_http()
+    .unwrap();
+
+    assert_eq!(response.body(), ITEMS);
+    assert_eq!(response.headers()[CONTENT_TYPE], "text/html; charset=utf-8");
+    response
+        .assert_vary_contains("hx-request-type")
+        .assert_vary_contains("hx-target");
+}
+
+#[tokio::test]
+async fn responds_from_axum() {
+    let app = Router::new().route(
+        "/items",
+        get(|kind: HxRequestKind, target: Option<HxTarget>| async move {
+            HxTemplate::new(page(), kind, target)
+        }),
+    );
+    let mut client = HtmxTestClient::new(app);
+
+    let response = client.navigate("/items").await;
+    assert_eq!(response.body(), FULL);
+
+    let response = client
+        .send(HtmxTestRequest::click("/items").with_target(HxTarget::from_static("ul#items")))
+        .await;
+    assert_eq!(response.body(), ITEMS);
+    response.assert_vary_contains("hx-target");
+}
+
+#[test]
+fn responds_with_500_on_render_errors() {
+    use axum::response::IntoResponse;
+
+    let response = HxTemplate::new(page(), HxRequestKind::Partial, None)
+        .with_block("missing")
+        .into_response();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn logs_render_errors() {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::response::IntoResponse;
+
+    #[derive(Clone, Default)]
+    struct Logs(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Logs {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let logs = Logs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, || {
+        HxTemplate::new(page(), HxRequestKind::Partial, None)
+            .with_block("missing")
+            .into_response()
+    });
+
+    let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+    assert!(logs.contains("ERROR"), "{logs}");
+    assert!(logs.contains("failed to render the template"), "{logs}");
+    assert!(logs.contains("block=Some(\"missing\")"), "{logs}");
+}
@@ -0,0 +1,119 @@
+use axum::{
+    Router,
+    extract::{FromRequestParts, Request},
+    routing::get,
+};
+use htmxtools::{
+    HxAutoVaryExt, HxAutoVaryLayer, HxHeaders, HxRequestHeaderSet,
+    request::HxRequestHeader,
+    testing::{HtmxTestClient, HtmxTestRequest},
+};
+use http::{
+    HeaderMap, HeaderName,
+    header::{ACCEPT_LANGUAGE, COOKIE, VARY},
+    request::Parts,
+};
+
+fn vary(headers: &HeaderMap) -> Vec<&str> {
+    headers
+        .get_all(VARY)
+        .iter()
+        .map(|value| value.to_str().unwrap())
+        .collect()
+}
+
+/// A custom extractor that takes part in auto vary through `&mut Parts`.
+struct Language;
+
+impl<S: Send + Sync> FromRequestParts<S> for Language {
+    type Rejection = ();
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        parts.hx_auto_vary_add_name(ACCEPT_LANGUAGE);
+        parts.hx_auto_vary_add(HxRequestHeader::Target);
+        Ok(Language)
+    }
+}
+
+#[test]
+fn header_set_tracks_builtin_and_other_names() {
+    let mut set = HxRequestHeaderSet::new();
+    assert!(set.is_empty());
+
+    set.add(HxRequestHeader::Target);
+    set.add_name(HeaderName::from_static("hx-request"));
+    set.add_name(COOKIE);
+    set.add_name(COOKIE);
+
+    assert!(set.contains(HxRequestHeader::Request));
+    assert!(set.contains_name(&HeaderName::from_static("hx-target")));
+    assert!(set.contains_name(&COOKIE));
+    assert!(!set.contains(HxRequestHeader::Boosted));
+    assert!(!set.contains_name(&ACCEPT_LANGUAGE));
+    assert_eq!(
+        set.iter().map(HeaderName::as_str).collect::<Vec<_>>(),
+        ["hx-request", "hx-target", "cookie"]
+    );
+}
+
+#[test]
+fn is_a_no_op_without_layer() {
+    let request = http::Request::new(());
+    request.hx_auto_vary_add(HxRequestHeader::Request);
+    request.hx_auto_vary_add_name(COOKIE);
+    assert_eq!(request.hx_auto_vary_headers(), None);
+}
+
+#[tokio::test]
+async fn records_from_request_and_parts() {
+    let app = Router::new()
+        .route(
+            "/request",
+            get(|request: Request| async move {
+                request.hx_auto_vary_add(HxRequestHeader::Request);
+                request.hx_auto_vary_add_name(COOKIE);
+
+                let set = request.hx_auto_vary_headers().unwrap();
+                set.iter()
+                    .map(HeaderName::as_str)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }),
+        )
+        .route("/parts", get(|_: Language| async {}))
+        .route("/none", get(|| async {}))
+        .layer(HxAutoVaryLayer::new());
+    let mut client = HtmxTestClient::new(app);
+
+    let response = client.send(HtmxTestRequest::click("/request")).await;
+    assert_eq!(response.body(), "hx-request,cookie");
+    assert_eq!(vary(response.headers()), ["hx-request", "cookie"]);
+
+    let response = client.send(HtmxTestRequest::click("/parts")).await;
+    assert_eq!(vary(response.headers()), ["hx-target", "accept-language"]);
+
+    let response = client.send(HtmxTestRequest::click("/none")).await;
+    assert!(vary(response.headers()).is_empty());
+}
+
+#[test]
+fn hx_headers_copy_only_htmx_headers() {
+    let request = http::Request::get("/")
+        .header("hx-trigger", "first")
+        .header("hx-trigger", "second")
+        .header(COOKIE, "session=1")
+        .body(())
+        .unwrap();
+
+    let headers = HxHeaders::from_request(&request);
+    assert_eq!(
+        headers
+            .get_all("hx-trigger")
+            .unwrap()
+            .iter()
+            .collect::<Vec<_>>(),
+        ["first", "second"]
+    );
+    assert!(!headers.contains_key(COOKIE));
+    assert_eq!(headers.into_untracked().len(), 2);
+}
@@ -0,0 +1,143 @@
+use std::{
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use axum::{Router, body::Body, routing::get};
+use bytes::Bytes;
+use htmxtools::{
+    HxAutoVaryLayer, HxHeaders,
+    testing::{HtmxTestClient, HtmxTestRequest},
+};
+use http::{HeaderMap, StatusCode, header::VARY};
+use http_body::{Frame, SizeHint};
+
+/// Collects the log output of a test.
+#[derive(Clone, Default)]
+struct Logs(Arc<Mutex<Vec<u8>>>);
+
+impl Logs {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl io::Write for Logs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A body of a known size that fails to be read.
+struct Failing;
+
+impl http_body::Body for Failing {
+    type Data = Bytes;
+
+    type Error = io::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        Poll::Ready(Some(Err(io::Error::other("connection reset"))))
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(8)
+    }
+}
+
+fn fragment(has_request: bool) -> &'static str {
+    if has_request { "fragment" } else { "page" }
+}
+
+fn app() -> Router {
+    Router::new()
+        .route(
+            "/raw",
+            get(|headers: HeaderMap| async move { fragment(headers.contains_key("hx-request")) }),
+        )
+        .route(
+            "/tracked",
+            get(|headers: HxHeaders| async move { fragment(headers.contains_key("hx-request")) }),
+        )
+        .route("/static", get(|| async { "page" }))
+        .route(
+            "/failing",
+            get(|| async {
+                (
+                    StatusCode::ACCEPTED,
+                    [("x-kept", "yes")],
+                    Body::new(Failing),
+                )
+            }),
+        )
+        .layer(HxAutoVaryLayer::debug())
+}
+
+#[tokio::test]
+async fn warns_only_for_undeclared_dependencies() {
+    let logs = Logs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+    let mut client = HtmxTestClient::new(app());
+
+    let response = client.send(HtmxTestRequest::click("/static")).await;
+    assert_eq!(response.body(), "page");
+    assert!(response.headers().get(VARY).is_none());
+
+    let response = client.send(HtmxTestRequest::click("/tracked")).await;
+    assert_eq!(response.body(), "fragment");
+    assert_eq!(response.headers()[VARY], "hx-request");
+
+    let response = client.navigate("/raw").await;
+    assert_eq!(response.body(), "page");
+    assert!(logs.contents().is_empty(), "{}", logs.contents());
+
+    let response = client.send(HtmxTestRequest::click("/raw")).await;
+    assert_eq!(response.body(), "fragment");
+    assert!(response.headers().get(VARY).is_none());
+
+    let logged = logs.contents();
+    assert_eq!(logged.matches("WARN").count(), 1, "{logged}");
+    assert!(logged.contains("request=\"GET /raw\""), "{logged}");
+    assert!(logged.contains("hx-request"), "{logged}");
+}
+
+#[tokio::test]
+async fn keeps_the_response_when_the_body_cannot_be_read() {
+    let logs = Logs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+    let mut client = HtmxTestClient::new(app());
+
+    let response = client.send(HtmxTestRequest::click("/failing")).await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    assert_eq!(response.headers()["x-kept"], "yes");
+    assert_eq!(response.body(), "");
+
+    let logged = logs.contents();
+    assert!(logged.contains("ERROR"), "{logged}");
+    assert!(logged.contains("request=GET /failing"), "{logged}");
+}
+
+#[test]
+fn keeps_unit_layer() {
+    let _: Router = Router::new().layer(HxAutoVaryLayer);
+}
@@ -0,0 +1,298 @@
+use std::{
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::{Context, Poll},
+};
+
+use axum::{
+    Router,
+    body::{Body, Bytes},
+    extract::State,
+    routing::get,
+};
+use htmxtools::{HxAutoVaryLayer, HxCacheLayer, HxCacheStore, request::HtmxRequestExt};
+use http::{
+    Request, Response, StatusCode,
+    header::{CACHE_CONTROL, CONTENT_TYPE, VARY},
+};
+use http_body::{Frame, SizeHint};
+use tower_service::Service;
+
+/// A body that yields a single chunk, announcing its size only if `exact` is set.
+struct Chunk {
+    chunk: Option<Result<Bytes, &'static str>>,
+    exact: bool,
+}
+
+impl Chunk {
+    fn stream(data: &'static str) -> Body {
+        Body::new(Self {
+            chunk: Some(Ok(Bytes::from(data))),
+            exact: false,
+        })
+    }
+
+    fn broken() -> Body {
+        Body::new(Self {
+            chunk: Some(Err("broken")),
+            exact: true,
+        })
+    }
+}
+
+impl http_body::Body for Chunk {
+    type Data = Bytes;
+    type Error = &'static str;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Self::Error>>> {
+        Poll::Ready(self.chunk.take().map(|chunk| chunk.map(Frame::data)))
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        if self.exact {
+            SizeHint::with_exact(6)
+        } else {
+            SizeHint::default()
+        }
+    }
+}
+
+type Calls = Arc<AtomicUsize>;
+
+struct App {
+    router: Router,
+    calls: Calls,
+    store: HxCacheStore,
+}
+
+impl App {
+    fn new(cache: HxCacheLayer) -> Self {
+        let calls = Calls::default();
+        let store = cache.store();
+
+        let router = Router::new()
+            .route(
+                "/items",
+                get(
+                    |State(calls): State<Calls>, request: Request<Body>| async move {
+                        let call = calls.fetch_add(1, Ordering::SeqCst);
+                        format!("{:?} {call}", request.hx_request_kind())
+                    },
+                ),
+            )
+            .route(
+                "/items/{id}",
+                get(|State(calls): State<Calls>| async move {
+                    let call = calls.fetch_add(1, Ordering::SeqCst);
+                    format!("item {call}")
+                }),
+            )
+            .route(
+                "/itemsfoo",
+                get(|State(calls): State<Calls>| async move {
+                    let call = calls.fetch_add(1, Ordering::SeqCst);
+                    format!("itemsfoo {call}")
+                }),
+            )
+            .route(
+                "/public",
+                get(|State(calls): State<Calls>| async move {
+                    let call = calls.fetch_add(1, Ordering::SeqCst);
+                    ([(CACHE_CONTROL, "s-maxage=60")], format!("public {call}"))
+                }),
+            )
+            .route(
+                "/cookie",
+                get(|State(calls): State<Calls>| async move {
+                    let call = calls.fetch_add(1, Ordering::SeqCst);
+                    ([(VARY, "Cookie")], format!("cookie {call}"))
+                }),
+            )
+            .route(
+                "/any",
+                get(|State(calls): State<Calls>| async move {
+                    let call = calls.fetch_add(1, Ordering::SeqCst);
+                    ([(VARY, "*")], format!("any {call}"))
+                }),
+            )
+            .route(
+                "/events",
+                get(|State(calls): State<Calls>| async move {
+                    let call = calls.fetch_add(1, Ordering::SeqCst);
+                    (
+                        [(CONTENT_TYPE, "text/event-stream")],
+                        format!("data: {call}\n\n"),
+                    )
+                }),
+            )
+            .route(
+                "/stream",
+                get(|State(calls): State<Calls>| async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Chunk::stream("stream")
+                }),
+            )
+            .route("/error", get(|| async { Chunk::broken() }))
+            .with_state(calls.clone())
+            .layer(cache)
+            .layer(HxAutoVaryLayer);
+
+        Self {
+            router,
+            calls,
+            store,
+        }
+    }
+
+    async fn get(&mut self, uri: &str, headers: &[(&'static str, &str)]) -> Response<String> {
+        let mut request = Request::get(uri);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+
+        let response = self
+            .router
+            .call(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let (parts, body) = response.into_parts();
+        let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        Response::from_parts(parts, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    /// Requests `uri` twice and returns `true` if the second response was served from the cache.
+    async fn is_cached(&mut self, uri: &str, headers: &[(&'static str, &str)]) -> bool {
+        self.get(uri, headers).await;
+        let calls = self.calls.load(Ordering::SeqCst);
+        self.get(uri, headers).await;
+
+        self.calls.load(Ordering::SeqCst) == calls
+    }
+}
+
+#[tokio::test]
+async fn caches_each_htmx_variant_separately() {
+    let mut app = App::new(HxCacheLayer::new());
+
+    let page = app.get("/items", &[]).await;
+    assert_eq!(page.body(), "Page 0");
+    assert_eq!(page.headers()[VARY], "hx-request");
+
+    let partial = app.get("/items", &[("hx-request", "true")]).await;
+    assert_eq!(partial.body(), "Partial 1");
+
+    assert_eq!(app.get("/items", &[]).await.body(), "Page 0");
+    let cached = app.get("/items", &[("hx-request", "true")]).await;
+    assert_eq!(cached.body(), "Partial 1");
+    assert_eq!(
+        cached.headers().get_all(VARY).iter().collect::<Vec<_>>(),
+        partial.headers().get_all(VARY).iter().collect::<Vec<_>>()
+    );
+    assert_eq!(app.store.len(), 2);
+
+    app.store.invalidate_prefix("/items");
+    assert!(app.store.is_empty());
+    assert_eq!(app.get("/items", &[]).await.body(), "Page 2");
+}
+
+#[tokio::test]
+async fn invalidates_whole_path_segments() {
+    let mut app = App::new(HxCacheLayer::new());
+
+    assert_eq!(app.get("/items", &[]).await.body(), "Page 0");
+    assert_eq!(app.get("/items/1", &[]).await.body(), "item 1");
+    assert_eq!(app.get("/itemsfoo", &[]).await.body(), "itemsfoo 2");
+    assert_eq!(app.store.len(), 3);
+
+    app.store.invalidate_prefix("/items");
+    assert_eq!(app.store.len(), 1);
+    assert_eq!(app.get("/itemsfoo", &[]).await.body(), "itemsfoo 2");
+    assert_eq!(app.get("/items/1", &[]).await.body(), "item 3");
+
+    app.store.invalidate_prefix("/items/");
+    assert_eq!(app.store.len(), 1);
+    assert_eq!(app.get("/itemsfoo", &[]).await.body(), "itemsfoo 2");
+}
+
+#[tokio::test]
+async fn evicts_the_oldest_entries() {
+    let mut app = App::new(HxCacheLayer::new().max_entries(2));
+
+    assert_eq!(app.get("/items", &[]).await.body(), "Page 0");
+    assert_eq!(app.get("/items/1", &[]).await.body(), "item 1");
+    let partial = app.get("/items", &[("hx-request", "true")]).await;
+    assert_eq!(partial.body(), "Partial 2");
+    assert_eq!(app.store.len(), 2);
+
+    assert_eq!(app.get("/items/1", &[]).await.body(), "item 1");
+    let partial = app.get("/items", &[("hx-request", "true")]).await;
+    assert_eq!(partial.body(), "Partial 2");
+    assert_eq!(app.get("/items", &[]).await.body(), "Page 3");
+}
+
+#[tokio::test]
+async fn skips_responses_varying_by_anything() {
+    let mut app = App::new(HxCacheLayer::new());
+    assert!(!app.is_cached("/any", &[]).await);
+    assert!(app.store.is_empty());
+}
+
+#[tokio::test]
+async fn shares_authorized_responses_only_if_public() {
+    let mut app = App::new(HxCacheLayer::new());
+    let authorized = [("authorization", "Bearer secret")];
+
+    assert!(!app.is_cached("/items", &authorized).await);
+    assert!(app.store.is_empty());
+
+    assert!(app.is_cached("/items", &[]).await);
+    assert!(!app.is_cached("/items", &authorized).await);
+
+    assert!(app.is_cached("/public", &authorized).await);
+    assert!(app.is_cached("/public", &[]).await);
+}
+
+#[tokio::test]
+async fn shares_cookie_responses_only_if_varying_by_cookie() {
+    let mut app = App::new(HxCacheLayer::new());
+    let alice = [("cookie", "session=alice")];
+    let bob = [("cookie", "session=bob")];
+
+    assert!(!app.is_cached("/items", &alice).await);
+    assert!(app.store.is_empty());
+
+    assert!(app.is_cached("/items", &[]).await);
+    assert!(!app.is_cached("/items", &alice).await);
+
+    assert!(app.is_cached("/cookie", &alice).await);
+    let alice_response = app.get("/cookie", &alice).await;
+    let bob_response = app.get("/cookie", &bob).await;
+    assert_ne!(alice_response.body(), bob_response.body());
+    assert!(app.is_cached("/cookie", &bob).await);
+}
+
+#[tokio::test]
+async fn skips_unbuffered_responses() {
+    let mut app = App::new(HxCacheLayer::new());
+    assert!(!app.is_cached("/events", &[]).await);
+    assert!(!app.is_cached("/stream", &[]).await);
+    assert_eq!(app.get("/stream", &[]).await.body(), "stream");
+
+    let mut app = App::new(HxCacheLayer::new().max_entry_size(4));
+    assert!(!app.is_cached("/items", &[]).await);
+    assert!(app.store.is_empty());
+}
+
+#[tokio::test]
+async fn fails_on_body_errors() {
+    let mut app = App::new(HxCacheLayer::new());
+    let response = app.get("/error", &[]).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(app.store.is_empty());
+}
@@ -0,0 +1,156 @@
+use axum::{Router, response::IntoResponse, routing::get};
+use htmxtools::{
+    HxAutoVaryLayer, HxCacheControlLayer,
+    request::HxRequestKind,
+    testing::{HtmxTestClient, HtmxTestRequest},
+};
+use http::{
+    HeaderMap, HeaderValue, Response,
+    header::{CACHE_CONTROL, VARY},
+};
+
+const KINDS: [HxRequestKind; 5] = [
+    HxRequestKind::Page,
+    HxRequestKind::HistoryRestore,
+    HxRequestKind::Boosted,
+    HxRequestKind::Full,
+    HxRequestKind::Partial,
+];
+
+fn vary(headers: &HeaderMap) -> Vec<&str> {
+    let mut names: Vec<_> = headers
+        .get_all(VARY)
+        .iter()
+        .map(|value| value.to_str().unwrap())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+fn consulted(kind: HxRequestKind) -> Vec<&'static str> {
+    let mut names: Vec<_> = kind
+        .consulted_headers()
+        .iter()
+        .map(|header| header.name().as_str())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+async fn send(
+    client: &mut HtmxTestClient<Router>,
+    kind: HxRequestKind,
+    uri: &str,
+) -> Response<String> {
+    let request = match kind {
+        HxRequestKind::Page => return client.navigate(uri).await,
+        HxRequestKind::HistoryRestore => HtmxTestRequest::history_restore(uri),
+        HxRequestKind::Boosted => HtmxTestRequest::boosted(uri),
+        HxRequestKind::Full => HtmxTestRequest::click(uri).full(),
+        HxRequestKind::Partial => HtmxTestRequest::click(uri),
+    };
+    client.send(request).await
+}
+
+fn client(layer: HxCacheControlLayer) -> HtmxTestClient<Router> {
+    let app = Router::new()
+        .route("/", get(|| async {}))
+        .route(
+            "/private",
+            get(|| async { [(CACHE_CONTROL, "private")].into_response() }),
+        )
+        .layer(layer)
+        .layer(HxAutoVaryLayer);
+    HtmxTestClient::new(app)
+}
+
+#[tokio::test]
+async fn applies_policy_per_kind() {
+    let mut client = client(
+        HxCacheControlLayer::new()
+            .page(HeaderValue::from_static("max-age=60"))
+            .history_restore(HeaderValue::from_static("no-store"))
+            .boosted(HeaderValue::from_static("max-age=30"))
+            .full(HeaderValue::from_static("max-age=10"))
+            .partial(HeaderValue::from_static("no-cache")),
+    );
+
+    for (kind, expected) in KINDS.into_iter().zip([
+        "max-age=60",
+        "no-store",
+        "max-age=30",
+        "max-age=10",
+        "no-cache",
+    ]) {
+        let response = send(&mut client, kind, "/").await;
+        assert_eq!(response.headers()[CACHE_CONTROL], expected, "{kind:?}");
+        assert_eq!(vary(response.headers()), consulted(kind), "{kind:?}");
+    }
+}
+
+#[tokio::test]
+async fn applies_single_policy() {
+    for kind in KINDS {
+        let mut client =
+            client(HxCacheControlLayer::new().policy(kind, HeaderValue::from_static("no-store")));
+
+        for other in KINDS {
+            let response = send(&mut client, other, "/").await;
+            let cache_control = response.headers().get(CACHE_CONTROL);
+            if other == kind {
+                assert_eq!(cache_control.unwrap(), "no-store", "{kind:?}");
+            } else {
+                assert!(cache_control.is_none(), "{kind:?} {other:?}");
+            }
+            assert_eq!(
+                vary(response.headers()),
+                consulted(other),
+                "{kind:?} {other:?}"
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn keeps_existing_header_by_default() {
+    let mut client =
+        client(HxCacheControlLayer::new().partial(HeaderValue::from_static("no-cache")));
+
+    for kind in KINDS {
+        let response = send(&mut client, kind, "/private").await;
+        assert_eq!(response.headers()[CACHE_CONTROL], "private", "{kind:?}");
+        assert!(vary(response.headers()).is_empty(), "{kind:?}");
+    }
+
+    let response = send(&mut client, HxRequestKind::Partial, "/").await;
+    assert_eq!(response.headers()[CACHE_CONTROL], "no-cache");
+    assert_eq!(vary(response.headers()), consulted(HxRequestKind::Partial));
+}
+
+#[tokio::test]
+async fn overrides_existing_header() {
+    let mut client = client(
+        HxCacheControlLayer::new()
+            .partial(HeaderValue::from_static("no-cache"))
+            .override_existing(true),
+    );
+
+    let response = send(&mut client, HxRequestKind::Partial, "/private").await;
+    assert_eq!(response.headers()[CACHE_CONTROL], "no-cache");
+    assert_eq!(vary(response.headers()), consulted(HxRequestKind::Partial));
+
+    let response = send(&mut client, HxRequestKind::Page, "/private").await;
+    assert_eq!(response.headers()[CACHE_CONTROL], "private");
+    assert_eq!(vary(response.headers()), consulted(HxRequestKind::Page));
+}
+
+#[tokio::test]
+async fn does_nothing_without_policy() {
+    let mut client = client(HxCacheControlLayer::new());
+
+    for kind in KINDS {
+        let response = send(&mut client, kind, "/").await;
+        assert!(response.headers().get(CACHE_CONTROL).is_none(), "{kind:?}");
+        assert!(vary(response.headers()).is_empty(), "{kind:?}");
+    }
+}
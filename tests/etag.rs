@@ -0,0 +1,201 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    Router,
+    body::{Body, Bytes},
+    routing::get,
+};
+use htmxtools::{HxAutoVaryLayer, HxETagLayer, request::HtmxRequestExt};
+use http::{
+    HeaderMap, Method, Request, Response, StatusCode,
+    header::{CONTENT_TYPE, ETAG},
+};
+use http_body::{Frame, SizeHint};
+use tower_service::Service;
+
+/// A body that yields a single chunk, announcing its size only if `exact` is set.
+struct Chunk {
+    chunk: Option<Result<Bytes, &'static str>>,
+    exact: bool,
+}
+
+impl Chunk {
+    fn stream(data: &'static str) -> Body {
+        Body::new(Self {
+            chunk: Some(Ok(Bytes::from(data))),
+            exact: false,
+        })
+    }
+
+    fn broken() -> Body {
+        Body::new(Self {
+            chunk: Some(Err("broken")),
+            exact: true,
+        })
+    }
+}
+
+impl http_body::Body for Chunk {
+    type Data = Bytes;
+    type Error = &'static str;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Self::Error>>> {
+        Poll::Ready(self.chunk.take().map(|chunk| chunk.map(Frame::data)))
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        if self.exact {
+            SizeHint::with_exact(6)
+        } else {
+            SizeHint::default()
+        }
+    }
+}
+
+fn app() -> Router {
+    Router::new()
+        .route(
+            "/items",
+            get(|headers: HeaderMap| async move {
+                if headers.contains_key("hx-request") {
+                    "<li>Milk</li>"
+                } else {
+                    "<ul><li>Milk</li></ul>"
+                }
+            }),
+        )
+        .route(
+            "/kind",
+            get(|request: Request<Body>| async move { format!("{:?}", request.hx_request_kind()) }),
+        )
+        .route("/tagged", get(|| async { ([(ETAG, "\"v1\"")], "tagged") }))
+        .route("/stream", get(|| async { Chunk::stream("stream") }))
+        .route(
+            "/events",
+            get(|| async { ([(CONTENT_TYPE, "text/event-stream")], "data: 1\n\n") }),
+        )
+        .route("/error", get(|| async { Chunk::broken() }))
+        .route(
+            "/missing",
+            get(|| async { (StatusCode::NOT_FOUND, "missing") }),
+        )
+        .layer(HxETagLayer::new())
+        .layer(HxAutoVaryLayer)
+}
+
+async fn send(method: Method, uri: &str, headers: &[(&'static str, &str)]) -> Response<String> {
+    let mut request = Request::builder().method(method).uri(uri);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+
+    let response = app()
+        .call(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let (parts, body) = response.into_parts();
+    let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+    Response::from_parts(parts, String::from_utf8(body.to_vec()).unwrap())
+}
+
+async fn etag(uri: &str, headers: &[(&'static str, &str)]) -> String {
+    let response = send(Method::GET, uri, headers).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    response.headers()[ETAG].to_str().unwrap().to_owned()
+}
+
+#[tokio::test]
+async fn adds_weak_etag_depending_on_recorded_headers() {
+    let page = etag("/kind", &[]).await;
+    assert!(page.starts_with("W/\""), "{page}");
+    assert_eq!(etag("/kind", &[]).await, page);
+
+    let partial = etag("/kind", &[("hx-request", "true")]).await;
+    assert_ne!(partial, page);
+    assert_eq!(
+        etag(
+            "/kind",
+            &[("hx-request", "true"), ("hx-target", "ul#items")]
+        )
+        .await,
+        partial
+    );
+}
+
+#[tokio::test]
+async fn answers_conditional_requests() {
+    let tag = etag("/items", &[]).await;
+
+    let response = send(Method::GET, "/items", &[("if-none-match", &tag)]).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers()[ETAG], tag.as_str());
+    assert!(response.body().is_empty());
+
+    let strong = tag.trim_start_matches("W/");
+    let response = send(Method::GET, "/items", &[("if-none-match", strong)]).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    let response = send(Method::GET, "/items", &[("if-none-match", "\"other\", *")]).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    let response = send(Method::GET, "/items", &[("if-none-match", "\"other\"")]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.body(), "<ul><li>Milk</li></ul>");
+}
+
+#[tokio::test]
+async fn handles_head_requests() {
+    let tag = etag("/kind", &[]).await;
+
+    let response = send(Method::HEAD, "/kind", &[]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[ETAG], tag.as_str());
+    assert!(response.body().is_empty());
+
+    let response = send(Method::HEAD, "/kind", &[("if-none-match", &tag)]).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[tokio::test]
+async fn keeps_handler_etag() {
+    let response = send(Method::GET, "/tagged", &[]).await;
+    assert_eq!(response.headers()[ETAG], "\"v1\"");
+
+    let response = send(Method::GET, "/tagged", &[("if-none-match", "W/\"v1\"")]).await;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[tokio::test]
+async fn skips_unbuffered_responses() {
+    for uri in ["/stream", "/events", "/missing"] {
+        let response = send(Method::GET, uri, &[]).await;
+        assert!(response.headers().get(ETAG).is_none(), "{uri}");
+        assert!(!response.body().is_empty(), "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn fails_on_body_errors() {
+    let response = send(Method::GET, "/error", &[]).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(response.headers().get(ETAG).is_none());
+}
+
+#[tokio::test]
+async fn skips_non_get_requests() {
+    let app = Router::new()
+        .route("/", axum::routing::post(|| async { "posted" }))
+        .layer(HxETagLayer::new());
+    let response = app
+        .clone()
+        .call(Request::post("/").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert!(response.headers().get(ETAG).is_none());
+}
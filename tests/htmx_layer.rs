@@ -0,0 +1,285 @@
+use std::{
+    convert::Infallible,
+    future::{Ready, ready},
+    io,
+    pin::Pin,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::{Context, Poll},
+};
+
+use axum::{Router, extract::State, routing::get};
+use bytes::Bytes;
+use htmxtools::{
+    HtmxLayer, HxCacheControlLayer, HxCacheLayer,
+    request::HxRequestKind,
+    testing::{HtmxTestClient, HtmxTestRequest},
+};
+use http::{
+    HeaderMap, HeaderValue, Request, Response, StatusCode,
+    header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH, VARY},
+};
+use http_body::{Frame, SizeHint};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Collects the log output of a test.
+#[derive(Clone, Default)]
+struct Logs(Arc<Mutex<Vec<u8>>>);
+
+impl Logs {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl io::Write for Logs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A body of a known size that fails to be read.
+struct Failing;
+
+impl http_body::Body for Failing {
+    type Data = Bytes;
+
+    type Error = io::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        Poll::Ready(Some(Err(io::Error::other("connection reset"))))
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(8)
+    }
+}
+
+type Calls = Arc<AtomicUsize>;
+
+fn app(layer: impl FnOnce(Router) -> Router) -> (HtmxTestClient<Router>, Calls) {
+    let calls = Calls::default();
+    let router = Router::new()
+        .route(
+            "/items",
+            get(
+                |State(calls): State<Calls>, kind: HxRequestKind| async move {
+                    let call = calls.fetch_add(1, Ordering::SeqCst);
+                    format!("{kind:?} {call}")
+                },
+            ),
+        )
+        .route(
+            "/kind",
+            get(|kind: HxRequestKind| async move { format!("{kind:?}") }),
+        )
+        .route(
+            "/raw",
+            get(|headers: HeaderMap| async move {
+                if headers.contains_key("hx-request") {
+                    "fragment"
+                } else {
+                    "page"
+                }
+            }),
+        )
+        .route("/failing", get(|| async { axum::body::Body::new(Failing) }))
+        .with_state(calls.clone());
+
+    (HtmxTestClient::new(layer(router)), calls)
+}
+
+fn vary(headers: &HeaderMap) -> Vec<&str> {
+    headers
+        .get_all(VARY)
+        .iter()
+        .map(|value| value.to_str().unwrap())
+        .collect()
+}
+
+fn full(router: Router) -> Router {
+    router.layer(
+        HtmxLayer::new()
+            .tracing()
+            .auto_vary()
+            .etag()
+            .cache(HxCacheLayer::new())
+            .cache_control(
+                HxCacheControlLayer::new()
+                    .page(HeaderValue::from_static("max-age=60"))
+                    .partial(HeaderValue::from_static("no-store")),
+            ),
+    )
+}
+
+#[tokio::test]
+async fn does_nothing_by_default() {
+    let (mut client, _) = app(|router| router.layer(HtmxLayer::new()));
+
+    let response = client.send(HtmxTestRequest::click("/items")).await;
+    assert_eq!(response.body(), "Partial 0");
+    assert!(vary(response.headers()).is_empty());
+    assert!(response.headers().get(ETAG).is_none());
+    assert!(response.headers().get(CACHE_CONTROL).is_none());
+}
+
+#[tokio::test]
+async fn cache_sees_cache_control() {
+    let (mut client, calls) = app(full);
+
+    assert_eq!(client.navigate("/items").await.body(), "Page 0");
+    let response = client.navigate("/items").await;
+    assert_eq!(response.body(), "Page 0");
+    assert_eq!(response.headers()[CACHE_CONTROL], "max-age=60");
+
+    // Partial responses get `no-store` from the cache control layer, which runs inside of the cache.
+    client.send(HtmxTestRequest::click("/items")).await;
+    let response = client.send(HtmxTestRequest::click("/items")).await;
+    assert_eq!(response.body(), "Partial 2");
+    assert_eq!(response.headers()[CACHE_CONTROL], "no-store");
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn etag_and_vary_wrap_cache() {
+    let (mut client, calls) = app(full);
+
+    let response = client.navigate("/items").await;
+    let etag = response.headers()[ETAG].clone();
+    assert_eq!(vary(response.headers()), ["hx-request"]);
+
+    // Served from the cache, the response still gets its `ETag` and `Vary` headers.
+    let response = client.navigate("/items").await;
+    assert_eq!(response.headers()[ETAG], etag);
+    assert_eq!(vary(response.headers()), ["hx-request"]);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let response = client.send(HtmxTestRequest::click("/items").full()).await;
+    assert_ne!(response.headers()[ETAG], etag);
+    assert!(vary(response.headers()).contains(&"hx-request-type"));
+}
+
+#[tokio::test]
+async fn etag_answers_conditional_requests_from_cache() {
+    let (client, _) = app(full);
+    let mut router = client.into_inner();
+
+    let request = |etag: Option<&HeaderValue>| {
+        let mut request = Request::get("/items");
+        if let Some(etag) = etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        request.body(axum::body::Body::empty()).unwrap()
+    };
+
+    let response = Service::call(&mut router, request(None)).await.unwrap();
+    let etag = response.headers()[ETAG].clone();
+
+    let response = Service::call(&mut router, request(Some(&etag)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[tokio::test]
+async fn tracing_wraps_auto_vary_debug() {
+    let logs = Logs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_max_level(tracing::Level::DEBUG)
+        .with_ansi(false)
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+    let (mut client, _) = app(|router| router.layer(HtmxLayer::new().tracing().auto_vary_debug()));
+
+    let response = client.send(HtmxTestRequest::click("/kind")).await;
+    assert_eq!(response.body(), "Partial");
+    assert!(!logs.contents().contains("WARN"), "{}", logs.contents());
+
+    let response = client.send(HtmxTestRequest::click("/raw")).await;
+    assert_eq!(response.body(), "fragment");
+
+    let logs = logs.contents();
+    let warning = logs
+        .lines()
+        .find(|line| line.contains("WARN"))
+        .unwrap_or_else(|| panic!("{logs}"));
+    assert!(warning.contains("htmx{kind=Partial"), "{warning}");
+    assert!(warning.contains("hx-request"), "{warning}");
+}
+
+#[tokio::test]
+async fn etag_logs_body_read_errors() {
+    let logs = Logs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+    let (mut client, _) = app(|router| router.layer(HtmxLayer::new().tracing().etag()));
+
+    let response = client.send(HtmxTestRequest::click("/failing")).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let logs = logs.contents();
+    let error = logs
+        .lines()
+        .find(|line| line.contains("ERROR"))
+        .unwrap_or_else(|| panic!("{logs}"));
+    assert!(error.contains("`ETag`"), "{error}");
+    assert!(error.contains("connection reset"), "{error}");
+}
+
+/// A request body without `Default`.
+struct Body;
+
+/// A service that is not `Clone` and returns a response body without `From<Bytes>`.
+struct Handler;
+
+impl Service<Request<Body>> for Handler {
+    type Response = Response<String>;
+
+    type Error = Infallible;
+
+    type Future = Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let kind = HxRequestKind::from_headers(req.headers());
+        ready(Ok(Response::new(format!("{kind:?}"))))
+    }
+}
+
+#[tokio::test]
+async fn disabled_middleware_add_no_requirements() {
+    let mut service = HtmxLayer::new()
+        .tracing()
+        .auto_vary()
+        .cache_control(HxCacheControlLayer::new().partial(HeaderValue::from_static("no-store")))
+        .layer(Handler);
+
+    let request = Request::get("/items")
+        .header("hx-request", "true")
+        .body(Body)
+        .unwrap();
+    let response = service.call(request).await.unwrap();
+    assert_eq!(response.body(), "Partial");
+    assert_eq!(response.headers()[CACHE_CONTROL], "no-store");
+    assert!(vary(response.headers()).contains(&"hx-request"));
+}
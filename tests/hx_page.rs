@@ -0,0 +1,105 @@
+use axum::{Router, routing::get};
+use htmxtools::{
+    HxAutoVaryLayer,
+    request::HxRequestKind,
+    response::{HxPage, HxRender},
+    testing::{HtmxResponseAssertions, HtmxTestClient, HtmxTestRequest},
+};
+use http::header::VARY;
+
+struct Items;
+
+impl HxPage for Items {
+    type Output = &'static str;
+
+    fn full(self) -> Self::Output {
+        "<html><ul id=\"items\"></ul></html>"
+    }
+
+    fn partial(self) -> Self::Output {
+        "<ul id=\"items\"></ul>"
+    }
+}
+
+fn app() -> Router {
+    Router::new().route(
+        "/items",
+        get(|kind: HxRequestKind| async move { HxRender::new(Items, kind) }),
+    )
+}
+
+fn vary(headers: &http::HeaderMap) -> Vec<&str> {
+    headers
+        .get_all(VARY)
+        .iter()
+        .map(|value| value.to_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn renders_by_kind() {
+    let cases = [
+        (HxRequestKind::Page, "<html><ul id=\"items\"></ul></html>"),
+        (
+            HxRequestKind::HistoryRestore,
+            "<html><ul id=\"items\"></ul></html>",
+        ),
+        (
+            HxRequestKind::Boosted,
+            "<html><ul id=\"items\"></ul></html>",
+        ),
+        (HxRequestKind::Full, "<html><ul id=\"items\"></ul></html>"),
+        (HxRequestKind::Partial, "<ul id=\"items\"></ul>"),
+    ];
+
+    for (kind, body) in cases {
+        assert_eq!(HxRender::new(Items, kind).render(), body, "{kind:?}");
+    }
+}
+
+#[test]
+fn varies_by_consulted_headers() {
+    let response = HxRender::new(Items, HxRequestKind::Page).into_http();
+    assert_eq!(vary(response.headers()), ["hx-request"]);
+
+    let response = HxRender::new(Items, HxRequestKind::Partial).into_http();
+    assert_eq!(
+        vary(response.headers()),
+        [
+            "hx-request",
+            "hx-history-restore-request",
+            "hx-boosted",
+            "hx-request-type"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn responds_from_axum() {
+    let mut client = HtmxTestClient::new(app());
+
+    let response = client.navigate("/items").await;
+    assert_eq!(response.body(), "<html><ul id=\"items\"></ul></html>");
+    response.assert_vary_contains("hx-request");
+
+    let response = client.send(HtmxTestRequest::click("/items")).await;
+    assert_eq!(response.body(), "<ul id=\"items\"></ul>");
+    response
+        .assert_vary_contains("hx-boosted")
+        .assert_vary_contains("hx-request-type");
+
+    let response = client.send(HtmxTestRequest::boosted("/items")).await;
+    assert_eq!(response.body(), "<html><ul id=\"items\"></ul></html>");
+}
+
+#[tokio::test]
+async fn does_not_duplicate_auto_vary() {
+    let mut client = HtmxTestClient::new(app().layer(HxAutoVaryLayer::new()));
+
+    let response = client.send(HtmxTestRequest::click("/items")).await;
+    let mut names = vary(response.headers());
+    let count = names.len();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(names.len(), count, "{names:?}");
+}
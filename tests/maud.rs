@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use htmxtools::{
+    attribute::{HxAttributes, HxSwap, HxTriggerSpec},
+    maud::element,
+    request::HxTarget,
+    response::{HxPushUrl, HxReplaceUrl, HxReselect, HxReswap},
+};
+use http::Uri;
+use maud::html;
+
+#[test]
+fn renders_swap_with_modifiers() {
+    let swap = HxSwap::new(HxReswap::BeforeEnd)
+        .swap(Duration::from_secs(1))
+        .settle(Duration::from_millis(20))
+        .scroll_bottom()
+        .ignore_title()
+        .focus_scroll(false)
+        .modifier("show:#list:top");
+
+    assert_eq!(
+        html! { div hx-swap=(swap) {} }.into_string(),
+        r##"<div hx-swap="beforeend swap:1s settle:20ms scroll:bottom ignoreTitle:true focus-scroll:false show:#list:top"></div>"##
+    );
+}
+
+#[test]
+fn renders_trigger_specs() {
+    let trigger = HxTriggerSpec::new("click")
+        .filter("ctrlKey && target.value != \"\"")
+        .once()
+        .or("keyup")
+        .from_element("body")
+        .throttle(Duration::from_millis(250))
+        .or("load");
+
+    assert_eq!(
+        html! { div hx-trigger=(trigger) {} }.into_string(),
+        r#"<div hx-trigger="click[ctrlKey &amp;&amp; target.value != &quot;&quot;] once, keyup from:body throttle:250ms, load"></div>"#
+    );
+
+    assert_eq!(
+        html! { div hx-trigger=(HxTriggerSpec::every(Duration::from_secs(2))) {} }.into_string(),
+        r#"<div hx-trigger="every 2s"></div>"#
+    );
+}
+
+#[test]
+fn renders_header_types() {
+    let markup = html! {
+        a
+            hx-swap=(HxReswap::TextContent)
+            hx-target=(HxTarget::from_static("ul#items"))
+            hx-select=(HxReselect::from_static("li > a[href=\"/\"]"))
+            hx-push-url=(HxPushUrl::Uri(Uri::from_static("/items?page=2&size=10")))
+            hx-replace-url=(HxReplaceUrl::True) {}
+    };
+
+    assert_eq!(
+        markup.into_string(),
+        concat!(
+            r#"<a hx-swap="textContent" hx-target="ul#items" hx-select="li &gt; a[href=&quot;/&quot;]" "#,
+            r#"hx-push-url="/items?page=2&amp;size=10" hx-replace-url="true"></a>"#,
+        )
+    );
+}
+
+#[test]
+fn renders_element_with_attributes() {
+    let attributes = HxAttributes::new()
+        .delete("/items/1")
+        .target("closest li")
+        .swap(HxSwap::new(HxReswap::OuterHtml).swap(Duration::from_millis(500)))
+        .attribute("hx-confirm", "Delete \"Milk\" & <all> its notes?")
+        .target("#items");
+
+    let markup = html! {
+        li { (element("button", &attributes, html! { b { "Delete" } })) }
+    };
+
+    assert_eq!(
+        markup.into_string(),
+        concat!(
+            r##"<li><button hx-delete="/items/1" hx-target="#items" hx-swap="outerHTML swap:500ms" "##,
+            r##"hx-confirm="Delete &quot;Milk&quot; &amp; &lt;all&gt; its notes?"><b>Delete</b></button></li>"##,
+        )
+    );
+
+    assert_eq!(
+        attributes.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+        ["hx-delete", "hx-target", "hx-swap", "hx-confirm"]
+    );
+}
+
+#[test]
+fn escapes_element_content() {
+    let markup = element("p", &HxAttributes::new(), "<script>");
+    assert_eq!(markup.into_string(), "<p>&lt;script&gt;</p>");
+}
+
+#[test]
+fn renders_void_element_without_closing_tag() {
+    let attributes = HxAttributes::new().get("/search").target("#results");
+
+    let markup = element("input", &attributes, "");
+    assert_eq!(
+        markup.into_string(),
+        r##"<input hx-get="/search" hx-target="#results">"##
+    );
+
+    let markup = element("IMG", &HxAttributes::new(), "");
+    assert_eq!(markup.into_string(), "<IMG>");
+}
+
+#[test]
+#[should_panic(expected = "`br` is a void element and cannot have content")]
+fn rejects_void_element_content() {
+    element("br", &HxAttributes::new(), "text");
+}
+
+#[test]
+#[should_panic(expected = "`div onclick` is not a valid tag name")]
+fn rejects_invalid_tag() {
+    element("div onclick", &HxAttributes::new(), "");
+}
+
+#[test]
+#[should_panic(expected = "`hx-on:click=\"x\"` is not a valid attribute name")]
+fn rejects_invalid_attribute_name() {
+    HxAttributes::new().attribute("hx-on:click=\"x\"", "");
+}
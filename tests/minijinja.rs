@@ -0,0 +1,159 @@
+use axum::{Router, routing::get};
+use htmxtools::{
+    minijinja::HxRenderer,
+    request::{HxRequestKind, HxSource, HxTarget},
+    testing::{HtmxTestClient, HtmxTestRequest},
+};
+use http::header::{CONTENT_TYPE, VARY};
+use minijinja::{Environment, context};
+
+const LAYOUT: &str = r#"<html><body>{% block items %}{% endblock %}{% block item_count %}{% endblock %}</body></html>"#;
+const ITEMS: &str = r#"{% extends "layout.html" %}{% block items %}<ul id="items">{% for item in items %}<li>{{ item }}</li>{% endfor %}</ul>{% endblock %}{% block item_count %}<span id="item-count">{{ count }}</span>{% endblock %}"#;
+const INFO: &str = r#"{% if is_htmx %}htmx{% else %}page{% endif %} {{ target or "-" }} {{ source or "-" }}{% if boosted %} boosted{% endif %}"#;
+
+const FULL: &str = r#"<html><body><ul id="items"><li>Milk</li><li>&lt;Eggs&gt;</li></ul><span id="item-count">2</span></body></html>"#;
+const ITEMS_BLOCK: &str = r#"<ul id="items"><li>Milk</li><li>&lt;Eggs&gt;</li></ul>"#;
+
+fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.add_template("layout.html", LAYOUT).unwrap();
+    env.add_template("items.html", ITEMS).unwrap();
+    env.add_template("info.html", INFO).unwrap();
+    env
+}
+
+fn render(kind: HxRequestKind, target: Option<&'static str>) -> String {
+    HxRenderer::new(kind, target.map(HxTarget::from_static), None)
+        .render(
+            &environment(),
+            "items.html",
+            context! { items => ["Milk", "<Eggs>"], count => 2 },
+        )
+        .unwrap()
+}
+
+#[test]
+fn renders_full_template_for_full_pages() {
+    for kind in [
+        HxRequestKind::Page,
+        HxRequestKind::HistoryRestore,
+        HxRequestKind::Boosted,
+        HxRequestKind::Full,
+    ] {
+        assert_eq!(render(kind, Some("ul#items")), FULL, "{kind:?}");
+    }
+}
+
+#[test]
+fn renders_block_named_after_target() {
+    assert_eq!(
+        render(HxRequestKind::Partial, Some("ul#items")),
+        ITEMS_BLOCK
+    );
+    assert_eq!(
+        render(HxRequestKind::Partial, Some("span#item-count")),
+        r#"<span id="item-count">2</span>"#
+    );
+}
+
+#[test]
+fn falls_back_to_full_template() {
+    assert_eq!(render(HxRequestKind::Partial, Some("div#missing")), FULL);
+    assert_eq!(render(HxRequestKind::Partial, Some("body")), FULL);
+    assert_eq!(render(HxRequestKind::Partial, None), FULL);
+}
+
+#[test]
+fn exposes_request_values() {
+    let env = environment();
+
+    let renderer = HxRenderer::new(
+        HxRequestKind::Partial,
+        Some(HxTarget::from_static("ul#items")),
+        Some(HxSource::from_static("button#add")),
+    );
+    assert_eq!(
+        renderer.render(&env, "info.html", ()).unwrap(),
+        "htmx ul#items button#add"
+    );
+
+    let renderer = HxRenderer::new(HxRequestKind::Boosted, None, None);
+    assert_eq!(
+        renderer.render(&env, "info.html", ()).unwrap(),
+        "htmx - - boosted"
+    );
+
+    let renderer = HxRenderer::new(HxRequestKind::Page, None, None);
+    assert_eq!(
+        renderer
+            .render(&env, "info.html", context! { target => "overridden" })
+            .unwrap(),
+        "page overridden -"
+    );
+}
+
+#[test]
+fn renders_http_responses_with_vary() {
+    let renderer = HxRenderer::new(
+        HxRequestKind::Partial,
+        Some(HxTarget::from_static("ul#items")),
+        None,
+    );
+    let response = renderer
+        .render_http(
+            &environment(),
+            "items.html",
+            context! { items => ["Milk", "<Eggs>"], count => 2 },
+        )
+        .unwrap();
+
+    assert_eq!(response.body(), ITEMS_BLOCK);
+    assert_eq!(response.headers()[CONTENT_TYPE], "text/html; charset=utf-8");
+    let vary: Vec<_> = response
+        .headers()
+        .get_all(VARY)
+        .iter()
+        .flat_map(|value| value.to_str().unwrap().split(", "))
+        .collect();
+    assert_eq!(
+        vary,
+        [
+            "hx-request",
+            "hx-history-restore-request",
+            "hx-boosted",
+            "hx-request-type",
+            "hx-target",
+            "hx-source"
+        ]
+    );
+}
+
+#[test]
+fn reports_missing_template() {
+    let renderer = HxRenderer::new(HxRequestKind::Page, None, None);
+    assert!(renderer.render(&environment(), "missing.html", ()).is_err());
+}
+
+#[tokio::test]
+async fn renders_from_axum() {
+    let app = Router::new().route(
+        "/items",
+        get(|htmx: HxRenderer| async move {
+            htmx.render(
+                &environment(),
+                "items.html",
+                context! { items => ["Milk", "<Eggs>"], count => 2 },
+            )
+            .unwrap()
+        }),
+    );
+    let mut client = HtmxTestClient::new(app);
+
+    let response = client.navigate("/items").await;
+    assert_eq!(response.body(), FULL);
+
+    let response = client
+        .send(HtmxTestRequest::click("/items").with_target(HxTarget::from_static("ul#items")))
+        .await;
+    assert_eq!(response.body(), ITEMS_BLOCK);
+}
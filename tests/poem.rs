@@ -0,0 +1,187 @@
+use htmxtools::poem::HxAutoVary;
+use http::{StatusCode, header::VARY};
+use poem::{Endpoint, EndpointExt, Request, Response, Route, get};
+
+mod handlers {
+    use htmxtools::{
+        poem::HxIntoResponseExt,
+        request::{HxRequest, HxRequestKind, HxTarget},
+        response::{HxRetarget, HxTrigger},
+    };
+    use http::header::VARY;
+    use poem::{IntoResponse, handler};
+
+    #[handler]
+    pub fn target(hx_target: HxTarget) -> String {
+        hx_target.as_str().to_string()
+    }
+
+    #[handler]
+    pub fn optional_target(hx_target: Option<HxTarget>) -> String {
+        hx_target.map_or_else(
+            || "missing".to_string(),
+            |hx_target| hx_target.as_str().to_string(),
+        )
+    }
+
+    #[handler]
+    pub fn request(_: HxRequest) -> &'static str {
+        "htmx"
+    }
+
+    #[handler]
+    pub fn optional_request(hx_request: Option<HxRequest>) -> &'static str {
+        if hx_request.is_some() { "htmx" } else { "page" }
+    }
+
+    #[handler]
+    pub fn kind(hx_request_kind: HxRequestKind) -> String {
+        format!("{hx_request_kind:?}")
+    }
+
+    #[handler]
+    pub fn declared(_: HxTarget) -> impl IntoResponse {
+        "declared".with_header(VARY, "HX-Target")
+    }
+
+    #[handler]
+    pub fn any(_: HxTarget) -> impl IntoResponse {
+        "any".with_header(VARY, "*")
+    }
+
+    #[handler]
+    pub fn trigger() -> impl IntoResponse {
+        "loaded".with_hx_header(HxTrigger::new().with_event("loaded".to_string()))
+    }
+
+    #[handler]
+    pub fn retarget() -> HxRetarget {
+        HxRetarget::from_static("#errors")
+    }
+}
+
+async fn call(uri: &str, headers: &[(&'static str, &'static str)]) -> Response {
+    let app = Route::new()
+        .at("/target", get(handlers::target))
+        .at("/optional", get(handlers::optional_target))
+        .at("/request", get(handlers::request))
+        .at("/optional-request", get(handlers::optional_request))
+        .at("/kind", get(handlers::kind))
+        .at("/declared", get(handlers::declared))
+        .at("/any", get(handlers::any))
+        .at("/trigger", get(handlers::trigger))
+        .at("/retarget", get(handlers::retarget))
+        .with(HxAutoVary::new());
+
+    let mut builder = Request::builder().uri_str(uri);
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+    app.get_response(builder.finish()).await
+}
+
+fn vary(response: &Response) -> Vec<&str> {
+    let mut names: Vec<_> = response
+        .headers()
+        .get_all(VARY)
+        .iter()
+        .map(|value| value.to_str().unwrap())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+async fn body(response: Response) -> String {
+    response.into_body().into_string().await.unwrap()
+}
+
+#[tokio::test]
+async fn extracts_headers() {
+    let response = call("/target", &[("hx-target", "div#items")]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(response).await, "div#items");
+
+    let response = call("/optional", &[]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(response).await, "missing");
+
+    let response = call("/kind", &[("hx-request", "true"), ("hx-boosted", "true")]).await;
+    assert_eq!(body(response).await, "Boosted");
+}
+
+#[tokio::test]
+async fn rejects_missing_and_invalid_headers() {
+    let response = call("/target", &[]).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(vary(&response), ["hx-target"]);
+
+    let response = call("/request", &[("hx-request", "false")]).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = call("/optional-request", &[("hx-request", "false")]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(response).await, "page");
+}
+
+#[tokio::test]
+async fn varies_by_consumed_headers_only() {
+    let headers = [("hx-request", "true"), ("hx-target", "div#items")];
+
+    let response = call("/target", &headers).await;
+    assert_eq!(vary(&response), ["hx-target"]);
+
+    let response = call("/kind", &headers).await;
+    assert_eq!(
+        vary(&response),
+        [
+            "hx-boosted",
+            "hx-history-restore-request",
+            "hx-request",
+            "hx-request-type"
+        ]
+    );
+
+    let response = call("/kind", &[]).await;
+    assert_eq!(vary(&response), ["hx-request"]);
+
+    let response = call("/trigger", &headers).await;
+    assert!(vary(&response).is_empty());
+}
+
+#[tokio::test]
+async fn keeps_declared_vary() {
+    let headers = [("hx-target", "div#items")];
+
+    let response = call("/declared", &headers).await;
+    assert_eq!(vary(&response), ["HX-Target"]);
+
+    let response = call("/any", &headers).await;
+    assert_eq!(vary(&response), ["*"]);
+}
+
+#[tokio::test]
+async fn sets_response_headers() {
+    let response = call("/trigger", &[]).await;
+    assert_eq!(response.headers()["hx-trigger"], "loaded");
+    assert_eq!(body(response).await, "loaded");
+
+    let response = call("/retarget", &[]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["hx-retarget"], "#errors");
+}
+
+#[tokio::test]
+async fn returns_errors_with_vary() {
+    let app = Route::new()
+        .at("/target", get(handlers::target))
+        .with(HxAutoVary::new());
+
+    let error = app
+        .call(Request::builder().uri_str("/target").finish())
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), StatusCode::BAD_REQUEST);
+
+    let response = error.into_response();
+    assert_eq!(vary(&response), ["hx-target"]);
+}
@@ -0,0 +1,113 @@
+use headers_core::Header;
+use htmxtools::{
+    proptest::{encoded, header_value},
+    request::{
+        HxBoosted, HxCurrentUrl, HxHistoryRestoreRequest, HxRequest, HxRequestKind, HxRequestType,
+        HxSource, HxTarget,
+    },
+    response::{
+        HxLocation, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl, HxReselect, HxReswap,
+        HxRetarget, HxTrigger, HxTriggerAfterSettle, HxTriggerAfterSwap,
+    },
+};
+use http::{HeaderMap, HeaderName, HeaderValue};
+use proptest::{collection::vec, prelude::*, sample::select};
+
+/// Decodes the values, and re-encodes the header if they are valid. Must never panic.
+fn decode<H: Header>(values: &[HeaderValue]) -> Option<H> {
+    let header = H::decode(&mut values.iter()).ok()?;
+    let mut encoded = Vec::new();
+    header.encode(&mut encoded);
+    Some(header)
+}
+
+macro_rules! properties {
+    ($($round_trip:ident, $never_panics:ident: $ty:ty),* $(,)?) => {
+        proptest! {
+            $(
+                #[test]
+                fn $round_trip(header in any::<$ty>()) {
+                    let mut values = Vec::new();
+                    header.encode(&mut values);
+                    prop_assert_eq!(<$ty>::decode(&mut values.iter()).ok(), Some(header));
+                }
+
+                #[test]
+                fn $never_panics(values in vec(header_value(), 0..3)) {
+                    decode::<$ty>(&values);
+                }
+            )*
+        }
+    };
+}
+
+properties!(
+    boosted_round_trips, boosted_never_panics: HxBoosted,
+    current_url_round_trips, current_url_never_panics: HxCurrentUrl,
+    history_restore_request_round_trips, history_restore_request_never_panics: HxHistoryRestoreRequest,
+    request_round_trips, request_never_panics: HxRequest,
+    request_type_round_trips, request_type_never_panics: HxRequestType,
+    source_round_trips, source_never_panics: HxSource,
+    target_round_trips, target_never_panics: HxTarget,
+    location_round_trips, location_never_panics: HxLocation,
+    push_url_round_trips, push_url_never_panics: HxPushUrl,
+    redirect_round_trips, redirect_never_panics: HxRedirect,
+    refresh_round_trips, refresh_never_panics: HxRefresh,
+    replace_url_round_trips, replace_url_never_panics: HxReplaceUrl,
+    reselect_round_trips, reselect_never_panics: HxReselect,
+    reswap_round_trips, reswap_never_panics: HxReswap,
+    retarget_round_trips, retarget_never_panics: HxRetarget,
+    trigger_round_trips, trigger_never_panics: HxTrigger,
+    trigger_after_settle_round_trips, trigger_after_settle_never_panics: HxTriggerAfterSettle,
+    trigger_after_swap_round_trips, trigger_after_swap_never_panics: HxTriggerAfterSwap,
+);
+
+proptest! {
+    #[test]
+    fn source_and_target_accessors_never_panic(values in vec(header_value(), 1..2)) {
+        if let Some(source) = decode::<HxSource>(&values) {
+            let _ = (source.as_str(), source.tag(), source.id());
+        }
+        if let Some(target) = decode::<HxTarget>(&values) {
+            let _ = (target.as_str(), target.tag(), target.id());
+        }
+    }
+
+    #[test]
+    fn target_splits_into_tag_and_id(target in any::<HxTarget>()) {
+        let joined = match target.id() {
+            Some(id) => format!("{}#{id}", target.tag()),
+            None => target.tag().to_string(),
+        };
+        prop_assert_eq!(joined, target.as_str());
+    }
+
+    #[test]
+    fn request_kind_never_panics(
+        headers in vec(
+            (
+                select(vec![
+                    "hx-request",
+                    "hx-request-type",
+                    "hx-boosted",
+                    "hx-history-restore-request",
+                    "hx-target",
+                    "hx-source",
+                ]),
+                header_value(),
+            ),
+            0..6,
+        ),
+    ) {
+        let headers: HeaderMap = headers
+            .into_iter()
+            .map(|(name, value)| (HeaderName::from_static(name), value))
+            .collect();
+        let _ = HxRequestKind::from_headers(&headers).consulted_headers();
+    }
+
+    #[test]
+    fn encoded_values_decode(values in encoded::<HxLocation>()) {
+        prop_assert!(HxLocation::decode(&mut values.iter()).is_ok());
+    }
+}
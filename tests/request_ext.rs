@@ -0,0 +1,137 @@
+use axum::{Router, extract::Request, routing::get};
+use htmxtools::{
+    HxAutoVaryLayer,
+    request::{HtmxRequestExt, HxBoosted, HxRequestKind, HxTarget},
+    testing::{HtmxTestClient, HtmxTestRequest},
+};
+use http::{HeaderMap, HeaderValue, header::VARY};
+
+fn vary(headers: &HeaderMap) -> Vec<&str> {
+    let mut names: Vec<_> = headers
+        .get_all(VARY)
+        .iter()
+        .map(|value| value.to_str().unwrap())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+fn app() -> Router {
+    Router::new()
+        .route(
+            "/target",
+            get(|request: Request| async move {
+                match request.hx_header::<HxTarget>() {
+                    Ok(Some(target)) => target.as_str().to_string(),
+                    Ok(None) => "missing".to_string(),
+                    Err(_) => "invalid".to_string(),
+                }
+            }),
+        )
+        .route(
+            "/kind",
+            get(|request: Request| async move { format!("{:?}", request.hx_request_kind()) }),
+        )
+        .layer(HxAutoVaryLayer::new())
+}
+
+#[test]
+fn classifies_request_kind() {
+    let cases = [
+        (
+            HtmxTestRequest::click("/").headers(),
+            HxRequestKind::Partial,
+        ),
+        (
+            HtmxTestRequest::click("/").full().headers(),
+            HxRequestKind::Full,
+        ),
+        (
+            HtmxTestRequest::boosted("/").headers(),
+            HxRequestKind::Boosted,
+        ),
+        (
+            HtmxTestRequest::history_restore("/").headers(),
+            HxRequestKind::HistoryRestore,
+        ),
+        (HeaderMap::new(), HxRequestKind::Page),
+    ];
+
+    for (headers, kind) in cases {
+        assert_eq!(HxRequestKind::from_headers(&headers), kind, "{headers:?}");
+
+        let mut request = http::Request::new(());
+        *request.headers_mut() = headers;
+        assert_eq!(request.hx_request_kind(), kind);
+    }
+}
+
+#[test]
+fn decodes_headers_without_axum_extractors() {
+    let request = http::Request::builder()
+        .header("hx-target", "ul#items")
+        .header("hx-boosted", "yes")
+        .body(())
+        .unwrap();
+
+    assert_eq!(
+        request.hx_header::<HxTarget>().unwrap(),
+        Some(HxTarget::from_static("ul#items"))
+    );
+    assert!(request.hx_header::<HxBoosted>().is_err());
+
+    let (parts, ()) = http::Request::new(()).into_parts();
+    assert_eq!(parts.hx_header::<HxTarget>().unwrap(), None);
+}
+
+#[tokio::test]
+async fn records_decoded_headers() {
+    let mut client = HtmxTestClient::new(app());
+
+    let response = client
+        .send(HtmxTestRequest::click("/target").with_target(HxTarget::from_static("ul#items")))
+        .await;
+    assert_eq!(response.body(), "ul#items");
+    assert_eq!(vary(response.headers()), ["hx-target"]);
+
+    let response = client.navigate("/target").await;
+    assert_eq!(response.body(), "missing");
+    assert_eq!(vary(response.headers()), ["hx-target"]);
+
+    let response = client
+        .send(HtmxTestRequest::click("/target").with_header(
+            "hx-target",
+            HeaderValue::from_bytes(b"ul#\xffitems").unwrap(),
+        ))
+        .await;
+    assert_eq!(response.body(), "invalid");
+    assert_eq!(vary(response.headers()), ["hx-target"]);
+}
+
+#[tokio::test]
+async fn records_only_consulted_headers_for_kind() {
+    let mut client = HtmxTestClient::new(app());
+
+    let response = client.navigate("/kind").await;
+    assert_eq!(response.body(), "Page");
+    assert_eq!(vary(response.headers()), ["hx-request"]);
+
+    let response = client.send(HtmxTestRequest::boosted("/kind")).await;
+    assert_eq!(response.body(), "Boosted");
+    assert_eq!(
+        vary(response.headers()),
+        ["hx-boosted", "hx-history-restore-request", "hx-request"]
+    );
+
+    let response = client.send(HtmxTestRequest::click("/kind")).await;
+    assert_eq!(response.body(), "Partial");
+    assert_eq!(
+        vary(response.headers()),
+        [
+            "hx-boosted",
+            "hx-history-restore-request",
+            "hx-request",
+            "hx-request-type"
+        ]
+    );
+}
@@ -0,0 +1,184 @@
+use std::net::SocketAddr;
+
+use axum::{Router, routing::get};
+use htmxtools::{
+    request::{HxBoosted, HxCurrentUrl, HxRequest, HxRequestType, HxSource, HxTarget},
+    reqwest::{HxRequestBuilderExt, HxResponseExt},
+    response::{
+        HxLocation, HxLocationSwapOption, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl,
+        HxReselect, HxReswap, HxRetarget, HxTrigger, HxTriggerAfterSettle, HxTriggerAfterSwap,
+    },
+};
+use serde_json::json;
+use tokio::net::TcpListener;
+
+async fn echo(
+    hx_request: Option<HxRequest>,
+    hx_boosted: Option<HxBoosted>,
+    hx_request_type: Option<HxRequestType>,
+    hx_current_url: Option<HxCurrentUrl>,
+    hx_source: Option<HxSource>,
+    hx_target: Option<HxTarget>,
+) -> String {
+    format!(
+        "request={} boosted={} type={:?} url={:?} source={:?} target={:?}",
+        hx_request.is_some(),
+        hx_boosted.is_some(),
+        hx_request_type,
+        hx_current_url.map(|url| url.to_string()),
+        hx_source.map(|source| source.tag().to_string()),
+        hx_target.and_then(|target| target.id().map(str::to_string)),
+    )
+}
+
+async fn respond() -> impl axum::response::IntoResponse {
+    (
+        HxTrigger::new()
+            .with_event_data("saved".to_string(), json!({ "id": 1 }))
+            .with_event("refreshed".to_string()),
+        HxTriggerAfterSettle::new().with_event("settled".to_string()),
+        HxTriggerAfterSwap::new().with_event("swapped".to_string()),
+        HxLocation::new("/items".parse().unwrap())
+            .with_target("#main".to_string())
+            .with_swap(HxLocationSwapOption::OuterHtml),
+        HxPushUrl::False,
+        HxReplaceUrl::Uri("/items/1".parse().unwrap()),
+        HxRedirect("/login".parse().unwrap()),
+        HxRefresh,
+        HxReselect::from_static("#content"),
+        HxReswap::BeforeEnd,
+        HxRetarget::from_static("#list"),
+        "ok",
+    )
+}
+
+async fn serve() -> SocketAddr {
+    let app = Router::new()
+        .route("/echo", get(echo))
+        .route("/respond", get(respond))
+        .route("/plain", get(|| async { "plain" }));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    addr
+}
+
+#[tokio::test]
+async fn attaches_request_headers() {
+    let addr = serve().await;
+
+    let body = reqwest::Client::new()
+        .get(format!("http://{addr}/echo"))
+        .hx_request()
+        .hx_boosted()
+        .hx_request_type(HxRequestType::Partial)
+        .hx_current_url(HxCurrentUrl::from(
+            "http://example.com/page".parse::<http::Uri>().unwrap(),
+        ))
+        .hx_source(HxSource::from_static("button#save"))
+        .hx_target(HxTarget::from_static("div#results"))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        body,
+        "request=true boosted=true type=Some(Partial) url=Some(\"http://example.com/page\") \
+         source=Some(\"button\") target=Some(\"results\")"
+    );
+}
+
+#[tokio::test]
+async fn omits_unset_request_headers() {
+    let addr = serve().await;
+
+    let body = reqwest::get(format!("http://{addr}/echo"))
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        body,
+        "request=false boosted=false type=None url=None source=None target=None"
+    );
+}
+
+#[tokio::test]
+async fn parses_response_headers() {
+    let addr = serve().await;
+
+    let response = reqwest::get(format!("http://{addr}/respond"))
+        .await
+        .unwrap();
+
+    let trigger = response.hx_trigger().unwrap();
+    assert_eq!(
+        trigger.events().collect::<Vec<_>>(),
+        [("refreshed", None), ("saved", Some(&json!({ "id": 1 })))]
+    );
+    assert_eq!(
+        response
+            .hx_trigger_after_settle()
+            .unwrap()
+            .events()
+            .collect::<Vec<_>>(),
+        [("settled", None)]
+    );
+    assert_eq!(
+        response
+            .hx_trigger_after_swap()
+            .unwrap()
+            .events()
+            .collect::<Vec<_>>(),
+        [("swapped", None)]
+    );
+
+    assert_eq!(
+        response.hx_location(),
+        Some(
+            HxLocation::new("/items".parse().unwrap())
+                .with_target("#main".to_string())
+                .with_swap(HxLocationSwapOption::OuterHtml)
+        )
+    );
+    assert_eq!(response.hx_push_url(), Some(HxPushUrl::False));
+    assert_eq!(
+        response.hx_replace_url(),
+        Some(HxReplaceUrl::Uri("/items/1".parse().unwrap()))
+    );
+    assert_eq!(
+        response.hx_redirect(),
+        Some(HxRedirect("/login".parse().unwrap()))
+    );
+    assert!(response.hx_refresh());
+    assert_eq!(
+        response.hx_reselect(),
+        Some(HxReselect::from_static("#content"))
+    );
+    assert_eq!(response.hx_reswap(), Some(HxReswap::BeforeEnd));
+    assert_eq!(
+        response.hx_retarget(),
+        Some(HxRetarget::from_static("#list"))
+    );
+
+    assert_eq!(response.text().await.unwrap(), "ok");
+}
+
+#[tokio::test]
+async fn missing_response_headers_are_none() {
+    let addr = serve().await;
+
+    let response = reqwest::get(format!("http://{addr}/plain")).await.unwrap();
+
+    assert!(matches!(response.hx_header::<HxTrigger>(), Ok(None)));
+    assert_eq!(response.hx_trigger(), None);
+    assert_eq!(response.hx_location(), None);
+    assert!(!response.hx_refresh());
+}
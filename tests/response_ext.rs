@@ -0,0 +1,87 @@
+use htmxtools::response::{
+    HtmxResponseExt, HxLocation, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl, HxReselect,
+    HxReswap, HxRetarget, HxTrigger, HxTriggerAfterSettle, HxTriggerAfterSwap,
+};
+use http::{HeaderMap, HeaderValue, Response, Uri};
+
+fn values<'a>(headers: &'a HeaderMap, name: &str) -> Vec<&'a str> {
+    headers
+        .get_all(name)
+        .iter()
+        .map(|value| value.to_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn sets_every_header_on_responses() {
+    let mut response = Response::new(());
+    response
+        .hx_location(HxLocation::new(Uri::from_static("/items")))
+        .hx_push_url(HxPushUrl::Uri(Uri::from_static("/items?page=2")))
+        .hx_redirect(HxRedirect::new(Uri::from_static("/login")))
+        .hx_refresh(HxRefresh)
+        .hx_replace_url(HxReplaceUrl::False)
+        .hx_reselect(HxReselect::from_static("#items"))
+        .hx_reswap(HxReswap::OuterHtml)
+        .hx_retarget(HxRetarget::from_static("#errors"))
+        .hx_trigger(HxTrigger::new().with_event("saved".to_string()))
+        .hx_trigger_after_settle(HxTriggerAfterSettle::new().with_event("settled".to_string()))
+        .hx_trigger_after_swap(HxTriggerAfterSwap::new().with_event("swapped".to_string()));
+
+    let headers = response.headers();
+    assert_eq!(headers["hx-location"], "/items");
+    assert_eq!(headers["hx-push-url"], "/items?page=2");
+    assert_eq!(headers["hx-redirect"], "/login");
+    assert_eq!(headers["hx-refresh"], "true");
+    assert_eq!(headers["hx-replace-url"], "false");
+    assert_eq!(headers["hx-reselect"], "#items");
+    assert_eq!(headers["hx-reswap"], "outerHTML");
+    assert_eq!(headers["hx-retarget"], "#errors");
+    assert_eq!(headers["hx-trigger"], "saved");
+    assert_eq!(headers["hx-trigger-after-settle"], "settled");
+    assert_eq!(headers["hx-trigger-after-swap"], "swapped");
+}
+
+#[test]
+fn sets_headers_on_parts() {
+    let (mut parts, ()) = Response::new(()).into_parts();
+    parts
+        .hx_retarget(HxRetarget::from_static("#errors"))
+        .hx_reswap(HxReswap::InnerHtml);
+
+    let response = Response::from_parts(parts, ());
+    assert_eq!(response.headers()["hx-retarget"], "#errors");
+    assert_eq!(response.headers()["hx-reswap"], "innerHTML");
+}
+
+#[test]
+fn replaces_existing_values() {
+    let mut response = Response::new(());
+    response
+        .headers_mut()
+        .append("hx-trigger", HeaderValue::from_static("first"));
+    response
+        .headers_mut()
+        .append("hx-trigger", HeaderValue::from_static("second"));
+
+    response.hx_trigger(HxTrigger::new().with_event("third".to_string()));
+    assert_eq!(values(response.headers(), "hx-trigger"), ["third"]);
+
+    response
+        .hx_retarget(HxRetarget::from_static("#first"))
+        .hx_retarget(HxRetarget::from_static("#second"));
+    assert_eq!(values(response.headers(), "hx-retarget"), ["#second"]);
+}
+
+#[test]
+fn leaves_other_headers_alone() {
+    let mut response = Response::new(());
+    response
+        .headers_mut()
+        .insert("hx-reswap", HeaderValue::from_static("beforeend"));
+
+    response.hx_header(HxRetarget::from_static("#items"));
+    assert_eq!(response.headers()["hx-reswap"], "beforeend");
+    assert_eq!(response.headers()["hx-retarget"], "#items");
+    assert_eq!(response.headers().len(), 2);
+}
@@ -0,0 +1,232 @@
+use htmxtools::{
+    request::{HxRequest, HxRequestKind, HxTarget},
+    response::{HxRetarget, HxTrigger},
+    rocket::{HxAutoVary, HxForward, HxResponderExt, WithHxHeader},
+};
+use rocket::{
+    get,
+    http::{Header, Status},
+    local::asynchronous::{Client, LocalResponse},
+    response::Responder,
+    routes,
+};
+
+#[get("/target")]
+fn target(hx_target: HxTarget) -> String {
+    hx_target.as_str().to_string()
+}
+
+#[get("/optional")]
+fn optional_target(hx_target: Option<HxTarget>) -> String {
+    hx_target.map_or_else(
+        || "missing".to_string(),
+        |hx_target| hx_target.as_str().to_string(),
+    )
+}
+
+#[get("/request")]
+fn request(_hx_request: HxRequest) -> &'static str {
+    "htmx"
+}
+
+#[get("/forward")]
+fn partial(_hx_request: HxForward<HxRequest>) -> &'static str {
+    "partial"
+}
+
+#[get("/forward", rank = 2)]
+fn page() -> &'static str {
+    "page"
+}
+
+#[get("/forward-only")]
+fn forward_only(_hx_request: HxForward<HxRequest>) -> &'static str {
+    "partial"
+}
+
+#[get("/kind")]
+fn kind(hx_request_kind: HxRequestKind) -> String {
+    format!("{hx_request_kind:?}")
+}
+
+#[derive(Responder)]
+#[response(content_type = "text/plain")]
+struct WithVary {
+    body: &'static str,
+    vary: Header<'static>,
+}
+
+#[get("/declared")]
+fn declared(_hx_target: HxTarget) -> WithVary {
+    WithVary {
+        body: "declared",
+        vary: Header::new("Vary", "HX-Target"),
+    }
+}
+
+#[get("/any")]
+fn any(_hx_target: HxTarget) -> WithVary {
+    WithVary {
+        body: "any",
+        vary: Header::new("Vary", "*"),
+    }
+}
+
+#[get("/trigger")]
+fn trigger() -> WithHxHeader<&'static str, HxTrigger> {
+    "loaded".with_hx_header(HxTrigger::new().with_event("loaded".to_string()))
+}
+
+#[get("/retarget")]
+fn retarget() -> HxRetarget {
+    HxRetarget::from_static("#errors")
+}
+
+async fn client() -> Client {
+    let rocket = rocket::build()
+        .mount(
+            "/",
+            routes![
+                target,
+                optional_target,
+                request,
+                partial,
+                page,
+                forward_only,
+                kind,
+                declared,
+                any,
+                trigger,
+                retarget
+            ],
+        )
+        .attach(HxAutoVary::new());
+    Client::untracked(rocket).await.unwrap()
+}
+
+async fn call<'c>(
+    client: &'c Client,
+    uri: &'static str,
+    headers: &[(&'static str, &'static str)],
+) -> LocalResponse<'c> {
+    let mut request = client.get(uri);
+    for (name, value) in headers {
+        request = request.header(Header::new(*name, *value));
+    }
+    request.dispatch().await
+}
+
+fn vary<'a>(response: &'a LocalResponse<'_>) -> Vec<&'a str> {
+    let mut names: Vec<_> = response.headers().get("Vary").collect();
+    names.sort_unstable();
+    names
+}
+
+#[rocket::async_test]
+async fn extracts_headers() {
+    let client = client().await;
+
+    let response = call(&client, "/target", &[("hx-target", "div#items")]).await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().await.unwrap(), "div#items");
+
+    let response = call(&client, "/optional", &[]).await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().await.unwrap(), "missing");
+
+    let response = call(
+        &client,
+        "/kind",
+        &[("hx-request", "true"), ("hx-boosted", "true")],
+    )
+    .await;
+    assert_eq!(response.into_string().await.unwrap(), "Boosted");
+}
+
+#[rocket::async_test]
+async fn rejects_missing_and_invalid_headers() {
+    let client = client().await;
+
+    let response = call(&client, "/target", &[]).await;
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(vary(&response), ["hx-target"]);
+
+    let response = call(&client, "/request", &[("hx-request", "false")]).await;
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(vary(&response), ["hx-request"]);
+}
+
+#[rocket::async_test]
+async fn forwards_missing_headers() {
+    let client = client().await;
+
+    let response = call(&client, "/forward", &[("hx-request", "true")]).await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(vary(&response), ["hx-request"]);
+    assert_eq!(response.into_string().await.unwrap(), "partial");
+
+    let response = call(&client, "/forward", &[]).await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(vary(&response), ["hx-request"]);
+    assert_eq!(response.into_string().await.unwrap(), "page");
+
+    let response = call(&client, "/forward-only", &[]).await;
+    assert_eq!(response.status(), Status::NotFound);
+
+    let response = call(&client, "/forward", &[("hx-request", "false")]).await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[rocket::async_test]
+async fn varies_by_consumed_headers_only() {
+    let client = client().await;
+    let headers = [("hx-request", "true"), ("hx-target", "div#items")];
+
+    let response = call(&client, "/target", &headers).await;
+    assert_eq!(vary(&response), ["hx-target"]);
+
+    let response = call(&client, "/optional", &headers).await;
+    assert_eq!(vary(&response), ["hx-target"]);
+
+    let response = call(&client, "/kind", &headers).await;
+    assert_eq!(
+        vary(&response),
+        [
+            "hx-boosted",
+            "hx-history-restore-request",
+            "hx-request",
+            "hx-request-type"
+        ]
+    );
+
+    let response = call(&client, "/kind", &[]).await;
+    assert_eq!(vary(&response), ["hx-request"]);
+
+    let response = call(&client, "/trigger", &headers).await;
+    assert!(vary(&response).is_empty());
+}
+
+#[rocket::async_test]
+async fn keeps_declared_vary() {
+    let client = client().await;
+    let headers = [("hx-target", "div#items")];
+
+    let response = call(&client, "/declared", &headers).await;
+    assert_eq!(vary(&response), ["HX-Target"]);
+
+    let response = call(&client, "/any", &headers).await;
+    assert_eq!(vary(&response), ["*"]);
+}
+
+#[rocket::async_test]
+async fn sets_response_headers() {
+    let client = client().await;
+
+    let response = call(&client, "/trigger", &[]).await;
+    assert_eq!(response.headers().get_one("hx-trigger"), Some("loaded"));
+    assert_eq!(response.into_string().await.unwrap(), "loaded");
+
+    let response = call(&client, "/retarget", &[]).await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("hx-retarget"), Some("#errors"));
+}
@@ -0,0 +1,170 @@
+use htmxtools::salvo::HxAutoVary;
+use http::{StatusCode, header::VARY};
+use salvo_core::{
+    Response, Router, Service,
+    test::{ResponseExt, TestClient},
+};
+
+mod handlers {
+    use htmxtools::{
+        request::{HtmxRequestExt, HxRequest, HxRequestKind, HxTarget},
+        response::{HxRetarget, HxTrigger},
+    };
+    use http::{HeaderValue, header::VARY};
+    use salvo_core::prelude::*;
+
+    #[handler]
+    pub async fn target(hx_target: HxTarget) -> String {
+        hx_target.as_str().to_string()
+    }
+
+    #[handler]
+    pub async fn optional_target(req: &mut Request) -> String {
+        req.hx_target().map_or_else(
+            || "missing".to_string(),
+            |hx_target| hx_target.as_str().to_string(),
+        )
+    }
+
+    #[handler]
+    pub async fn request(_hx_request: HxRequest) -> &'static str {
+        "htmx"
+    }
+
+    #[handler]
+    pub async fn kind(hx_request_kind: HxRequestKind) -> String {
+        format!("{hx_request_kind:?}")
+    }
+
+    #[handler]
+    pub async fn declared(_hx_target: HxTarget, res: &mut Response) {
+        res.headers_mut()
+            .append(VARY, HeaderValue::from_static("HX-Target"));
+    }
+
+    #[handler]
+    pub async fn any(_hx_target: HxTarget, res: &mut Response) {
+        res.headers_mut()
+            .append(VARY, HeaderValue::from_static("*"));
+    }
+
+    #[handler]
+    pub async fn trigger(res: &mut Response) {
+        res.render(HxTrigger::new().with_event("loaded".to_string()));
+        res.render("loaded");
+    }
+
+    #[handler]
+    pub async fn retarget() -> HxRetarget {
+        HxRetarget::from_static("#errors")
+    }
+}
+
+async fn call(path: &str, headers: &[(&'static str, &'static str)]) -> Response {
+    let router = Router::new()
+        .hoop(HxAutoVary::new())
+        .push(Router::with_path("target").get(handlers::target))
+        .push(Router::with_path("optional").get(handlers::optional_target))
+        .push(Router::with_path("request").get(handlers::request))
+        .push(Router::with_path("kind").get(handlers::kind))
+        .push(Router::with_path("declared").get(handlers::declared))
+        .push(Router::with_path("any").get(handlers::any))
+        .push(Router::with_path("trigger").get(handlers::trigger))
+        .push(Router::with_path("retarget").get(handlers::retarget));
+
+    let mut builder = TestClient::get(format!("http://localhost{path}"));
+    for (name, value) in headers {
+        builder = builder.add_header(*name, *value, false);
+    }
+    builder.send(&Service::new(router)).await
+}
+
+fn vary(response: &Response) -> Vec<&str> {
+    let mut names: Vec<_> = response
+        .headers()
+        .get_all(VARY)
+        .iter()
+        .map(|value| value.to_str().unwrap())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+fn status(response: &Response) -> StatusCode {
+    response.status_code.unwrap_or(StatusCode::OK)
+}
+
+#[tokio::test]
+async fn extracts_headers() {
+    let mut response = call("/target", &[("hx-target", "div#items")]).await;
+    assert_eq!(status(&response), StatusCode::OK);
+    assert_eq!(response.take_string().await.unwrap(), "div#items");
+
+    let mut response = call("/optional", &[]).await;
+    assert_eq!(status(&response), StatusCode::OK);
+    assert_eq!(response.take_string().await.unwrap(), "missing");
+
+    let mut response = call("/kind", &[("hx-request", "true"), ("hx-boosted", "true")]).await;
+    assert_eq!(response.take_string().await.unwrap(), "Boosted");
+}
+
+#[tokio::test]
+async fn rejects_missing_and_invalid_headers() {
+    let response = call("/target", &[]).await;
+    assert_eq!(status(&response), StatusCode::BAD_REQUEST);
+    assert_eq!(vary(&response), ["hx-target"]);
+
+    let response = call("/request", &[("hx-request", "false")]).await;
+    assert_eq!(status(&response), StatusCode::BAD_REQUEST);
+    assert_eq!(vary(&response), ["hx-request"]);
+}
+
+#[tokio::test]
+async fn varies_by_consumed_headers_only() {
+    let headers = [("hx-request", "true"), ("hx-target", "div#items")];
+
+    let response = call("/target", &headers).await;
+    assert_eq!(vary(&response), ["hx-target"]);
+
+    let response = call("/optional", &headers).await;
+    assert_eq!(vary(&response), ["hx-target"]);
+
+    let response = call("/kind", &headers).await;
+    assert_eq!(
+        vary(&response),
+        [
+            "hx-boosted",
+            "hx-history-restore-request",
+            "hx-request",
+            "hx-request-type"
+        ]
+    );
+
+    let response = call("/kind", &[]).await;
+    assert_eq!(vary(&response), ["hx-request"]);
+
+    let response = call("/trigger", &headers).await;
+    assert!(vary(&response).is_empty());
+}
+
+#[tokio::test]
+async fn keeps_declared_vary() {
+    let headers = [("hx-target", "div#items")];
+
+    let response = call("/declared", &headers).await;
+    assert_eq!(vary(&response), ["HX-Target"]);
+
+    let response = call("/any", &headers).await;
+    assert_eq!(vary(&response), ["*"]);
+}
+
+#[tokio::test]
+async fn sets_response_headers() {
+    let mut response = call("/trigger", &[]).await;
+    assert_eq!(response.headers()["hx-trigger"], "loaded");
+    assert_eq!(response.take_string().await.unwrap(), "loaded");
+
+    let response = call("/retarget", &[]).await;
+    assert_eq!(status(&response), StatusCode::OK);
+    assert_eq!(response.headers()["hx-retarget"], "#errors");
+}
@@ -0,0 +1,296 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::{Router, extract::State, routing::get};
+use htmxtools::{
+    attribute::{HxAttributes, HxSwap, HxTriggerSpec},
+    request::{HxRequestKind, HxSource, HxTarget},
+    response::{HxPushUrl, HxReswap},
+    tera::{HxRenderer, HxTemplate, register},
+    testing::{HtmxResponseAssertions, HtmxTestClient, HtmxTestRequest},
+};
+use http::{StatusCode, header::CONTENT_TYPE};
+use tera::{Context, Tera, Value};
+
+const ITEMS: &str = r#"<html><body><h1>Items</h1>{% include "items.partial.html" %}</body></html>"#;
+const ITEMS_PARTIAL: &str =
+    r#"<ul id="items">{% for item in items %}<li>{{ item }}</li>{% endfor %}</ul>"#;
+const INFO: &str = r#"{% if htmx.is_htmx %}htmx{% else %}page{% endif %}{% if htmx.partial %} partial{% endif %}{% if htmx.boosted %} boosted{% endif %} {{ htmx.target or "-" }} {{ htmx.source or "-" }}"#;
+
+const FULL: &str = r#"<html><body><h1>Items</h1><ul id="items"><li>Milk</li><li>&lt;Eggs&gt;</li></ul></body></html>"#;
+const PARTIAL: &str = r#"<ul id="items"><li>Milk</li><li>&lt;Eggs&gt;</li></ul>"#;
+
+fn tera() -> Tera {
+    let mut tera = Tera::default();
+    register(&mut tera);
+    tera.add_raw_templates([
+        ("items.html", ITEMS),
+        ("items.partial.html", ITEMS_PARTIAL),
+        (
+            "count.html",
+            r#"<span id="count">{{ items | length }}</span>"#,
+        ),
+        ("info.html", INFO),
+    ])
+    .unwrap();
+    tera
+}
+
+fn context() -> Context {
+    let mut context = Context::new();
+    context.insert("items", &["Milk", "<Eggs>"]);
+    context
+}
+
+fn render_str(tera: &mut Tera, template: &str, context: &Context) -> String {
+    tera.add_raw_template("inline.html", template).unwrap();
+    tera.render("inline.html", context).unwrap()
+}
+
+#[test]
+fn renders_swap_filter() {
+    let mut tera = tera();
+    assert_eq!(
+        render_str(
+            &mut tera,
+            r#"{{ "beforeend" | hx_swap(transition=true, swap=1000, settle=20, scroll="bottom", focus_scroll=false) }}"#,
+            &Context::new(),
+        ),
+        "beforeend transition:true swap:1s settle:20ms scroll:bottom focus-scroll:false"
+    );
+
+    tera.add_raw_template("invalid.html", r#"{{ "sideways" | hx_swap }}"#)
+        .unwrap();
+    assert!(tera.render("invalid.html", &Context::new()).is_err());
+    tera.add_raw_template(
+        "invalid.html",
+        r#"{{ "innerHTML" | hx_swap(show="middle") }}"#,
+    )
+    .unwrap();
+    assert!(tera.render("invalid.html", &Context::new()).is_err());
+}
+
+#[test]
+fn renders_trigger_filter() {
+    let mut tera = tera();
+    assert_eq!(
+        render_str(
+            &mut tera,
+            r#"{{ "click" | hx_trigger(filter="ctrlKey", once=true, from="body", throttle=250) }}"#,
+            &Context::new(),
+        ),
+        "click[ctrlKey] once throttle:250ms from:body"
+    );
+    assert_eq!(
+        render_str(
+            &mut tera,
+            r#"<input hx-trigger="{{ "keyup" | hx_trigger(filter='key == "Enter"') }}">"#,
+            &Context::new(),
+        ),
+        r#"<input hx-trigger="keyup[key == &quot;Enter&quot;]">"#
+    );
+}
+
+#[test]
+fn renders_attrs_function() {
+    let mut tera = tera();
+    let mut context = Context::new();
+    context.insert("missing", &None::<String>);
+
+    assert_eq!(
+        render_str(
+            &mut tera,
+            r##"<button{{ hx_attrs(post="/items?a&b", target="#items", push_url=false, confirm='Add "Milk"?', select=missing) }}>"##,
+            &context,
+        ),
+        r##"<button hx-confirm="Add &quot;Milk&quot;?" hx-post="/items?a&amp;b" hx-push-url="false" hx-target="#items">"##
+    );
+}
+
+#[test]
+fn converts_types_into_values() {
+    let mut tera = tera();
+    let mut context = Context::new();
+    context.insert_value(
+        "swap",
+        HxSwap::new(HxReswap::OuterHtml)
+            .swap(Duration::from_millis(500))
+            .into(),
+    );
+    context.insert_value(
+        "trigger",
+        HxTriggerSpec::new("keyup").changed().or("search").into(),
+    );
+    context.insert_value("target", HxTarget::from_static("ul#items").into());
+    context.insert_value("push_url", HxPushUrl::True.into());
+    context.insert_value("attributes", HxAttributes::new().get("/items?a&b").into());
+
+    assert_eq!(
+        render_str(
+            &mut tera,
+            r#"<a hx-swap="{{ swap }}" hx-trigger="{{ trigger }}" hx-target="{{ target }}" hx-push-url="{{ push_url }}"{{ attributes }}>"#,
+            &context,
+        ),
+        r#"<a hx-swap="outerHTML swap:500ms" hx-trigger="keyup changed, search" hx-target="ul#items" hx-push-url="true" hx-get="/items?a&amp;b">"#
+    );
+    assert_eq!(Value::from(HxReswap::InnerHtml).as_str(), Some("innerHTML"));
+}
+
+#[test]
+fn inserts_request_object() {
+    let tera = tera();
+    let render = |renderer: HxRenderer| {
+        let mut context = Context::new();
+        renderer.insert_into(&mut context);
+        tera.render("info.html", &context).unwrap()
+    };
+
+    assert_eq!(
+        render(HxRenderer::new(
+            HxRequestKind::Partial,
+            Some(HxTarget::from_static("ul#items")),
+            Some(HxSource::from_static("button#add")),
+        )),
+        "htmx partial ul#items button#add"
+    );
+    assert_eq!(
+        render(HxRenderer::new(HxRequestKind::Boosted, None, None)),
+        "htmx boosted - -"
+    );
+    assert_eq!(
+        render(HxRenderer::new(HxRequestKind::Page, None, None)),
+        "page - -"
+    );
+}
+
+#[test]
+fn chooses_template_by_naming_convention() {
+    let tera = tera();
+
+    for kind in [
+        HxRequestKind::Page,
+        HxRequestKind::HistoryRestore,
+        HxRequestKind::Boosted,
+        HxRequestKind::Full,
+    ] {
+        let template = HxTemplate::new(&tera, "items.html", context(), kind);
+        assert_eq!(template.template_name(), "items.html", "{kind:?}");
+        assert_eq!(template.render().unwrap(), FULL, "{kind:?}");
+    }
+
+    let template = HxTemplate::new(&tera, "items.html", context(), HxRequestKind::Partial);
+    assert_eq!(template.template_name(), "items.partial.html");
+    assert_eq!(template.render().unwrap(), PARTIAL);
+
+    let template = HxTemplate::new(&tera, "count.html", context(), HxRequestKind::Partial);
+    assert_eq!(template.template_name(), "count.html");
+    assert_eq!(template.render().unwrap(), r#"<span id="count">2</span>"#);
+}
+
+#[test]
+fn renders_explicit_partial() {
+    let tera = tera();
+
+    let template = HxTemplate::new(&tera, "items.html", context(), HxRequestKind::Partial)
+        .with_partial("count.html");
+    assert_eq!(template.render().unwrap(), r#"<span id="count">2</span>"#);
+
+    let template = HxTemplate::new(&tera, "items.html", context(), HxRequestKind::Full)
+        .with_partial("count.html");
+    assert_eq!(template.render().unwrap(), FULL);
+
+    let template = HxTemplate::new(&tera, "items.html", context(), HxRequestKind::Partial)
+        .with_partial("missing.html");
+    assert!(template.render().is_err());
+}
+
+#[test]
+fn into_http_adds_headers() {
+    let tera = tera();
+    let response = HxTemplate::new(&tera, "items.html", context(), HxRequestKind::Partial)
+        .into_http()
+        .unwrap();
+
+    assert_eq!(response.body(), PARTIAL);
+    assert_eq!(response.headers()[CONTENT_TYPE], "text/html; charset=utf-8");
+    response.assert_vary_contains("hx-request-type");
+}
+
+#[tokio::test]
+async fn responds_from_axum() {
+    let app = Router::new()
+        .route(
+            "/items",
+            get(
+                |State(tera): State<Arc<Tera>>, htmx: HxRenderer| async move {
+                    htmx.template(tera, "items.html", context())
+                },
+            ),
+        )
+        .with_state(Arc::new(tera()));
+    let mut client = HtmxTestClient::new(app);
+
+    let response = client.navigate("/items").await;
+    assert_eq!(response.body(), FULL);
+    response.assert_vary_contains("hx-request");
+
+    let response = client
+        .send(HtmxTestRequest::click("/items").with_target(HxTarget::from_static("ul#items")))
+        .await;
+    assert_eq!(response.body(), PARTIAL);
+    response.assert_vary_contains("hx-request-type");
+}
+
+#[test]
+fn responds_with_500_on_render_errors() {
+    use axum::response::IntoResponse;
+
+    let tera = tera();
+    let response = HxTemplate::new(&tera, "items.html", context(), HxRequestKind::Partial)
+        .with_partial("missing.html")
+        .into_response();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn logs_render_errors() {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::response::IntoResponse;
+
+    #[derive(Clone, Default)]
+    struct Logs(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Logs {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let logs = Logs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+
+    let tera = tera();
+    tracing::subscriber::with_default(subscriber, || {
+        HxTemplate::new(&tera, "items.html", context(), HxRequestKind::Partial)
+            .with_partial("missing.html")
+            .into_response()
+    });
+
+    let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+    assert!(logs.contains("ERROR"), "{logs}");
+    assert!(logs.contains("failed to render the template"), "{logs}");
+    assert!(logs.contains("template=items.html"), "{logs}");
+}
@@ -0,0 +1,273 @@
+use htmxtools::{
+    request::{HtmxRequestExt, HxRequestKind, HxSource, HxTarget},
+    response::{
+        HtmxResponseExt, HxLocation, HxPushUrl, HxReswap, HxRetarget, HxTrigger, HxTriggerAfterSwap,
+    },
+    testing::{HtmxResponseAssertions, HtmxTestRequest, HtmxVersion},
+};
+use http::{HeaderMap, HeaderValue, Request, Response, Uri, header::VARY};
+use serde::Deserialize;
+use serde_json::json;
+
+fn names(headers: &HeaderMap) -> Vec<&str> {
+    let mut names: Vec<_> = headers.keys().map(|name| name.as_str()).collect();
+    names.sort_unstable();
+    names
+}
+
+#[test]
+fn presets_are_classified() {
+    let cases = [
+        (HtmxTestRequest::click("/"), HxRequestKind::Partial),
+        (HtmxTestRequest::click("/").full(), HxRequestKind::Full),
+        (HtmxTestRequest::boosted("/"), HxRequestKind::Boosted),
+        (
+            HtmxTestRequest::history_restore("/"),
+            HxRequestKind::HistoryRestore,
+        ),
+        (
+            HtmxTestRequest::submit("/", HxSource::from_static("form")),
+            HxRequestKind::Partial,
+        ),
+    ];
+
+    for (request, kind) in cases {
+        let request: Request<String> = request.build();
+        assert_eq!(request.hx_request_kind(), kind);
+    }
+}
+
+#[test]
+fn v4_headers() {
+    let request = HtmxTestRequest::click("/items?page=2")
+        .with_current_url("http://example.com/items")
+        .with_source(HxSource::from_static("button#more"))
+        .with_target(HxTarget::from_static("ul#items"));
+    let headers = request.headers();
+
+    assert_eq!(
+        names(&headers),
+        [
+            "hx-current-url",
+            "hx-request",
+            "hx-request-type",
+            "hx-source",
+            "hx-target"
+        ]
+    );
+    assert_eq!(headers["hx-current-url"], "http://example.com/items");
+    assert_eq!(headers["hx-request"], "true");
+    assert_eq!(headers["hx-request-type"], "partial");
+    assert_eq!(headers["hx-source"], "button#more");
+    assert_eq!(headers["hx-target"], "ul#items");
+
+    let request: Request<String> = request.build();
+    assert_eq!(request.method(), "GET");
+    assert_eq!(request.uri(), "/items?page=2");
+}
+
+#[test]
+fn v4_boosted_and_history_restore_headers() {
+    let headers = HtmxTestRequest::boosted("/about").headers();
+    assert_eq!(
+        names(&headers),
+        [
+            "hx-boosted",
+            "hx-current-url",
+            "hx-request",
+            "hx-request-type",
+            "hx-source",
+            "hx-target"
+        ]
+    );
+    assert_eq!(headers["hx-request-type"], "full");
+    assert_eq!(headers["hx-target"], "body");
+
+    let headers = HtmxTestRequest::history_restore("/about").headers();
+    assert_eq!(
+        names(&headers),
+        [
+            "hx-current-url",
+            "hx-history-restore-request",
+            "hx-request",
+            "hx-request-type",
+            "hx-target"
+        ]
+    );
+}
+
+#[test]
+fn v2_headers() {
+    let headers = HtmxTestRequest::click("/")
+        .with_version(HtmxVersion::V2)
+        .with_source(HxSource::from_static("button#more"))
+        .with_target(HxTarget::from_static("ul#items"))
+        .headers();
+
+    assert_eq!(
+        names(&headers),
+        ["hx-current-url", "hx-request", "hx-target", "hx-trigger"]
+    );
+    assert_eq!(headers["hx-target"], "items");
+    assert_eq!(headers["hx-trigger"], "more");
+
+    let headers = HtmxTestRequest::click("/")
+        .with_version(HtmxVersion::V2)
+        .headers();
+    assert_eq!(names(&headers), ["hx-current-url", "hx-request"]);
+
+    let headers = HtmxTestRequest::history_restore("/")
+        .with_version(HtmxVersion::V2)
+        .headers();
+    assert_eq!(
+        names(&headers),
+        ["hx-current-url", "hx-history-restore-request", "hx-request"]
+    );
+}
+
+#[test]
+fn form_submit() {
+    let request: Request<String> =
+        HtmxTestRequest::submit("/items", HxSource::from_static("form#new"))
+            .with_form(&[("name", "Milk & Honey"), ("qty", "2")])
+            .build();
+
+    assert_eq!(request.method(), "POST");
+    assert_eq!(
+        request.headers()["content-type"],
+        "application/x-www-form-urlencoded"
+    );
+    assert_eq!(request.headers()["hx-source"], "form#new");
+    assert_eq!(request.headers()["hx-target"], "form#new");
+    assert_eq!(request.body(), "name=Milk+%26+Honey&qty=2");
+}
+
+fn response() -> Response<()> {
+    let mut response = Response::new(());
+    response
+        .hx_trigger(
+            HxTrigger::new()
+                .with_event_data(
+                    "showToast".to_string(),
+                    json!({ "message": "Saved", "level": 1 }),
+                )
+                .with_event("refresh".to_string()),
+        )
+        .hx_trigger_after_swap(HxTriggerAfterSwap::new().with_event("swapped".to_string()))
+        .hx_reswap(HxReswap::OuterHtml)
+        .hx_location(HxLocation::new(Uri::from_static("/items")))
+        .hx_push_url(HxPushUrl::False);
+    response.headers_mut().append(
+        VARY,
+        HeaderValue::from_static("accept-encoding, HX-Request"),
+    );
+    response
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Toast {
+    message: String,
+    level: u8,
+}
+
+#[test]
+fn response_assertions() {
+    let response = response();
+
+    response
+        .assert_triggered("showToast")
+        .assert_triggered("refresh")
+        .assert_not_triggered("closeModal")
+        .assert_triggered_after_swap("swapped")
+        .assert_reswap(HxReswap::OuterHtml)
+        .assert_location(HxLocation::new(Uri::from_static("/items")))
+        .assert_push_url(HxPushUrl::False)
+        .assert_vary_contains("hx-request");
+
+    assert_eq!(
+        response.trigger_payload::<Toast>("showToast"),
+        Toast {
+            message: "Saved".to_string(),
+            level: 1
+        }
+    );
+
+    let (parts, ()) = response.into_parts();
+    parts.assert_triggered("refresh");
+    parts.headers.assert_reswap(HxReswap::OuterHtml);
+}
+
+#[cfg(feature = "axum")]
+#[test]
+fn axum_response_assertions() {
+    use axum::response::IntoResponse;
+
+    (
+        HxReswap::InnerHtml,
+        HxRetarget::from_static("#list"),
+        "<li></li>",
+    )
+        .into_response()
+        .assert_reswap(HxReswap::InnerHtml)
+        .assert_retarget(HxRetarget::from_static("#list"));
+}
+
+#[test]
+#[should_panic(
+    expected = "expected `hx-trigger` to fire `closeModal`\nhtmx response headers:\n  hx-location: "
+)]
+fn assert_triggered_fails() {
+    response().assert_triggered("closeModal");
+}
+
+#[test]
+#[should_panic(
+    expected = "  hx-trigger: refresh, showToast {\"level\":1,\"message\":\"Saved\"}\n  hx-trigger-after-swap: swapped\n"
+)]
+fn failures_show_trigger_events() {
+    response().assert_refresh();
+}
+
+#[test]
+#[should_panic(expected = "expected `hx-reswap` to be InnerHtml, found OuterHtml")]
+fn assert_reswap_fails() {
+    response().assert_reswap(HxReswap::InnerHtml);
+}
+
+#[test]
+#[should_panic(
+    expected = "`hx-retarget` to be HxRetarget(HeaderValueString(\"#list\")), found nothing"
+)]
+fn assert_missing_header_fails() {
+    response().assert_retarget(HxRetarget::from_static("#list"));
+}
+
+#[test]
+#[should_panic(
+    expected = "expected `vary` to contain `hx-target`, found [\"accept-encoding\", \"HX-Request\"]"
+)]
+fn assert_vary_contains_fails() {
+    response().assert_vary_contains("hx-target");
+}
+
+#[test]
+fn vary_any_contains_every_name() {
+    let mut response = Response::new(());
+    response
+        .headers_mut()
+        .append(VARY, HeaderValue::from_static("accept-encoding"));
+    response
+        .headers_mut()
+        .append(VARY, HeaderValue::from_static("*"));
+
+    response
+        .assert_vary_contains("accept-encoding")
+        .assert_vary_contains("hx-target")
+        .assert_vary_contains("hx-request");
+}
+
+#[test]
+#[should_panic(expected = "expected `refresh` in `hx-trigger` to have data")]
+fn trigger_payload_without_data_fails() {
+    response().trigger_payload::<Toast>("refresh");
+}
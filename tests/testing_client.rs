@@ -0,0 +1,237 @@
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use htmxtools::{
+    request::{HxCurrentUrl, HxHistoryRestoreRequest, HxRequest, HxSource, HxTarget},
+    response::{
+        HxLocation, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl, HxTrigger,
+        HxTriggerAfterSettle, HxTriggerAfterSwap,
+    },
+    testing::{HtmxTestClient, HtmxTestRequest, HxEvent, HxEventTiming},
+};
+use http::Uri;
+use serde_json::json;
+
+fn app() -> Router {
+    Router::new()
+        .route(
+            "/login",
+            post(|| async {
+                (
+                    HxTrigger::new().with_event("loggedIn".to_string()),
+                    HxRedirect(Uri::from_static("/dashboard")),
+                )
+            }),
+        )
+        .route(
+            "/dashboard",
+            get(|hx_request: Option<HxRequest>| async move {
+                match hx_request {
+                    Some(_) => "<main>Dashboard</main>",
+                    None => "<html><main>Dashboard</main></html>",
+                }
+            }),
+        )
+        .route(
+            "/items",
+            post(|| async {
+                (
+                    HxTrigger::new()
+                        .with_event_data("showToast".to_string(), json!({ "message": "Saved" })),
+                    HxTriggerAfterSwap::new().with_event("swapped".to_string()),
+                    HxTriggerAfterSettle::new().with_event("settled".to_string()),
+                    HxPushUrl::Uri(Uri::from_static("/items/1")),
+                    "<li>Milk</li>",
+                )
+            }),
+        )
+        .route(
+            "/items/1",
+            get(|| async {
+                (
+                    HxReplaceUrl::Uri(Uri::from_static("/items/1?tab=details")),
+                    "<p>Details</p>",
+                )
+            }),
+        )
+        .route(
+            "/wizard",
+            get(|| async {
+                HxLocation::new(Uri::from_static("/wizard/step-2"))
+                    .with_target("#wizard".to_string())
+            }),
+        )
+        .route(
+            "/wizard/step-2",
+            get(|target: HxTarget, current_url: HxCurrentUrl| async move {
+                format!(
+                    "step 2 into {} from {}",
+                    target.as_str(),
+                    current_url.as_uri()
+                )
+            }),
+        )
+        .route(
+            "/wizard/skip",
+            get(|| async {
+                (
+                    HxLocation::new(Uri::from_static("/wizard/step-2"))
+                        .with_target("#wizard".to_string()),
+                    HxRedirect(Uri::from_static("/dashboard")),
+                    HxRefresh,
+                )
+            }),
+        )
+        .route("/reload", get(|| async { HxRefresh }))
+        .route(
+            "/about",
+            get(|restore: Option<HxHistoryRestoreRequest>| async move {
+                match restore {
+                    Some(_) => "restored about",
+                    None => "about",
+                }
+            }),
+        )
+        .route(
+            "/loop",
+            get(|| async { HxLocation::new(Uri::from_static("/loop")) }),
+        )
+}
+
+#[tokio::test]
+async fn login_redirect_then_swap() {
+    let mut client = HtmxTestClient::new(app()).with_current_url("/login");
+
+    let response = client
+        .send(
+            HtmxTestRequest::submit("/login", HxSource::from_static("form#login"))
+                .with_form(&[("user", "admin")]),
+        )
+        .await;
+
+    assert_eq!(response.body(), "<html><main>Dashboard</main></html>");
+    assert_eq!(client.current_url(), "http://localhost/dashboard");
+    assert_eq!(
+        client.history(),
+        ["http://localhost/login", "http://localhost/dashboard"]
+    );
+    assert!(client.fired("loggedIn"));
+
+    let response = client.send(HtmxTestRequest::click("/dashboard")).await;
+    assert_eq!(response.body(), "<main>Dashboard</main>");
+    assert_eq!(client.history().len(), 2);
+}
+
+#[tokio::test]
+async fn events_and_history() {
+    let mut client = HtmxTestClient::new(app());
+
+    let response = client
+        .send(HtmxTestRequest::submit(
+            "/items",
+            HxSource::from_static("form#new-item"),
+        ))
+        .await;
+
+    assert_eq!(response.body(), "<li>Milk</li>");
+    assert_eq!(
+        client.take_events(),
+        [
+            HxEvent {
+                name: "showToast".to_string(),
+                data: Some(json!({ "message": "Saved" })),
+                timing: HxEventTiming::Received,
+            },
+            HxEvent {
+                name: "swapped".to_string(),
+                data: None,
+                timing: HxEventTiming::AfterSwap,
+            },
+            HxEvent {
+                name: "settled".to_string(),
+                data: None,
+                timing: HxEventTiming::AfterSettle,
+            },
+        ]
+    );
+    assert!(client.events().is_empty());
+    assert_eq!(
+        client.history(),
+        ["http://localhost/", "http://localhost/items/1"]
+    );
+
+    client.send(HtmxTestRequest::click("/items/1")).await;
+    assert_eq!(
+        client.history(),
+        ["http://localhost/", "http://localhost/items/1?tab=details"]
+    );
+}
+
+#[tokio::test]
+async fn follows_location() {
+    let mut client = HtmxTestClient::new(app()).with_current_url("http://example.com/start");
+
+    let response = client.send(HtmxTestRequest::click("/wizard")).await;
+
+    assert_eq!(
+        response.body(),
+        "step 2 into #wizard from http://example.com/start"
+    );
+    assert_eq!(client.current_url(), "http://example.com/wizard/step-2");
+}
+
+#[tokio::test]
+async fn location_wins_over_redirect_and_refresh() {
+    let mut client = HtmxTestClient::new(app()).with_current_url("http://example.com/start");
+
+    let response = client.send(HtmxTestRequest::click("/wizard/skip")).await;
+
+    assert_eq!(
+        response.body(),
+        "step 2 into #wizard from http://example.com/start"
+    );
+    assert_eq!(client.current_url(), "http://example.com/wizard/step-2");
+}
+
+#[tokio::test]
+async fn refresh_reloads_current_page() {
+    let mut client = HtmxTestClient::new(app()).with_current_url("/dashboard");
+
+    let response = client.send(HtmxTestRequest::click("/reload")).await;
+
+    assert_eq!(response.body(), "<html><main>Dashboard</main></html>");
+    assert_eq!(client.history(), ["http://localhost/dashboard"]);
+}
+
+#[tokio::test]
+async fn boosted_navigation_and_back() {
+    let mut client = HtmxTestClient::new(app());
+
+    let response = client.navigate("/about").await;
+    assert_eq!(response.body(), "about");
+
+    client.send(HtmxTestRequest::boosted("/dashboard")).await;
+    assert_eq!(
+        client.history(),
+        [
+            "http://localhost/",
+            "http://localhost/about",
+            "http://localhost/dashboard"
+        ]
+    );
+
+    let response = client.back().await.unwrap();
+    assert_eq!(response.body(), "restored about");
+    assert_eq!(client.current_url(), "http://localhost/about");
+
+    client.back().await.unwrap();
+    assert!(client.back().await.is_none());
+}
+
+#[tokio::test]
+#[should_panic(expected = "followed more than 10 htmx redirects")]
+async fn redirect_loops_panic() {
+    let mut client = HtmxTestClient::new(app());
+    client.send(HtmxTestRequest::click("/loop")).await;
+}
@@ -0,0 +1,147 @@
+use htmxtools::{
+    request::HxTarget,
+    response::{HtmxResponseExt, HxReselect, HxReswap, HxRetarget},
+    testing::HtmxFragmentAssertions,
+};
+use http::Response;
+
+fn response(body: &str) -> Response<String> {
+    Response::new(body.to_string())
+}
+
+#[test]
+fn outer_swap_keeps_target_id() {
+    let mut response = response(r#"<div id="cart"><p>2 items</p></div>"#);
+    response.hx_reswap(HxReswap::OuterHtml);
+
+    response
+        .assert_swaps_into(&HxTarget::from_static("div#cart"))
+        .assert_swaps_into(&HxTarget::from_static("div"))
+        .assert_selects("#cart > p");
+}
+
+#[test]
+#[should_panic(
+    expected = "expected a top-level element with id `cart` for an outer swap, found `p`"
+)]
+fn outer_swap_without_target_id_fails() {
+    let mut response = response("<p>2 items</p>");
+    response.hx_reswap(HxReswap::OuterHtml);
+
+    response.assert_swaps_into(&HxTarget::from_static("div#cart"));
+}
+
+#[test]
+#[should_panic(expected = "for an outer swap, found `section#cart`")]
+fn outer_swap_uses_retarget_id() {
+    let mut response = response(r#"<section id="cart"></section>"#);
+    response
+        .hx_reswap(HxReswap::OuterMorph)
+        .hx_retarget(HxRetarget::from_static("#summary"));
+
+    response.assert_swaps_into(&HxTarget::from_static("section#cart"));
+}
+
+#[test]
+fn inner_swap_does_not_need_target_id() {
+    response("<p>2 items</p>").assert_swaps_into(&HxTarget::from_static("div#cart"));
+}
+
+#[test]
+fn reselect_narrows_the_swapped_content() {
+    let mut response = response(
+        r#"<!DOCTYPE html><html><body><header></header><main><div id="cart"></div></main></body></html>"#,
+    );
+    response
+        .hx_reswap(HxReswap::OuterHtml)
+        .hx_reselect(HxReselect::from_static("main > div"));
+
+    response
+        .assert_reselect_matches()
+        .assert_swaps_into(&HxTarget::from_static("div#cart"));
+}
+
+#[test]
+fn document_top_level_is_body_children() {
+    let mut response = response(r#"<html><body><div id="cart"></div></body></html>"#);
+    response.hx_reswap(HxReswap::OuterHtml);
+
+    response.assert_swaps_into(&HxTarget::from_static("div#cart"));
+}
+
+#[test]
+#[should_panic(
+    expected = "expected `hx-reselect` selector `#missing` to match an element of the body"
+)]
+fn reselect_without_match_fails() {
+    let mut response = response("<p></p>");
+    response.hx_reselect(HxReselect::from_static("#missing"));
+
+    response.assert_reselect_matches();
+}
+
+#[test]
+#[should_panic(expected = "expected an `hx-reselect` header")]
+fn reselect_without_header_fails() {
+    response("<p></p>").assert_reselect_matches();
+}
+
+#[test]
+#[should_panic(expected = "found `<li>` without one")]
+fn upsert_needs_ids() {
+    let mut response = response(r#"<li id="a"></li><li></li>"#);
+    response.hx_reswap(HxReswap::Upsert);
+
+    response.assert_swaps_into(&HxTarget::from_static("ul#items"));
+}
+
+#[test]
+fn oob_markers() {
+    response(
+        r#"<p>main</p>
+        <span id="count" hx-swap-oob="true">3</span>
+        <li hx-swap-oob="beforeend:#items">new</li>
+        <div id="flash" hx-swap-oob="outerHTML"></div>"#,
+    )
+    .assert_oob_valid()
+    .assert_oob("count")
+    .assert_oob("flash");
+}
+
+#[test]
+#[should_panic(
+    expected = "expected `hx-swap-oob` of `<span>` to be `true` or a swap strategy, found `outer`"
+)]
+fn oob_with_invalid_strategy_fails() {
+    response(r#"<span id="count" hx-swap-oob="outer">3</span>"#).assert_oob_valid();
+}
+
+#[test]
+#[should_panic(expected = "expected `<span>` marked with `hx-swap-oob=\"true\"` to have an id")]
+fn oob_without_id_fails() {
+    response(r#"<span hx-swap-oob="true">3</span>"#)
+        .assert_swaps_into(&HxTarget::from_static("div"));
+}
+
+#[test]
+#[should_panic(expected = "expected an element with id `count` marked with `hx-swap-oob`")]
+fn missing_oob_fails() {
+    response(r#"<span id="count">3</span>"#).assert_oob("count");
+}
+
+#[test]
+#[should_panic(
+    expected = "expected `ul li` to match an element of the body\nbody:\n<p></p>\nhtmx response headers:\n  hx-reswap: InnerHtml\n"
+)]
+fn failures_show_body_and_headers() {
+    let mut response = response("<p></p>");
+    response.hx_reswap(HxReswap::InnerHtml);
+
+    response.assert_selects("ul li");
+}
+
+#[test]
+#[should_panic(expected = "expected `ul >` to be a valid selector")]
+fn invalid_selector_fails() {
+    response("<p></p>").assert_selects("ul >");
+}
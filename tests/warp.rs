@@ -0,0 +1,203 @@
+use htmxtools::{
+    HxAutoVaryLayer,
+    request::{HxRequest, HxRequestKind, HxTarget},
+    response::{HxRetarget, HxTrigger},
+    warp as htmx,
+};
+use http::{HeaderMap, Request, StatusCode, header::VARY};
+use tower_layer::Layer;
+use tower_service::Service;
+use warp::{Filter, Rejection, Reply};
+
+fn routes()
+-> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone + Send + Sync + 'static {
+    let target = warp::path("target")
+        .and(htmx::target())
+        .map(|hx_target: HxTarget| hx_target.as_str().to_string());
+    let optional = warp::path("optional")
+        .and(htmx::optional::<HxTarget>())
+        .map(|hx_target: Option<HxTarget>| {
+            hx_target.map_or_else(
+                || "missing".to_string(),
+                |hx_target| hx_target.as_str().to_string(),
+            )
+        });
+    let request = warp::path("request")
+        .and(htmx::request())
+        .map(|_: HxRequest| "htmx".to_string());
+    let kind = warp::path("kind")
+        .and(htmx::kind())
+        .map(|hx_request_kind: HxRequestKind| format!("{hx_request_kind:?}"));
+    let declared = warp::path("declared")
+        .and(htmx::target())
+        .map(|_: HxTarget| warp::reply::with_header("declared", VARY, "HX-Target"));
+    let any = warp::path("any")
+        .and(htmx::target())
+        .map(|_: HxTarget| warp::reply::with_header("any", VARY, "*"));
+    let trigger = warp::path("trigger")
+        .map(|| htmx::with_header("loaded", HxTrigger::new().with_event("loaded".to_string())));
+    let retarget = warp::path("retarget").map(|| HxRetarget::from_static("#errors"));
+
+    target
+        .map(Reply::into_response)
+        .or(optional.map(Reply::into_response))
+        .unify()
+        .or(request.map(Reply::into_response))
+        .unify()
+        .or(kind.map(Reply::into_response))
+        .unify()
+        .or(declared.map(Reply::into_response))
+        .unify()
+        .or(any.map(Reply::into_response))
+        .unify()
+        .or(trigger.map(Reply::into_response))
+        .unify()
+        .or(retarget.map(Reply::into_response))
+        .unify()
+        .with(warp::wrap_fn(htmx::vary))
+}
+
+/// Calls the routes served with `warp::serve`, without an auto vary set.
+async fn call(
+    path: &str,
+    headers: &[(&'static str, &'static str)],
+) -> http::Response<bytes::Bytes> {
+    let mut request = warp::test::request().path(path);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    request.reply(&routes()).await
+}
+
+/// Calls the routes served with `warp::service` wrapped in the `HxAutoVaryLayer`.
+async fn call_with_layer(path: &str, headers: &[(&'static str, &'static str)]) -> HeaderMap {
+    let mut service = HxAutoVaryLayer::new().layer(warp::service(routes()));
+
+    let mut request = Request::builder().uri(path);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let response = service
+        .call(request.body(String::new()).unwrap())
+        .await
+        .unwrap();
+    response.headers().clone()
+}
+
+fn vary(headers: &HeaderMap) -> Vec<&str> {
+    let mut names: Vec<_> = headers
+        .get_all(VARY)
+        .iter()
+        .map(|value| value.to_str().unwrap())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+const ALL: [&str; 7] = [
+    "hx-boosted",
+    "hx-current-url",
+    "hx-history-restore-request",
+    "hx-request",
+    "hx-request-type",
+    "hx-source",
+    "hx-target",
+];
+
+#[tokio::test]
+async fn extracts_headers() {
+    let response = call("/target", &[("hx-target", "div#items")]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.body(), "div#items");
+
+    let response = call("/optional", &[]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.body(), "missing");
+
+    let response = call("/kind", &[("hx-request", "true"), ("hx-boosted", "true")]).await;
+    assert_eq!(response.body(), "Boosted");
+}
+
+#[tokio::test]
+async fn rejects_missing_and_invalid_headers() {
+    let response = call("/target", &[]).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = call("/request", &[("hx-request", "false")]).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let headers = call_with_layer("/target", &[]).await;
+    assert_eq!(vary(&headers), ["hx-target"]);
+}
+
+#[tokio::test]
+async fn varies_by_consumed_headers_only() {
+    let headers = [("hx-request", "true"), ("hx-target", "div#items")];
+
+    let response = call_with_layer("/target", &headers).await;
+    assert_eq!(vary(&response), ["hx-target"]);
+
+    let response = call_with_layer("/optional", &headers).await;
+    assert_eq!(vary(&response), ["hx-target"]);
+
+    let response = call_with_layer("/kind", &headers).await;
+    assert_eq!(
+        vary(&response),
+        [
+            "hx-boosted",
+            "hx-history-restore-request",
+            "hx-request",
+            "hx-request-type"
+        ]
+    );
+
+    let response = call_with_layer("/kind", &[]).await;
+    assert_eq!(vary(&response), ["hx-request"]);
+
+    let response = call_with_layer("/trigger", &headers).await;
+    assert!(vary(&response).is_empty());
+}
+
+#[tokio::test]
+async fn varies_by_every_header_without_layer() {
+    let response = call("/target", &[("hx-target", "div#items")]).await;
+    assert_eq!(vary(response.headers()), ALL);
+
+    let response = call("/trigger", &[]).await;
+    assert_eq!(vary(response.headers()), ALL);
+}
+
+#[tokio::test]
+async fn keeps_declared_vary() {
+    let headers = [("hx-target", "div#items")];
+
+    let response = call_with_layer("/declared", &headers).await;
+    assert_eq!(vary(&response), ["HX-Target"]);
+
+    let response = call_with_layer("/any", &headers).await;
+    assert_eq!(vary(&response), ["*"]);
+
+    let response = call("/declared", &headers).await;
+    let mut expected: Vec<_> = ALL
+        .iter()
+        .filter(|name| **name != "hx-target")
+        .copied()
+        .collect();
+    expected.push("HX-Target");
+    expected.sort_unstable();
+    assert_eq!(vary(response.headers()), expected);
+
+    let response = call("/any", &headers).await;
+    assert_eq!(vary(response.headers()), ["*"]);
+}
+
+#[tokio::test]
+async fn sets_response_headers() {
+    let response = call("/trigger", &[]).await;
+    assert_eq!(response.headers()["hx-trigger"], "loaded");
+    assert_eq!(response.body(), "loaded");
+
+    let response = call("/retarget", &[]).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["hx-retarget"], "#errors");
+}